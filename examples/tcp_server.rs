@@ -9,6 +9,8 @@ use std::str::from_utf8;
 
 // Setup some tokens to allow us to identify which event is for which socket.
 const SERVER: Token = Token(0);
+#[cfg(unix)]
+const RESERVE: Token = Token(1);
 
 // Some data we'll send over the connection.
 const DATA: &[u8] = b"Hello world!\n";
@@ -30,10 +32,22 @@ fn main() -> io::Result<()> {
     poll.registry()
         .register(&mut server, SERVER, Interest::READABLE)?;
 
+    // Reserved file descriptor to handle running out of file descriptors.
+    // It reports an event if it can't reserve a descriptor anymore, or if it
+    // reserved one again.
+    #[cfg(unix)]
+    let mut reserve = mio::net::FdReserve::new()?;
+    #[cfg(unix)]
+    poll.registry()
+        .register(&mut reserve, RESERVE, Interest::READABLE)?;
+    // Whether the server is registered.
+    #[cfg(unix)]
+    let mut accepting = true;
+
     // Map of `Token` -> `TcpStream`.
     let mut connections = HashMap::new();
     // Unique token for each incoming connection.
-    let mut unique_token = Token(SERVER.0 + 1);
+    let mut unique_token = Token(SERVER.0 + 2);
 
     println!("You can connect to the server using `nc`:");
     println!(" $ nc 127.0.0.1 9000");
@@ -47,7 +61,11 @@ fn main() -> io::Result<()> {
                 SERVER => loop {
                     // Received an event for the TCP server socket, which
                     // indicates we can accept an connection.
-                    let (mut connection, address) = match server.accept() {
+                    #[cfg(unix)]
+                    let res = server.accept_reserved(&mut reserve);
+                    #[cfg(not(unix))]
+                    let res = server.accept();
+                    let (mut connection, address) = match res {
                         Ok((connection, address)) => (connection, address),
                         Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
                             // If we get a `WouldBlock` error we know our
@@ -56,6 +74,23 @@ fn main() -> io::Result<()> {
                             // more.
                             break;
                         }
+                        #[cfg(unix)]
+                        Err(e)
+                            if e.raw_os_error() == Some(libc::EMFILE)
+                                || e.raw_os_error() == Some(libc::ENFILE) =>
+                        {
+                            println!("Failed to accept connection: {}", e);
+                            if !reserve.is_reserved() {
+                                // We ran out of file descriptors and couldn't
+                                // close the pending connection, stop accepting
+                                // until the event of the reserve.
+                                break;
+                            }
+                            // We ran out of file descriptors, the pending
+                            // connection is closed by `accept_reserved`. Try
+                            // the next one.
+                            continue;
+                        }
                         Err(e) => {
                             // If it was any other kind of error, something went
                             // wrong and we terminate with an error.
@@ -74,6 +109,26 @@ fn main() -> io::Result<()> {
 
                     connections.insert(token, connection);
                 },
+                #[cfg(unix)]
+                RESERVE => {
+                    if reserve.is_reserved() == accepting {
+                        // Lost and reserved a file descriptor again before
+                        // we got the event.
+                    } else if reserve.is_reserved() {
+                        // File descriptors were released, start accepting
+                        // connections again. Registering reports the
+                        // connections that are still queued.
+                        println!("Resuming accepting connections");
+                        poll.registry()
+                            .register(&mut server, SERVER, Interest::READABLE)?;
+                        accepting = true;
+                    } else {
+                        // Back off until connections are closed.
+                        println!("Out of file descriptors, pausing accepting connections");
+                        poll.registry().deregister(&mut server)?;
+                        accepting = false;
+                    }
+                }
                 token => {
                     // Maybe received an event for a TCP connection.
                     let done = if let Some(connection) = connections.get_mut(&token) {
@@ -86,6 +141,10 @@ fn main() -> io::Result<()> {
                         if let Some(mut connection) = connections.remove(&token) {
                             poll.registry().deregister(&mut connection)?;
                         }
+                        // Closing the connection released a file descriptor,
+                        // reserve it if we ran out.
+                        #[cfg(unix)]
+                        reserve.reserve();
                     }
                 }
             }
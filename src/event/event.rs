@@ -183,6 +183,11 @@ impl Event {
         sys::event::is_lio(&self.inner)
     }
 
+    /// Create an `Event` from a platform specific event.
+    pub(crate) fn from_sys_event(sys_event: sys::Event) -> Event {
+        Event { inner: sys_event }
+    }
+
     /// Create a reference to an `Event` from a platform specific event.
     pub(crate) fn from_sys_event_ref(sys_event: &sys::Event) -> &Event {
         unsafe {
@@ -1,7 +1,13 @@
 use crate::event::Event;
-use crate::sys;
+#[cfg(all(
+    feature = "readiness-cache",
+    any(feature = "net", all(unix, feature = "os-ext"))
+))]
+use crate::Interest;
+use crate::{sys, Token};
 
-use std::fmt;
+use std::collections::HashMap;
+use std::{fmt, vec};
 
 /// A collection of readiness events.
 ///
@@ -41,6 +47,8 @@ use std::fmt;
 /// ```
 pub struct Events {
     inner: sys::Events,
+    /// Whether the last call to `poll` returned because its timeout elapsed.
+    timed_out: bool,
 }
 
 /// [`Events`] iterator.
@@ -92,6 +100,7 @@ impl Events {
     pub fn with_capacity(capacity: usize) -> Events {
         Events {
             inner: sys::Events::with_capacity(capacity),
+            timed_out: false,
         }
     }
 
@@ -183,12 +192,247 @@ impl Events {
     /// ```
     pub fn clear(&mut self) {
         self.inner.clear();
+        self.timed_out = false;
+    }
+
+    /// Returns the number of events.
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns `true` if `self` holds as many events as its [`capacity`].
+    ///
+    /// If this returns `true` after a call to [`Poll::poll`] more events may
+    /// be ready, which will be returned by the next call.
+    ///
+    /// [`capacity`]: Events::capacity
+    /// [`Poll::poll`]: ../struct.Poll.html#method.poll
+    pub fn is_full(&self) -> bool {
+        self.len() >= self.capacity()
+    }
+
+    /// Returns `true` if the last call to [`Poll::poll`] (or
+    /// [`Poll::poll_until`]) returned because its timeout elapsed.
+    ///
+    /// An empty `Events` doesn't imply the timeout elapsed, `poll` may return
+    /// early without events, e.g. due to a spurious wakeup. If `poll` was
+    /// interrupted it returns an error of kind [`Interrupted`] and this
+    /// returns `false`.
+    ///
+    /// [`Poll::poll`]: ../struct.Poll.html#method.poll
+    /// [`Poll::poll_until`]: ../struct.Poll.html#method.poll_until
+    /// [`Interrupted`]: std::io::ErrorKind::Interrupted
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(feature = "os-poll", doc = "```")]
+    #[cfg_attr(not(feature = "os-poll"), doc = "```ignore")]
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use mio::{Events, Poll};
+    /// use std::time::Duration;
+    ///
+    /// let mut events = Events::with_capacity(1024);
+    /// let mut poll = Poll::new()?;
+    ///
+    /// // Nothing is registered, so this can only time out (or wake up
+    /// // spuriously).
+    /// poll.poll(&mut events, Some(Duration::from_millis(10)))?;
+    /// if events.timed_out() {
+    ///     println!("Timed out");
+    /// }
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn timed_out(&self) -> bool {
+        self.timed_out
+    }
+
+    /// Merge all events for the same [`Token`] into a single event, combining
+    /// their readiness.
+    ///
+    /// The merged event takes the place of the first event for the token, the
+    /// order of the events is otherwise unchanged.
+    ///
+    /// # Notes
+    ///
+    /// On platforms using kqueue, and on WASI, an event only describes a
+    /// single kind of readiness, e.g. either readable or writable. There only
+    /// consecutive events of the same kind (for the same token) are merged, so
+    /// a token can still have multiple events.
+    ///
+    /// [`Token`]: ../struct.Token.html
+    pub fn coalesce(&mut self) {
+        let events = self.events_mut();
+        // Index of the last kept event for each token.
+        let mut last: HashMap<Token, usize> = HashMap::with_capacity(events.len());
+        let mut kept = 0;
+        for i in 0..events.len() {
+            let token = sys::event::token(&events[i]);
+            if let Some(&index) = last.get(&token) {
+                let (head, tail) = events.split_at_mut(i);
+                if sys::event::merge(&mut head[index], &tail[0]) {
+                    continue;
+                }
+            }
+            events.swap(kept, i);
+            let _ = last.insert(token, kept);
+            kept += 1;
+        }
+        events.truncate(kept);
+    }
+
+    /// Sort the events by the key returned by `f`, e.g. a priority.
+    ///
+    /// The sort is stable, events with the same key keep their order.
+    ///
+    /// # Examples
+    ///
+    /// Handle the events for a control socket before all other events.
+    ///
+    #[cfg_attr(feature = "os-poll", doc = "```")]
+    #[cfg_attr(not(feature = "os-poll"), doc = "```ignore")]
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use mio::{Events, Poll, Token};
+    /// use std::time::Duration;
+    ///
+    /// const CONTROL: Token = Token(0);
+    ///
+    /// let mut events = Events::with_capacity(1024);
+    /// let mut poll = Poll::new()?;
+    ///
+    /// // Register handles with `poll`.
+    ///
+    /// poll.poll(&mut events, Some(Duration::from_millis(100)))?;
+    ///
+    /// // `false` sorts before `true`.
+    /// events.sort_by_key(|event| event.token() != CONTROL);
+    /// for event in events.iter() {
+    ///     println!("Got an event for {:?}", event.token());
+    /// }
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn sort_by_key<K, F>(&mut self, mut f: F)
+    where
+        K: Ord,
+        F: FnMut(&Event) -> K,
+    {
+        self.events_mut()
+            .sort_by_key(|event| f(Event::from_sys_event_ref(event)));
+    }
+
+    /// Retain only the events for which `f` returns `true`, removing all
+    /// other events.
+    ///
+    /// The order of the retained events is unchanged.
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&Event) -> bool,
+    {
+        self.events_mut()
+            .retain(|event| f(Event::from_sys_event_ref(event)));
+    }
+
+    /// Remove all events, returning them in an iterator.
+    ///
+    /// If the iterator is dropped before it's fully consumed the remaining
+    /// events are removed as well.
+    pub fn drain(&mut self) -> Drain<'_> {
+        Drain {
+            inner: self.events_mut().drain(..),
+        }
+    }
+
+    pub(crate) fn set_timed_out(&mut self, timed_out: bool) {
+        self.timed_out = timed_out;
     }
 
     /// Returns the inner `sys::Events`.
     pub(crate) fn sys(&mut self) -> &mut sys::Events {
         &mut self.inner
     }
+
+    /// Add an event for `token` with `readiness` (see `sys::mem`). Returns
+    /// `false`, without adding anything, if there's no capacity left for the
+    /// event.
+    #[cfg(any(
+        feature = "os-poll",
+        all(
+            feature = "readiness-cache",
+            any(feature = "net", all(unix, feature = "os-ext"))
+        )
+    ))]
+    pub(crate) fn push(&mut self, token: Token, readiness: u8) -> bool {
+        sys::event::push(self.events_mut(), token, readiness)
+    }
+
+    /// Add an event for `token` with the readiness of `interest`, see `push`.
+    #[cfg(all(
+        feature = "readiness-cache",
+        any(feature = "net", all(unix, feature = "os-ext"))
+    ))]
+    pub(crate) fn push_interest(&mut self, token: Token, interest: Interest) -> bool {
+        let mut readiness = 0;
+        if interest.is_readable() {
+            readiness |= sys::mem::READABLE;
+        }
+        if interest.is_writable() {
+            readiness |= sys::mem::WRITABLE;
+        }
+        self.push(token, readiness)
+    }
+
+    /// Returns the events as a `Vec`.
+    #[cfg(not(all(windows, feature = "os-poll")))]
+    fn events_mut(&mut self) -> &mut Vec<sys::Event> {
+        &mut self.inner
+    }
+
+    /// Returns the events as a `Vec`.
+    #[cfg(all(windows, feature = "os-poll"))]
+    fn events_mut(&mut self) -> &mut Vec<sys::Event> {
+        &mut self.inner.events
+    }
+}
+
+/// Owning [`Events`] iterator.
+///
+/// This struct is created by the [`drain`] method on [`Events`].
+///
+/// [`Events`]: struct.Events.html
+/// [`drain`]: struct.Events.html#method.drain
+pub struct Drain<'a> {
+    inner: vec::Drain<'a, sys::Event>,
+}
+
+impl<'a> Iterator for Drain<'a> {
+    type Item = Event;
+
+    fn next(&mut self) -> Option<Event> {
+        self.inner.next().map(Event::from_sys_event)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a> DoubleEndedIterator for Drain<'a> {
+    fn next_back(&mut self) -> Option<Event> {
+        self.inner.next_back().map(Event::from_sys_event)
+    }
+}
+
+impl<'a> ExactSizeIterator for Drain<'a> {}
+
+impl<'a> fmt::Debug for Drain<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.inner.as_slice().iter().map(Event::from_sys_event_ref))
+            .finish()
+    }
 }
 
 impl<'a> IntoIterator for &'a Events {
@@ -6,5 +6,5 @@ mod events;
 mod source;
 
 pub use self::event::Event;
-pub use self::events::{Events, Iter};
+pub use self::events::{Drain, Events, Iter};
 pub use self::source::Source;
@@ -109,6 +109,29 @@ pub trait Source {
     ///
     /// [`Registry::deregister`]: ../struct.Registry.html#method.deregister
     fn deregister(&mut self, registry: &Registry) -> io::Result<()>;
+
+    /// Move `self` from the `from` `Registry` instance to the `to` `Registry`
+    /// instance.
+    ///
+    /// This function should not be called directly. Use
+    /// [`Registry::transfer`] instead. Implementors should handle transfers by
+    /// delegating the call to another `Source` type.
+    ///
+    /// The default implementation deregisters `self` from `from` and then
+    /// registers it with `to`. Mio's own types override it to register with
+    /// `to` first, so the source is never left unregistered.
+    ///
+    /// [`Registry::transfer`]: ../struct.Registry.html#method.transfer
+    fn transfer(
+        &mut self,
+        from: &Registry,
+        to: &Registry,
+        token: Token,
+        interests: Interest,
+    ) -> io::Result<()> {
+        self.deregister(from)?;
+        self.register(to, token, interests)
+    }
 }
 
 impl<T> Source for Box<T>
@@ -136,4 +159,14 @@ where
     fn deregister(&mut self, registry: &Registry) -> io::Result<()> {
         (**self).deregister(registry)
     }
+
+    fn transfer(
+        &mut self,
+        from: &Registry,
+        to: &Registry,
+        token: Token,
+        interests: Interest,
+    ) -> io::Result<()> {
+        (**self).transfer(from, to, token, interests)
+    }
 }
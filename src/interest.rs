@@ -24,6 +24,8 @@ const AIO: u8 = 0b0100;
 const LIO: u8 = 0b1000;
 const PRIORITY: u8 = 0b10000;
 
+// `NonZeroU8::new(..).unwrap()` can't be used in a `const` context on our MSRV.
+#[allow(clippy::useless_nonzero_new_unchecked)]
 impl Interest {
     /// Returns a `Interest` set representing readable interests.
     pub const READABLE: Interest = Interest(unsafe { NonZeroU8::new_unchecked(READABLE) });
@@ -167,7 +169,7 @@ impl fmt::Debug for Interest {
                 one = true
             }
         }
-        #[cfg(any(target_os = "freebsd"))]
+        #[cfg(target_os = "freebsd")]
         {
             if self.is_lio() {
                 if one {
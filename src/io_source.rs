@@ -1,3 +1,10 @@
+#[cfg(any(
+    all(debug_assertions, feature = "drain-check"),
+    feature = "readiness-cache"
+))]
+use std::collections::HashMap;
+#[cfg(feature = "readiness-cache")]
+use std::collections::{HashSet, VecDeque};
 use std::ops::{Deref, DerefMut};
 #[cfg(unix)]
 use std::os::unix::io::AsRawFd;
@@ -5,11 +12,41 @@ use std::os::unix::io::AsRawFd;
 use std::os::wasi::io::AsRawFd;
 #[cfg(windows)]
 use std::os::windows::io::AsRawSocket;
-#[cfg(debug_assertions)]
+#[cfg(any(
+    all(debug_assertions, feature = "drain-check"),
+    feature = "readiness-cache"
+))]
+use std::sync::atomic::AtomicBool;
+#[cfg(feature = "readiness-cache")]
+use std::sync::atomic::AtomicU8;
+// Also used by `SelectorId`.
+#[cfg(any(debug_assertions, feature = "readiness-cache"))]
 use std::sync::atomic::{AtomicUsize, Ordering};
+#[cfg(any(
+    all(debug_assertions, feature = "drain-check"),
+    feature = "readiness-cache"
+))]
+use std::sync::{Arc, PoisonError, RwLock, Weak};
+#[cfg(any(
+    all(debug_assertions, feature = "drain-check"),
+    feature = "readiness-cache"
+))]
+use std::sync::{Mutex, MutexGuard};
+#[cfg(feature = "readiness-cache")]
+use std::time::Duration;
 use std::{fmt, io};
 
+#[cfg(all(debug_assertions, feature = "drain-check"))]
+use log::warn;
+
+#[cfg(all(unix, debug_assertions))]
+use crate::poll::Operation;
 use crate::sys::IoSourceState;
+#[cfg(any(
+    all(debug_assertions, feature = "drain-check"),
+    feature = "readiness-cache"
+))]
+use crate::Events;
 use crate::{event, Interest, Registry, Token};
 
 /// Adapter for a [`RawFd`] or [`RawSocket`] providing an [`event::Source`]
@@ -30,8 +67,21 @@ use crate::{event, Interest, Registry, Token};
 /// as `read`, `write`, etc.) must go through the [`do_io`] method to ensure the
 /// internal state is updated accordingly.
 ///
+/// With the `readiness-cache` feature enabled `IoSource` remembers the
+/// readiness of the last event received for it, see `IoSource::readiness`.
+/// The readiness is cleared once an I/O operation returns a [`WouldBlock`]
+/// error.
+///
+/// With the `drain-check` feature enabled, in debug builds, `IoSource` logs a
+/// warning when it received a readable event and was read from, but not
+/// drained before the next poll. The source is drained once a read returns a
+/// [`WouldBlock`] error, reaches the end of the stream (`Ok(0)`) or returns
+/// less bytes than requested. With edge-triggered readiness this can result in
+/// a stalled source.
+///
 /// [`Poll`]: crate::Poll
 /// [`do_io`]: IoSource::do_io
+/// [`WouldBlock`]: io::ErrorKind::WouldBlock
 /*
 ///
 /// # Examples
@@ -62,6 +112,12 @@ use crate::{event, Interest, Registry, Token};
 pub struct IoSource<T> {
     state: IoSourceState,
     inner: T,
+    /// State shared with the `Registry` the source is registered with.
+    #[cfg(any(
+        all(debug_assertions, feature = "drain-check"),
+        feature = "readiness-cache"
+    ))]
+    shared: Shared,
     #[cfg(debug_assertions)]
     selector_id: SelectorId,
 }
@@ -72,6 +128,11 @@ impl<T> IoSource<T> {
         IoSource {
             state: IoSourceState::new(),
             inner: io,
+            #[cfg(any(
+                all(debug_assertions, feature = "drain-check"),
+                feature = "readiness-cache"
+            ))]
+            shared: Shared(Arc::new(SharedState::new())),
             #[cfg(debug_assertions)]
             selector_id: SelectorId::new(),
         }
@@ -86,12 +147,108 @@ impl<T> IoSource<T> {
     /// ensure the user will receive events once the socket is ready again after
     /// returning a [`WouldBlock`] error.
     ///
+    /// As the direction of the I/O operation is unknown it doesn't clear the
+    /// cached readiness, use [`do_io_for`] where possible.
+    ///
     /// [`WouldBlock`]: io::ErrorKind::WouldBlock
+    /// [`do_io_for`]: IoSource::do_io_for
+    #[cfg_attr(not(feature = "net"), allow(dead_code))]
     pub fn do_io<F, R>(&self, f: F) -> io::Result<R>
     where
         F: FnOnce(&T) -> io::Result<R>,
     {
-        self.state.do_io(f, &self.inner)
+        let res = self.state.do_io(f, &self.inner);
+        #[cfg(any(
+            all(debug_assertions, feature = "drain-check"),
+            feature = "readiness-cache"
+        ))]
+        self.shared.did_io(None, &res, false);
+        res
+    }
+
+    /// Same as [`do_io`], but for an I/O operation in the direction of
+    /// `interest`, only clearing the readiness for `interest` if the I/O
+    /// operation returns a [`WouldBlock`] error.
+    ///
+    /// For example, a read operation returning [`WouldBlock`] doesn't say
+    /// anything about the writability of the source, so it should use
+    /// `Interest::READABLE`.
+    ///
+    /// [`do_io`]: IoSource::do_io
+    /// [`WouldBlock`]: io::ErrorKind::WouldBlock
+    #[cfg_attr(
+        not(any(
+            all(debug_assertions, feature = "drain-check"),
+            feature = "readiness-cache"
+        )),
+        allow(unused_variables)
+    )]
+    pub fn do_io_for<F, R>(&self, interest: Interest, f: F) -> io::Result<R>
+    where
+        F: FnOnce(&T) -> io::Result<R>,
+    {
+        let res = self.state.do_io(f, &self.inner);
+        #[cfg(any(
+            all(debug_assertions, feature = "drain-check"),
+            feature = "readiness-cache"
+        ))]
+        self.shared.did_io(Some(interest), &res, false);
+        res
+    }
+
+    /// Same as [`do_io_for`] with `Interest::READABLE`, for a read of at most
+    /// `len` bytes from a stream.
+    ///
+    /// A read returning `Ok(0)` (end of the stream) or less than `len` bytes
+    /// drained the source, same as a [`WouldBlock`] error. Once the reading
+    /// side is closed only `Ok(0)` does, as buffered data doesn't trigger a
+    /// new event.
+    ///
+    /// [`do_io_for`]: IoSource::do_io_for
+    /// [`WouldBlock`]: io::ErrorKind::WouldBlock
+    #[cfg_attr(
+        not(any(
+            all(debug_assertions, feature = "drain-check"),
+            feature = "readiness-cache"
+        )),
+        allow(unused_variables)
+    )]
+    pub(crate) fn do_read<F>(&self, len: usize, f: F) -> io::Result<usize>
+    where
+        F: FnOnce(&T) -> io::Result<usize>,
+    {
+        let res = self.state.do_io(f, &self.inner);
+        #[cfg(any(
+            all(debug_assertions, feature = "drain-check"),
+            feature = "readiness-cache"
+        ))]
+        {
+            let drained = match res {
+                // End of the stream.
+                Ok(0) => len != 0,
+                // Once the reading side is closed no new event is received,
+                // so a short read doesn't mean the source is drained.
+                Ok(n) => n < len && !self.shared.read_closed.load(Ordering::Relaxed),
+                Err(_) => false,
+            };
+            self.shared.did_io(Some(Interest::READABLE), &res, drained);
+        }
+        res
+    }
+
+    /// Returns the readiness of the last event received for this source, or
+    /// `None` if it isn't known to be ready.
+    ///
+    /// The readiness is cleared when an I/O operation returns a
+    /// [`WouldBlock`] error and when the source is (re)registered or
+    /// deregistered. An I/O operation for an interest not included in the
+    /// returned readiness is likely to block, but a readiness is not a
+    /// guarantee that the I/O operation succeeds.
+    ///
+    /// [`WouldBlock`]: io::ErrorKind::WouldBlock
+    #[cfg(all(feature = "readiness-cache", feature = "net"))]
+    pub fn readiness(&self) -> Option<Interest> {
+        self.shared.readiness()
     }
 
     /// Returns the I/O source, dropping the state.
@@ -140,11 +297,18 @@ where
         token: Token,
         interests: Interest,
     ) -> io::Result<()> {
+        let fd = self.inner.as_raw_fd();
         #[cfg(debug_assertions)]
-        self.selector_id.associate(registry)?;
-        registry
-            .selector()
-            .register(self.inner.as_raw_fd(), token, interests)
+        self.selector_id.associate(registry).map_err(|err| {
+            registry.registration_error(Operation::Register, fd, Some(token), Some(interests), err)
+        })?;
+        registry.register_fd(fd, token, interests)?;
+        #[cfg(any(
+            all(debug_assertions, feature = "drain-check"),
+            feature = "readiness-cache"
+        ))]
+        registry.sources().add(token, &self.shared);
+        Ok(())
     }
 
     fn reregister(
@@ -153,17 +317,67 @@ where
         token: Token,
         interests: Interest,
     ) -> io::Result<()> {
+        let fd = self.inner.as_raw_fd();
         #[cfg(debug_assertions)]
-        self.selector_id.check_association(registry)?;
-        registry
-            .selector()
-            .reregister(self.inner.as_raw_fd(), token, interests)
+        self.selector_id
+            .check_association(registry)
+            .map_err(|err| {
+                registry.registration_error(
+                    Operation::Reregister,
+                    fd,
+                    Some(token),
+                    Some(interests),
+                    err,
+                )
+            })?;
+        registry.reregister_fd(fd, token, interests)?;
+        #[cfg(any(
+            all(debug_assertions, feature = "drain-check"),
+            feature = "readiness-cache"
+        ))]
+        registry.sources().add(token, &self.shared);
+        Ok(())
     }
 
     fn deregister(&mut self, registry: &Registry) -> io::Result<()> {
+        let fd = self.inner.as_raw_fd();
         #[cfg(debug_assertions)]
-        self.selector_id.remove_association(registry)?;
-        registry.selector().deregister(self.inner.as_raw_fd())
+        self.selector_id
+            .remove_association(registry)
+            .map_err(|err| {
+                registry.registration_error(Operation::Deregister, fd, None, None, err)
+            })?;
+        registry.deregister_fd(fd)?;
+        #[cfg(any(
+            all(debug_assertions, feature = "drain-check"),
+            feature = "readiness-cache"
+        ))]
+        registry.sources().remove(&self.shared);
+        Ok(())
+    }
+
+    fn transfer(
+        &mut self,
+        from: &Registry,
+        to: &Registry,
+        token: Token,
+        interests: Interest,
+    ) -> io::Result<()> {
+        let fd = self.inner.as_raw_fd();
+        // Transferring deregisters the source from `from`.
+        #[cfg(debug_assertions)]
+        self.selector_id
+            .check_association(from)
+            .map_err(|err| from.registration_error(Operation::Deregister, fd, None, None, err))?;
+        from.transfer_fd(to, fd, token, interests)?;
+        #[cfg(debug_assertions)]
+        self.selector_id.reassociate(to);
+        #[cfg(any(
+            all(debug_assertions, feature = "drain-check"),
+            feature = "readiness-cache"
+        ))]
+        transfer_state(from, to, token, &self.shared);
+        Ok(())
     }
 }
 
@@ -181,7 +395,13 @@ where
         #[cfg(debug_assertions)]
         self.selector_id.associate(registry)?;
         self.state
-            .register(registry, token, interests, self.inner.as_raw_socket())
+            .register(registry, token, interests, self.inner.as_raw_socket())?;
+        #[cfg(any(
+            all(debug_assertions, feature = "drain-check"),
+            feature = "readiness-cache"
+        ))]
+        registry.sources().add(token, &self.shared);
+        Ok(())
     }
 
     fn reregister(
@@ -192,13 +412,25 @@ where
     ) -> io::Result<()> {
         #[cfg(debug_assertions)]
         self.selector_id.check_association(registry)?;
-        self.state.reregister(registry, token, interests)
+        self.state.reregister(registry, token, interests)?;
+        #[cfg(any(
+            all(debug_assertions, feature = "drain-check"),
+            feature = "readiness-cache"
+        ))]
+        registry.sources().add(token, &self.shared);
+        Ok(())
     }
 
-    fn deregister(&mut self, _registry: &Registry) -> io::Result<()> {
+    fn deregister(&mut self, registry: &Registry) -> io::Result<()> {
         #[cfg(debug_assertions)]
-        self.selector_id.remove_association(_registry)?;
-        self.state.deregister()
+        self.selector_id.remove_association(registry)?;
+        self.state.deregister()?;
+        #[cfg(any(
+            all(debug_assertions, feature = "drain-check"),
+            feature = "readiness-cache"
+        ))]
+        registry.sources().remove(&self.shared);
+        Ok(())
     }
 }
 
@@ -217,7 +449,13 @@ where
         self.selector_id.associate(registry)?;
         registry
             .selector()
-            .register(self.inner.as_raw_fd() as _, token, interests)
+            .register(self.inner.as_raw_fd() as _, token, interests)?;
+        #[cfg(any(
+            all(debug_assertions, feature = "drain-check"),
+            feature = "readiness-cache"
+        ))]
+        registry.sources().add(token, &self.shared);
+        Ok(())
     }
 
     fn reregister(
@@ -230,13 +468,52 @@ where
         self.selector_id.check_association(registry)?;
         registry
             .selector()
-            .reregister(self.inner.as_raw_fd() as _, token, interests)
+            .reregister(self.inner.as_raw_fd() as _, token, interests)?;
+        #[cfg(any(
+            all(debug_assertions, feature = "drain-check"),
+            feature = "readiness-cache"
+        ))]
+        registry.sources().add(token, &self.shared);
+        Ok(())
     }
 
     fn deregister(&mut self, registry: &Registry) -> io::Result<()> {
         #[cfg(debug_assertions)]
         self.selector_id.remove_association(registry)?;
-        registry.selector().deregister(self.inner.as_raw_fd() as _)
+        registry
+            .selector()
+            .deregister(self.inner.as_raw_fd() as _)?;
+        #[cfg(any(
+            all(debug_assertions, feature = "drain-check"),
+            feature = "readiness-cache"
+        ))]
+        registry.sources().remove(&self.shared);
+        Ok(())
+    }
+
+    fn transfer(
+        &mut self,
+        from: &Registry,
+        to: &Registry,
+        token: Token,
+        interests: Interest,
+    ) -> io::Result<()> {
+        #[cfg(debug_assertions)]
+        self.selector_id.check_association(from)?;
+        let fd = self.inner.as_raw_fd() as _;
+        to.selector().register(fd, token, interests)?;
+        if let Err(err) = from.selector().deregister(fd) {
+            let _ = to.selector().deregister(fd);
+            return Err(err);
+        }
+        #[cfg(debug_assertions)]
+        self.selector_id.reassociate(to);
+        #[cfg(any(
+            all(debug_assertions, feature = "drain-check"),
+            feature = "readiness-cache"
+        ))]
+        transfer_state(from, to, token, &self.shared);
+        Ok(())
     }
 }
 
@@ -273,15 +550,20 @@ impl SelectorId {
     /// already registered.
     fn associate(&self, registry: &Registry) -> io::Result<()> {
         let registry_id = registry.selector().id();
-        let previous_id = self.id.swap(registry_id, Ordering::AcqRel);
+        let res = self.id.compare_exchange(
+            Self::UNASSOCIATED,
+            registry_id,
+            Ordering::AcqRel,
+            Ordering::Acquire,
+        );
 
-        if previous_id == Self::UNASSOCIATED {
-            Ok(())
-        } else {
-            Err(io::Error::new(
+        match res {
+            Ok(_) => Ok(()),
+            Err(id) if id == registry_id => Err(io::Error::new(
                 io::ErrorKind::AlreadyExists,
-                "I/O source already registered with a `Registry`",
-            ))
+                "I/O source already registered with this `Registry`",
+            )),
+            Err(_) => Err(Self::different_registry()),
         }
     }
 
@@ -295,33 +577,53 @@ impl SelectorId {
         if id == registry_id {
             Ok(())
         } else if id == Self::UNASSOCIATED {
-            Err(io::Error::new(
-                io::ErrorKind::NotFound,
-                "I/O source not registered with `Registry`",
-            ))
+            Err(Self::not_registered())
         } else {
-            Err(io::Error::new(
-                io::ErrorKind::AlreadyExists,
-                "I/O source already registered with a different `Registry`",
-            ))
+            Err(Self::different_registry())
         }
     }
 
+    /// Move the association to `registry`, after the I/O source is transferred
+    /// to it.
+    #[cfg(not(windows))]
+    fn reassociate(&self, registry: &Registry) {
+        self.id.store(registry.selector().id(), Ordering::Release);
+    }
+
     /// Remove a previously made association from `registry`, returns an error
     /// if it was not previously associated with `registry`.
     fn remove_association(&self, registry: &Registry) -> io::Result<()> {
         let registry_id = registry.selector().id();
-        let previous_id = self.id.swap(Self::UNASSOCIATED, Ordering::AcqRel);
+        let res = self.id.compare_exchange(
+            registry_id,
+            Self::UNASSOCIATED,
+            Ordering::AcqRel,
+            Ordering::Acquire,
+        );
 
-        if previous_id == registry_id {
-            Ok(())
-        } else {
-            Err(io::Error::new(
-                io::ErrorKind::NotFound,
-                "I/O source not registered with `Registry`",
-            ))
+        match res {
+            Ok(_) => Ok(()),
+            Err(Self::UNASSOCIATED) => Err(Self::not_registered()),
+            Err(_) => Err(Self::different_registry()),
         }
     }
+
+    fn not_registered() -> io::Error {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            "I/O source not registered with `Registry`",
+        )
+    }
+
+    /// Using an I/O source with a `Registry` it's not registered with is a
+    /// different mistake than not registering it at all, so it gets its own
+    /// error kind.
+    fn different_registry() -> io::Error {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "I/O source already registered with a different `Registry`",
+        )
+    }
 }
 
 #[cfg(debug_assertions)]
@@ -332,3 +634,452 @@ impl Clone for SelectorId {
         }
     }
 }
+
+/// Token value used by `SharedState` if the source isn't registered.
+#[cfg(any(
+    all(debug_assertions, feature = "drain-check"),
+    feature = "readiness-cache"
+))]
+const NOT_REGISTERED: usize = usize::MAX;
+
+/// Bits used in `SharedState::readiness`.
+#[cfg(feature = "readiness-cache")]
+const READABLE: u8 = 0b01;
+#[cfg(feature = "readiness-cache")]
+const WRITABLE: u8 = 0b10;
+
+/// Handle to the `SharedState` of an `IoSource`, removing the state from the
+/// bookkeeping of the `Registry` it's registered with once the source is
+/// dropped (even if it's not deregistered).
+#[cfg(any(
+    all(debug_assertions, feature = "drain-check"),
+    feature = "readiness-cache"
+))]
+#[derive(Debug)]
+struct Shared(Arc<SharedState>);
+
+#[cfg(any(
+    all(debug_assertions, feature = "drain-check"),
+    feature = "readiness-cache"
+))]
+impl Deref for Shared {
+    type Target = Arc<SharedState>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[cfg(any(
+    all(debug_assertions, feature = "drain-check"),
+    feature = "readiness-cache"
+))]
+impl Drop for Shared {
+    fn drop(&mut self) {
+        let registry = lock(&self.0.registry).upgrade();
+        if let Some(registry) = registry {
+            registry.remove(&self.0);
+        }
+    }
+}
+
+/// State of an I/O source shared with the `Registry` it's registered with, so
+/// that it can be updated in `Poll::poll`.
+#[cfg(any(
+    all(debug_assertions, feature = "drain-check"),
+    feature = "readiness-cache"
+))]
+#[derive(Debug)]
+struct SharedState {
+    /// Token the source is registered with, or `NOT_REGISTERED`.
+    token: AtomicUsize,
+    /// Bookkeeping of the `Registry` the source is registered with.
+    registry: Mutex<Weak<SharedStates>>,
+    /// Readiness of the last event received, see `READABLE` and `WRITABLE`.
+    #[cfg(feature = "readiness-cache")]
+    readiness: AtomicU8,
+    /// Set if a readable event was received and the source wasn't drained
+    /// since, i.e. no read returned `WouldBlock`, `Ok(0)`, a short read or an
+    /// error.
+    readable: AtomicBool,
+    /// Set if a read succeeded after the readable event without draining the
+    /// source, i.e. the source yielded.
+    yielded: AtomicBool,
+    /// Set if the reading side was reported as closed and the end of the
+    /// stream wasn't read yet, in which case a short read doesn't drain the
+    /// source as no new event will be received.
+    read_closed: AtomicBool,
+    /// Set if the source is queued for re-delivery, see `Fair`.
+    #[cfg(feature = "readiness-cache")]
+    queued: AtomicBool,
+}
+
+#[cfg(any(
+    all(debug_assertions, feature = "drain-check"),
+    feature = "readiness-cache"
+))]
+impl SharedState {
+    fn new() -> SharedState {
+        SharedState {
+            token: AtomicUsize::new(NOT_REGISTERED),
+            registry: Mutex::new(Weak::new()),
+            #[cfg(feature = "readiness-cache")]
+            readiness: AtomicU8::new(0),
+            readable: AtomicBool::new(false),
+            yielded: AtomicBool::new(false),
+            read_closed: AtomicBool::new(false),
+            #[cfg(feature = "readiness-cache")]
+            queued: AtomicBool::new(false),
+        }
+    }
+
+    #[cfg(all(feature = "readiness-cache", feature = "net"))]
+    fn readiness(&self) -> Option<Interest> {
+        match self.readiness.load(Ordering::Acquire) {
+            READABLE => Some(Interest::READABLE),
+            WRITABLE => Some(Interest::WRITABLE),
+            0 => None,
+            _ => Some(Interest::READABLE | Interest::WRITABLE),
+        }
+    }
+
+    /// Record the result of an I/O operation for `interest`, `None` if the
+    /// direction of the operation is unknown. `drained` indicates the
+    /// operation succeeded but showed the source is drained, e.g. a short
+    /// read.
+    fn did_io<R>(&self, interest: Option<Interest>, res: &io::Result<R>, drained: bool) {
+        let would_block = matches!(res, Err(err) if err.kind() == io::ErrorKind::WouldBlock);
+        #[cfg(feature = "readiness-cache")]
+        if let (true, Some(interest)) = (would_block, interest) {
+            let _ = self
+                .readiness
+                .fetch_and(!readiness_bits(interest), Ordering::AcqRel);
+        }
+        match interest {
+            Some(interest) if interest.is_readable() => {
+                if res.is_ok() && !drained {
+                    self.yielded.store(true, Ordering::Relaxed);
+                } else {
+                    self.drained();
+                }
+            }
+            // A write that doesn't return `WouldBlock` just means the caller
+            // has nothing more to write, that doesn't stall the source.
+            Some(_) => {}
+            // We can't tell what an operation in an unknown direction means
+            // for the reading side, so only a `WouldBlock` error is taken into
+            // account, preferring to miss an undrained source over reporting
+            // a drained one.
+            None if would_block => self.drained(),
+            None => {}
+        }
+    }
+
+    /// Mark the reading side as drained.
+    fn drained(&self) {
+        self.readable.store(false, Ordering::Relaxed);
+        self.yielded.store(false, Ordering::Relaxed);
+        self.read_closed.store(false, Ordering::Relaxed);
+    }
+
+    /// Returns `true` if the source received a readable event and yielded
+    /// without being drained.
+    fn undrained(&self) -> bool {
+        self.readable.load(Ordering::Relaxed) && self.yielded.load(Ordering::Relaxed)
+    }
+
+    /// Update the state based on a received `event`, returns `true` if it's
+    /// a readable event that must be drained.
+    fn received(&self, event: &event::Event) -> bool {
+        #[cfg(feature = "readiness-cache")]
+        {
+            let mut readiness = 0;
+            if event.is_readable() || event.is_read_closed() || event.is_error() {
+                readiness |= READABLE;
+            }
+            if event.is_writable() || event.is_write_closed() || event.is_error() {
+                readiness |= WRITABLE;
+            }
+            let _ = self.readiness.fetch_or(readiness, Ordering::AcqRel);
+        }
+        if event.is_readable() || event.is_read_closed() {
+            if event.is_read_closed() {
+                self.read_closed.store(true, Ordering::Relaxed);
+            }
+            self.readable.store(true, Ordering::Relaxed);
+            self.yielded.store(false, Ordering::Relaxed);
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Move `state` from the bookkeeping of `from` to `to`, after its source is
+/// transferred. The readiness is kept as the source didn't change.
+#[cfg(all(
+    not(windows),
+    any(
+        all(debug_assertions, feature = "drain-check"),
+        feature = "readiness-cache"
+    )
+))]
+fn transfer_state(from: &Registry, to: &Registry, token: Token, state: &Arc<SharedState>) {
+    #[cfg(feature = "readiness-cache")]
+    let readiness = state.readiness.load(Ordering::Acquire);
+    from.sources().remove(state);
+    to.sources().add(token, state);
+    #[cfg(feature = "readiness-cache")]
+    state.readiness.store(readiness, Ordering::Release);
+}
+
+#[cfg(feature = "readiness-cache")]
+fn readiness_bits(interest: Interest) -> u8 {
+    let mut bits = 0;
+    if interest.is_readable() {
+        bits |= READABLE;
+    }
+    if interest.is_writable() {
+        bits |= WRITABLE;
+    }
+    bits
+}
+
+/// Bookkeeping of the `SharedState` of all I/O sources registered with a
+/// `Registry`, shared between all its clones.
+///
+/// Sources remove themselves when they're deregistered or dropped.
+#[cfg(any(
+    all(debug_assertions, feature = "drain-check"),
+    feature = "readiness-cache"
+))]
+#[derive(Debug, Default)]
+pub(crate) struct SharedStates {
+    sources: RwLock<HashMap<Token, Vec<Weak<SharedState>>>>,
+    /// Number of tokens in `sources`, used to skip the bookkeeping in
+    /// `Poll::poll` if no I/O sources are registered.
+    len: AtomicUsize,
+    /// Sources that received a readable event in the last call to
+    /// `Poll::poll`.
+    #[cfg(all(debug_assertions, feature = "drain-check"))]
+    pending: Mutex<Vec<Arc<SharedState>>>,
+    #[cfg(feature = "readiness-cache")]
+    fair: Mutex<Fair>,
+}
+
+/// State for fair re-delivery, see `Poll::set_fair_redelivery`.
+#[cfg(feature = "readiness-cache")]
+#[derive(Debug, Default)]
+struct Fair {
+    enabled: bool,
+    /// Sources that received a readable event in the last call to
+    /// `Poll::poll`.
+    delivered: Vec<Weak<SharedState>>,
+    /// Sources that yielded without being drained, in the order they are
+    /// re-delivered.
+    queue: VecDeque<Weak<SharedState>>,
+}
+
+#[cfg(any(
+    all(debug_assertions, feature = "drain-check"),
+    feature = "readiness-cache"
+))]
+impl SharedStates {
+    /// Add, or move, `state` to `token`.
+    fn add(self: &Arc<Self>, token: Token, state: &Arc<SharedState>) {
+        let mut sources = self.sources.write().unwrap_or_else(PoisonError::into_inner);
+        let old_token = state.token.swap(usize::from(token), Ordering::Relaxed);
+        if old_token != NOT_REGISTERED {
+            remove_state(&mut sources, Token(old_token), state);
+        }
+        sources
+            .entry(token)
+            .or_default()
+            .push(Arc::downgrade(state));
+        self.len.store(sources.len(), Ordering::Relaxed);
+        *lock(&state.registry) = Arc::downgrade(self);
+        #[cfg(feature = "readiness-cache")]
+        state.readiness.store(0, Ordering::Release);
+        state.drained();
+    }
+
+    /// Remove `state`, after its source is deregistered or dropped.
+    fn remove(&self, state: &Arc<SharedState>) {
+        let mut sources = self.sources.write().unwrap_or_else(PoisonError::into_inner);
+        let token = state.token.swap(NOT_REGISTERED, Ordering::Relaxed);
+        if token != NOT_REGISTERED {
+            remove_state(&mut sources, Token(token), state);
+        }
+        self.len.store(sources.len(), Ordering::Relaxed);
+        *lock(&state.registry) = Weak::new();
+        #[cfg(feature = "readiness-cache")]
+        state.readiness.store(0, Ordering::Release);
+        state.drained();
+    }
+
+    /// Warn about sources that received a readable event in the previous
+    /// poll, read from it, but didn't drain it.
+    #[cfg(all(debug_assertions, feature = "drain-check"))]
+    pub(crate) fn check_pending(&self) {
+        if self.len.load(Ordering::Relaxed) == 0 {
+            return;
+        }
+        for state in lock(&self.pending).drain(..) {
+            if state.undrained() {
+                warn!(
+                    "I/O source with token {} received a readable event but wasn't drained \
+                    (no `WouldBlock` error was returned) before polling again, \
+                    this can stall the source when using edge-triggered events",
+                    state.token.load(Ordering::Relaxed),
+                );
+            }
+        }
+    }
+
+    /// Enable or disable fair re-delivery.
+    #[cfg(feature = "readiness-cache")]
+    pub(crate) fn set_fair(&self, enabled: bool) {
+        let mut fair = lock(&self.fair);
+        fair.enabled = enabled;
+        if !enabled {
+            fair.delivered.clear();
+            for state in fair.queue.drain(..).filter_map(|state| state.upgrade()) {
+                state.queued.store(false, Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Queue the sources that yielded without being drained since the last
+    /// call to `Poll::poll` for re-delivery, returning the timeout to use.
+    #[cfg(feature = "readiness-cache")]
+    pub(crate) fn poll_timeout(&self, timeout: Option<Duration>) -> Option<Duration> {
+        let mut fair = lock(&self.fair);
+        let Fair {
+            enabled,
+            delivered,
+            queue,
+        } = &mut *fair;
+        if !*enabled {
+            return timeout;
+        }
+        for state in delivered.drain(..) {
+            if let Some(s) = state.upgrade() {
+                if s.undrained() && !s.queued.swap(true, Ordering::Relaxed) {
+                    queue.push_back(state);
+                }
+            }
+        }
+        if queue.is_empty() {
+            timeout
+        } else {
+            // Don't block while sources are waiting to be re-delivered.
+            Some(Duration::from_millis(0))
+        }
+    }
+
+    /// Update the state of the sources that received an event in `events`.
+    ///
+    /// With fair re-delivery enabled this also adds events for queued sources.
+    pub(crate) fn received(&self, events: &mut Events) {
+        #[cfg(feature = "readiness-cache")]
+        let mut fair = lock(&self.fair);
+        #[cfg(feature = "readiness-cache")]
+        {
+            let fair = &mut *fair;
+            if fair.enabled && !fair.queue.is_empty() {
+                let sources = self.sources.read().unwrap_or_else(PoisonError::into_inner);
+                redeliver(&sources, &mut fair.queue, events);
+            }
+        }
+        if events.is_empty() || self.len.load(Ordering::Relaxed) == 0 {
+            return;
+        }
+
+        let sources = self.sources.read().unwrap_or_else(PoisonError::into_inner);
+        #[cfg(all(debug_assertions, feature = "drain-check"))]
+        let mut pending = lock(&self.pending);
+        for event in events.iter() {
+            if let Some(states) = sources.get(&event.token()) {
+                for state in states.iter().filter_map(Weak::upgrade) {
+                    if !state.received(event) {
+                        continue;
+                    }
+                    #[cfg(feature = "readiness-cache")]
+                    if fair.enabled {
+                        fair.delivered.push(Arc::downgrade(&state));
+                    }
+                    #[cfg(all(debug_assertions, feature = "drain-check"))]
+                    pending.push(state);
+                }
+            }
+        }
+    }
+}
+
+/// Add readable events for the queued sources to `events`, in order, for as
+/// long as `events` has capacity. Sources that received a new event are
+/// skipped.
+#[cfg(feature = "readiness-cache")]
+fn redeliver(
+    sources: &HashMap<Token, Vec<Weak<SharedState>>>,
+    queue: &mut VecDeque<Weak<SharedState>>,
+    events: &mut Events,
+) {
+    let received: HashSet<Token> = events.iter().map(event::Event::token).collect();
+    loop {
+        let weak = match queue.pop_front() {
+            Some(weak) => weak,
+            None => break,
+        };
+        let state = match weak.upgrade() {
+            Some(state) => state,
+            None => continue,
+        };
+        state.queued.store(false, Ordering::Relaxed);
+        let token = Token(state.token.load(Ordering::Relaxed));
+        // The source could have been deregistered, or transferred to
+        // another `Registry`, since it was queued.
+        let registered = sources
+            .get(&token)
+            .into_iter()
+            .flatten()
+            .any(|s| s.ptr_eq(&weak));
+        if !registered || received.contains(&token) {
+            continue;
+        }
+        if state.undrained() && !events.push_interest(token, Interest::READABLE) {
+            // Out of capacity, try again on the next call to `Poll::poll`.
+            state.queued.store(true, Ordering::Relaxed);
+            queue.push_front(weak);
+            break;
+        }
+    }
+}
+
+#[cfg(any(
+    all(debug_assertions, feature = "drain-check"),
+    feature = "readiness-cache"
+))]
+fn remove_state(
+    sources: &mut HashMap<Token, Vec<Weak<SharedState>>>,
+    token: Token,
+    state: &Arc<SharedState>,
+) {
+    if let Some(states) = sources.get_mut(&token) {
+        // Also clean up any dropped sources.
+        let state = Arc::downgrade(state);
+        states.retain(|s| s.strong_count() != 0 && !s.ptr_eq(&state));
+        if states.is_empty() {
+            let _ = sources.remove(&token);
+        }
+    }
+}
+
+/// Lock `mutex`, ignoring poisoning as the bookkeeping is always consistent.
+#[cfg(any(
+    all(debug_assertions, feature = "drain-check"),
+    feature = "readiness-cache"
+))]
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
@@ -61,9 +61,17 @@ cfg_net! {
     pub mod net;
 }
 
+cfg_util! {
+    pub mod util;
+}
+
 #[doc(no_inline)]
 pub use event::Events;
 pub use interest::Interest;
+#[cfg(all(unix, feature = "registry-introspection"))]
+pub use poll::Registration;
+#[cfg(all(unix, any(feature = "os-poll", feature = "os-ext", feature = "net")))]
+pub use poll::RegistrationError;
 pub use poll::{Poll, Registry};
 pub use token::Token;
 #[cfg(not(target_os = "wasi"))]
@@ -83,6 +91,46 @@ pub mod unix {
     }
 
     pub use crate::sys::SourceFd;
+
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    #[cfg_attr(docsrs, doc(cfg(any(target_os = "android", target_os = "linux"))))]
+    pub use crate::sys::splice::{splice, tee, SpliceBlocked, SpliceRead, SpliceWrite};
+}
+
+pub mod mem {
+    //! In-process event sources.
+    //!
+    //! The sources in this module can be registered with any [`Poll`]
+    //! instance. Without the `os-poll` feature Mio uses a selector implemented
+    //! in user space. It can't poll OS resources, such as the types in the
+    //! `net` module, but it does support [`Waker`] and the in-process sources
+    //! in this module. This allows libraries using Mio to run their tests
+    //! without the `os-poll` feature.
+    //!
+    //! With the `os-poll` feature the events of these sources are added to the
+    //! events returned by the OS selector. To wake up the OS selector a
+    //! `Waker` is registered with `Token(usize::MAX >> 1)` once the first
+    //! source is registered, that token must not be used for other event
+    //! sources registered with the same `Poll` instance.
+    //!
+    //! [`Poll`]: crate::Poll
+    //! [`Waker`]: crate::Waker
+
+    pub mod channel {
+        //! In-memory channel.
+        //!
+        //! See the [`new`] function for documentation.
+
+        pub use crate::sys::mem::channel::{new, Receiver, Sender};
+    }
+
+    pub mod pipe {
+        //! In-memory pipe.
+        //!
+        //! See the [`new`] function for documentation.
+
+        pub use crate::sys::mem::pipe::{new, Receiver, Sender};
+    }
 }
 
 #[cfg(all(windows, feature = "os-ext"))]
@@ -101,11 +149,14 @@ pub mod features {
     #![cfg_attr(feature = "os-poll", doc = "## `os-poll` (enabled)")]
     #![cfg_attr(not(feature = "os-poll"), doc = "## `os-poll` (disabled)")]
     //!
-    //! Mio by default provides only a shell implementation that `panic!`s the
-    //! moment it is actually run. To run it requires OS support, this is
-    //! enabled by activating the `os-poll` feature.
+    //! Mio by default provides only a shell implementation, which polls a
+    //! selector implemented in user space. It supports `Waker` and the
+    //! in-process sources in the `mem` module, but `panic!`s the moment OS
+    //! resources, such as the types in the `net` module, are used. Polling OS
+    //! resources requires OS support, this is enabled by activating the
+    //! `os-poll` feature.
     //!
-    //! This makes `Poll`, `Registry` and `Waker` functional.
+    //! This makes `Poll`, `Registry` and `Waker` use the OS selector.
     //!
     #![cfg_attr(feature = "os-ext", doc = "## `os-ext` (enabled)")]
     #![cfg_attr(not(feature = "os-ext"), doc = "## `os-ext` (disabled)")]
@@ -117,6 +168,56 @@ pub mod features {
     #![cfg_attr(not(feature = "net"), doc = "## Network types (disabled)")]
     //!
     //! The `net` feature enables networking primitives in the `net` module.
+    //!
+    #![cfg_attr(
+        feature = "registry-introspection",
+        doc = "## `registry-introspection` (enabled)"
+    )]
+    #![cfg_attr(
+        not(feature = "registry-introspection"),
+        doc = "## `registry-introspection` (disabled)"
+    )]
+    //!
+    //! `registry-introspection` makes `Registry` keep track of the file
+    //! descriptors registered with it (Unix only). The registrations can be
+    //! inspected using `Registry::registrations` and `Registry::is_registered`,
+    //! and are included in the `Debug` output of `Registry`. Registering a file
+    //! descriptor that is already registered returns an error naming the token
+    //! it's registered with.
+    //!
+    #![cfg_attr(feature = "readiness-cache", doc = "## `readiness-cache` (enabled)")]
+    #![cfg_attr(
+        not(feature = "readiness-cache"),
+        doc = "## `readiness-cache` (disabled)"
+    )]
+    //!
+    //! `readiness-cache` makes Mio's I/O sources remember the readiness of the
+    //! last event received for them, until an I/O operation returns a
+    //! `WouldBlock` error. The readiness can be retrieved using
+    //! `TcpStream::readiness` or `UdpSocket::readiness`. This adds a small
+    //! amount of memory and bookkeeping to every I/O source.
+    //!
+    //! Fair re-delivery, `Poll::set_fair_redelivery`, which re-delivers events
+    //! to sources that yielded before being drained, depends on this readiness
+    //! and so is only available with `readiness-cache` enabled, it doesn't
+    //! have a feature of its own.
+    //!
+    #![cfg_attr(feature = "drain-check", doc = "## `drain-check` (enabled)")]
+    #![cfg_attr(not(feature = "drain-check"), doc = "## `drain-check` (disabled)")]
+    //!
+    //! `drain-check` makes Mio's I/O sources log a warning, in debug builds,
+    //! when they received a readable event and were read from, but not
+    //! drained (i.e. no read returned a `WouldBlock` error) before polling
+    //! again. With edge-triggered events such a source can stall forever. This
+    //! adds bookkeeping to every I/O source and every call to `Poll::poll`, so
+    //! it's meant for debugging only.
+    //!
+    #![cfg_attr(feature = "util", doc = "## `util` (enabled)")]
+    #![cfg_attr(not(feature = "util"), doc = "## `util` (disabled)")]
+    //!
+    //! The `util` feature enables the `util` module, which contains utilities
+    //! built on top of Mio's core types, such as `TokenSlab`, `EventLoop` and
+    //! `block_on`.
 }
 
 pub mod guide {
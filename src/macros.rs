@@ -68,3 +68,13 @@ macro_rules! cfg_any_os_ext {
         )*
     }
 }
+
+macro_rules! cfg_util {
+    ($($item:item)*) => {
+        $(
+            #[cfg(feature = "util")]
+            #[cfg_attr(docsrs, doc(cfg(feature = "util")))]
+            $item
+        )*
+    }
+}
@@ -0,0 +1,216 @@
+use std::fs::File;
+use std::{fmt, io};
+
+use crate::sys::mem::{Registration, READABLE};
+use crate::{event, Interest, Registry, Token};
+
+/// A reserved file descriptor, used to handle running out of file descriptors
+/// while accepting connections.
+///
+/// When a process reaches its file descriptor limit `accept` fails with
+/// `EMFILE` (or `ENFILE` for the system-wide limit), but the connection
+/// remains in the accept queue. As the listener stays readable this leads to
+/// a busy loop (or, if accept is only retried on an event, a stalled
+/// listener). To get out of this, [`TcpListener::accept_reserved`] and
+/// [`UnixListener::accept_reserved`] temporarily release the reserved file
+/// descriptor to accept the pending connection and close it immediately,
+/// shedding load, before reserving a descriptor again.
+///
+/// The `EMFILE` or `ENFILE` error is still returned, shed connections are
+/// counted, see [`FdReserve::shed_count`].
+///
+/// # Backpressure
+///
+/// If the file descriptor can't be reserved again after shedding a
+/// connection, i.e. another file descriptor was opened in the meantime, the
+/// next connection can't be shed. Accepting would then fail over and over, so
+/// the listener should be paused until file descriptors are released. To
+/// report this `FdReserve` can be registered with a [`Registry`], like any
+/// other event source, using [`Interest::READABLE`]. It receives a readable
+/// event when it loses its reserved file descriptor, at which point the
+/// listener should be deregistered, and when it reserved a file descriptor
+/// again, at which point the listener can be registered again. Registering
+/// the listener reports the pending connections again. Check
+/// [`FdReserve::is_reserved`] to tell the two apart, note that both changes
+/// may be reported in a single event.
+///
+/// Reserving a file descriptor again is attempted by
+/// [`FdReserve::reserve`], which should be called after closing file
+/// descriptors, e.g. once a connection is closed.
+///
+/// [`TcpListener::accept_reserved`]: crate::net::TcpListener::accept_reserved
+/// [`UnixListener::accept_reserved`]: crate::net::UnixListener::accept_reserved
+/// [`Registry`]: crate::Registry
+///
+/// # Examples
+///
+#[cfg_attr(feature = "os-poll", doc = "```")]
+#[cfg_attr(not(feature = "os-poll"), doc = "```ignore")]
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use std::io;
+///
+/// use mio::net::{FdReserve, TcpListener};
+/// use mio::{Interest, Poll, Token};
+///
+/// const LISTENER: Token = Token(0);
+/// const RESERVE: Token = Token(1);
+///
+/// let poll = Poll::new()?;
+/// let mut listener = TcpListener::bind("127.0.0.1:0".parse()?)?;
+/// let mut reserve = FdReserve::new()?;
+/// poll.registry().register(&mut listener, LISTENER, Interest::READABLE)?;
+/// poll.registry().register(&mut reserve, RESERVE, Interest::READABLE)?;
+///
+/// // Once the listener is readable.
+/// loop {
+///     match listener.accept_reserved(&mut reserve) {
+///         Ok((stream, address)) => {
+///             // Handle the connection.
+/// #           drop((stream, address));
+///         }
+///         Err(ref err) if err.kind() == io::ErrorKind::WouldBlock => break,
+///         // The connection couldn't be shed, wait for the event of the
+///         // reserve to pause the listener.
+///         Err(_) if !reserve.is_reserved() => break,
+///         Err(err) => {
+///             // The connection was shed, keep accepting to drain the queue.
+///             eprintln!("failed to accept connection: {}", err);
+///         }
+///     }
+/// }
+///
+/// // Once the reserve is readable.
+/// # let mut accepting = true;
+/// if reserve.is_reserved() && !accepting {
+///     poll.registry().register(&mut listener, LISTENER, Interest::READABLE)?;
+///     accepting = true;
+/// } else if !reserve.is_reserved() && accepting {
+///     poll.registry().deregister(&mut listener)?;
+///     accepting = false;
+/// }
+/// # let _ = accepting;
+/// #     Ok(())
+/// # }
+/// ```
+pub struct FdReserve {
+    reserved: Option<File>,
+    shed: u64,
+    /// Registration used to report changes to `reserved`.
+    registration: Registration,
+}
+
+impl FdReserve {
+    /// Reserve a file descriptor, by opening `/dev/null`.
+    pub fn new() -> io::Result<FdReserve> {
+        File::open("/dev/null").map(|file| FdReserve {
+            reserved: Some(file),
+            shed: 0,
+            registration: Registration::default(),
+        })
+    }
+
+    /// Returns `true` if a file descriptor is reserved.
+    ///
+    /// This is `false` if the file descriptor couldn't be reserved again after
+    /// shedding a connection, in which case connections can't be shed until
+    /// [`FdReserve::reserve`] succeeds.
+    pub fn is_reserved(&self) -> bool {
+        self.reserved.is_some()
+    }
+
+    /// Attempts to reserve a file descriptor, if none is reserved, returning
+    /// [`FdReserve::is_reserved`].
+    ///
+    /// If this reserves a file descriptor a readable event is reported, see
+    /// [Backpressure].
+    ///
+    /// [Backpressure]: FdReserve#backpressure
+    pub fn reserve(&mut self) -> bool {
+        if self.reserved.is_none() {
+            self.reserved = File::open("/dev/null").ok();
+            if self.reserved.is_some() {
+                self.registration.notify(READABLE);
+            }
+        }
+        self.is_reserved()
+    }
+
+    /// Returns the number of connections accepted and closed immediately
+    /// because the process ran out of file descriptors.
+    pub fn shed_count(&self) -> u64 {
+        self.shed
+    }
+
+    /// Sheds a connection after `accept` failed with `err`, using `accept` to
+    /// accept the connection. Returns `err`, or a `WouldBlock` error if there
+    /// was no connection to shed.
+    pub(crate) fn shed<T, F>(&mut self, err: io::Error, accept: F) -> io::Error
+    where
+        F: FnOnce() -> io::Result<T>,
+    {
+        let was_reserved = self.is_reserved();
+        if self.reserved.is_none() {
+            // File descriptors might have been released since.
+            self.reserved = File::open("/dev/null").ok();
+        }
+        let err = match self.reserved.take() {
+            Some(file) => {
+                drop(file);
+                // Accept the connection and close it right away. Note that
+                // Linux allocates the file descriptor before checking the
+                // accept queue, so `EMFILE` doesn't mean a connection is
+                // pending.
+                match accept() {
+                    Ok(_) => {
+                        self.shed += 1;
+                        err
+                    }
+                    Err(ref accept_err) if accept_err.kind() == io::ErrorKind::WouldBlock => {
+                        io::ErrorKind::WouldBlock.into()
+                    }
+                    Err(_) => err,
+                }
+            }
+            None => err,
+        };
+        self.reserved = File::open("/dev/null").ok();
+        if self.is_reserved() != was_reserved {
+            self.registration.notify(READABLE);
+        }
+        err
+    }
+}
+
+impl event::Source for FdReserve {
+    fn register(
+        &mut self,
+        registry: &Registry,
+        token: Token,
+        interests: Interest,
+    ) -> io::Result<()> {
+        self.registration.register(registry, token, interests)
+    }
+
+    fn reregister(
+        &mut self,
+        registry: &Registry,
+        token: Token,
+        interests: Interest,
+    ) -> io::Result<()> {
+        self.registration.reregister(registry, token, interests)
+    }
+
+    fn deregister(&mut self, registry: &Registry) -> io::Result<()> {
+        self.registration.deregister(registry)
+    }
+}
+
+impl fmt::Debug for FdReserve {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FdReserve")
+            .field("reserved", &self.reserved)
+            .field("shed", &self.shed)
+            .finish()
+    }
+}
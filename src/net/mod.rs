@@ -26,6 +26,18 @@
 //! give is to always call receive with a large enough buffer.
 
 mod tcp;
+#[cfg(any(
+    target_os = "android",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "tvos",
+    target_os = "watchos",
+))]
+pub use self::tcp::TcpInfo;
+#[cfg(not(target_os = "wasi"))]
+pub use self::tcp::{TcpConnector, TcpKeepalive, TcpSocket};
 pub use self::tcp::{TcpListener, TcpStream};
 
 #[cfg(not(target_os = "wasi"))]
@@ -33,6 +45,16 @@ mod udp;
 #[cfg(not(target_os = "wasi"))]
 pub use self::udp::UdpSocket;
 
+#[cfg(any(target_os = "android", target_os = "linux"))]
+mod zerocopy;
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub use self::zerocopy::{ZeroCopyCompletion, ZeroCopyCompletions, ZeroCopyQueue};
+
+#[cfg(unix)]
+mod fd_reserve;
+#[cfg(unix)]
+pub use self::fd_reserve::FdReserve;
+
 #[cfg(unix)]
 mod uds;
 #[cfg(unix)]
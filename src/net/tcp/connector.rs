@@ -0,0 +1,239 @@
+use std::collections::VecDeque;
+use std::io;
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+
+use crate::net::TcpStream;
+use crate::{Interest, Registry, Token};
+
+/// Default time between starting two connection attempts, as recommended by
+/// RFC 8305.
+const DEFAULT_DELAY: Duration = Duration::from_millis(250);
+
+/// Connect to one of multiple addresses, using the Happy Eyeballs algorithm
+/// (RFC 8305).
+///
+/// The addresses, e.g. the result of resolving a host name, are tried in
+/// order, alternating between IPv6 and IPv4 addresses starting with the
+/// family of the first address. Rather than waiting for an attempt to fail,
+/// a next attempt is started once the previous one didn't succeed within a
+/// delay (250 milliseconds by default, see [`TcpConnector::with_delay`]).
+/// This avoids long waits if one of the address families is broken, while
+/// preferring the first addresses.
+///
+/// All attempts are registered with the same [`Token`] and driven using
+/// [`TcpConnector::drive`], which must be called for every event with that
+/// token and once the [`TcpConnector::timeout`] expires. It returns the
+/// first stream that connected successfully and closes the other attempts.
+///
+/// # Notes
+///
+/// The returned stream is still registered with the token and
+/// `Interest::WRITABLE`, use [`Registry::reregister`] to change its
+/// interests.
+///
+/// [`Registry::reregister`]: crate::Registry::reregister
+///
+/// # Examples
+///
+#[cfg_attr(feature = "os-poll", doc = "```")]
+#[cfg_attr(not(feature = "os-poll"), doc = "```ignore")]
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use mio::net::TcpConnector;
+/// use mio::{Events, Poll, Token};
+///
+/// # let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+/// # let addrs = vec![listener.local_addr()?];
+/// let mut poll = Poll::new()?;
+/// let mut events = Events::with_capacity(128);
+///
+/// let mut connector = TcpConnector::new(addrs, Token(0));
+/// let (stream, address) = loop {
+///     if let Some(connected) = connector.drive(poll.registry())? {
+///         break connected;
+///     }
+///     // Wait for an event for one of the attempts, or for the time to start
+///     // the next attempt.
+///     poll.poll(&mut events, connector.timeout())?;
+/// };
+/// println!("connected to {}", address);
+/// #     drop(stream);
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct TcpConnector {
+    token: Token,
+    delay: Duration,
+    /// Addresses not yet tried, in the order to try them.
+    addrs: VecDeque<SocketAddr>,
+    /// Connection attempts in progress.
+    attempts: Vec<(TcpStream, SocketAddr)>,
+    /// Time to start the next attempt while other attempts are in progress,
+    /// `None` if the delay doesn't fit in an `Instant`. Without attempts in
+    /// progress the next attempt is always started right away.
+    next_attempt: Option<Instant>,
+    /// Error of the last failed attempt.
+    last_error: Option<io::Error>,
+}
+
+impl TcpConnector {
+    /// Create a new connector for `addrs`, registering the attempts using
+    /// `token`.
+    ///
+    /// No attempts are started until [`TcpConnector::drive`] is called.
+    pub fn new<I>(addrs: I, token: Token) -> TcpConnector
+    where
+        I: IntoIterator<Item = SocketAddr>,
+    {
+        TcpConnector {
+            token,
+            delay: DEFAULT_DELAY,
+            addrs: interleave(addrs),
+            attempts: Vec::new(),
+            next_attempt: None,
+            last_error: None,
+        }
+    }
+
+    /// Set the time to wait for an attempt before starting the next one.
+    ///
+    /// Defaults to 250 milliseconds. A failed attempt always starts the next
+    /// one right away.
+    pub fn with_delay(self, delay: Duration) -> TcpConnector {
+        TcpConnector { delay, ..self }
+    }
+
+    /// Returns the time until the next attempt should be started, to be used
+    /// as timeout in [`Poll::poll`].
+    ///
+    /// Returns `None` if all attempts are started, or if the delay is too
+    /// large to compute the time of the next attempt, in which case only
+    /// events for the token can complete the connection (or start the next
+    /// attempt once an attempt fails).
+    ///
+    /// [`Poll::poll`]: crate::Poll::poll
+    pub fn timeout(&self) -> Option<Duration> {
+        if self.addrs.is_empty() {
+            return None;
+        }
+        if self.attempts.is_empty() {
+            return Some(Duration::from_secs(0));
+        }
+        self.next_attempt
+            .map(|next_attempt| next_attempt.saturating_duration_since(Instant::now()))
+    }
+
+    /// Advance the connection attempts.
+    ///
+    /// Checks the attempts in progress and starts the next attempt if it's
+    /// due, or if there are no attempts in progress. Returns the connected
+    /// stream and its address once an attempt succeeds, closing the other
+    /// attempts, or `None` if the attempts are still in progress.
+    ///
+    /// If all attempts fail the error of the last failed attempt is returned.
+    /// Calling this after it returned a stream or an error returns an error.
+    pub fn drive(&mut self, registry: &Registry) -> io::Result<Option<(TcpStream, SocketAddr)>> {
+        let mut i = 0;
+        while i < self.attempts.len() {
+            match is_connected(&self.attempts[i].0) {
+                Ok(true) => {
+                    let connected = self.attempts.swap_remove(i);
+                    self.close(registry);
+                    return Ok(Some(connected));
+                }
+                Ok(false) => i += 1,
+                Err(err) => {
+                    let (mut stream, _) = self.attempts.swap_remove(i);
+                    let _ = registry.deregister(&mut stream);
+                    self.last_error = Some(err);
+                    // Start the next attempt right away.
+                    self.next_attempt = Some(Instant::now());
+                }
+            }
+        }
+
+        let now = Instant::now();
+        while !self.addrs.is_empty() && self.attempt_due(now) {
+            let addr = self.addrs.pop_front().unwrap();
+            match self.start(registry, addr) {
+                Ok(stream) => {
+                    self.attempts.push((stream, addr));
+                    self.next_attempt = now.checked_add(self.delay);
+                }
+                Err(err) => self.last_error = Some(err),
+            }
+        }
+
+        if self.attempts.is_empty() {
+            Err(self.last_error.take().unwrap_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidInput, "no addresses to connect to")
+            }))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn attempt_due(&self, now: Instant) -> bool {
+        if self.attempts.is_empty() {
+            return true;
+        }
+        match self.next_attempt {
+            Some(next_attempt) => next_attempt <= now,
+            None => false,
+        }
+    }
+
+    fn start(&self, registry: &Registry, addr: SocketAddr) -> io::Result<TcpStream> {
+        let mut stream = TcpStream::connect(addr)?;
+        registry.register(&mut stream, self.token, Interest::WRITABLE)?;
+        Ok(stream)
+    }
+
+    /// Closes all attempts in progress and drops the remaining addresses.
+    fn close(&mut self, registry: &Registry) {
+        for (mut stream, _) in self.attempts.drain(..) {
+            let _ = registry.deregister(&mut stream);
+        }
+        self.addrs.clear();
+        self.last_error = None;
+    }
+}
+
+/// Returns `Ok(true)` if the connection is established, `Ok(false)` if it's
+/// still in progress or an error if it failed.
+fn is_connected(stream: &TcpStream) -> io::Result<bool> {
+    if let Some(err) = stream.take_error()? {
+        return Err(err);
+    }
+    match stream.peer_addr() {
+        Ok(_) => Ok(true),
+        Err(ref err) if err.kind() == io::ErrorKind::NotConnected => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+/// Orders `addrs` alternating between the address families, starting with
+/// the family of the first address.
+fn interleave<I>(addrs: I) -> VecDeque<SocketAddr>
+where
+    I: IntoIterator<Item = SocketAddr>,
+{
+    let mut addrs = addrs.into_iter();
+    let first = match addrs.next() {
+        Some(first) => first,
+        None => return VecDeque::new(),
+    };
+    let (mut same, mut other): (VecDeque<_>, VecDeque<_>) =
+        addrs.partition(|addr| addr.is_ipv6() == first.is_ipv6());
+    same.push_front(first);
+
+    let mut ordered = VecDeque::with_capacity(same.len() + other.len());
+    loop {
+        match (same.pop_front(), other.pop_front()) {
+            (None, None) => return ordered,
+            (a, b) => ordered.extend(a.into_iter().chain(b)),
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use std::time::Duration;
+
+/// Information about a TCP connection, returned by [`TcpStream::tcp_info`].
+///
+/// All values are a snapshot taken at the time of the call. Values that are
+/// not provided by the OS, e.g. because the kernel is too old, are `None`.
+///
+/// All values are available on Android and Linux (4.9 or later). On FreeBSD
+/// [`unacked`], [`pacing_rate`] and [`delivery_rate`] are not available. On
+/// Apple platforms only [`rtt`], [`rtt_var`] and [`congestion_window`] are
+/// available, with a precision of milliseconds for the round-trip times.
+///
+/// [`unacked`]: TcpInfo::unacked
+/// [`pacing_rate`]: TcpInfo::pacing_rate
+/// [`delivery_rate`]: TcpInfo::delivery_rate
+/// [`rtt`]: TcpInfo::rtt
+/// [`rtt_var`]: TcpInfo::rtt_var
+/// [`congestion_window`]: TcpInfo::congestion_window
+/// [`TcpStream::tcp_info`]: crate::net::TcpStream::tcp_info
+///
+/// # Examples
+///
+#[cfg_attr(feature = "os-poll", doc = "```")]
+#[cfg_attr(not(feature = "os-poll"), doc = "```ignore")]
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use mio::net::TcpStream;
+///
+/// # let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+/// let stream = TcpStream::connect(listener.local_addr()?)?;
+///
+/// let info = stream.tcp_info()?;
+/// if let Some(rtt) = info.rtt() {
+///     println!("round-trip time: {:?}", rtt);
+/// }
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct TcpInfo {
+    pub(crate) rtt: Option<Duration>,
+    pub(crate) rtt_var: Option<Duration>,
+    pub(crate) congestion_window: Option<u32>,
+    pub(crate) retransmits: Option<u32>,
+    pub(crate) unacked: Option<u32>,
+    pub(crate) pacing_rate: Option<u64>,
+    pub(crate) delivery_rate: Option<u64>,
+}
+
+impl TcpInfo {
+    /// Returns the smoothed round-trip time.
+    pub fn rtt(&self) -> Option<Duration> {
+        self.rtt
+    }
+
+    /// Returns the variance of the round-trip time.
+    pub fn rtt_var(&self) -> Option<Duration> {
+        self.rtt_var
+    }
+
+    /// Returns the size of the congestion window, in segments.
+    pub fn congestion_window(&self) -> Option<u32> {
+        self.congestion_window
+    }
+
+    /// Returns the total number of retransmitted segments.
+    pub fn retransmits(&self) -> Option<u32> {
+        self.retransmits
+    }
+
+    /// Returns the number of segments sent but not yet acknowledged.
+    pub fn unacked(&self) -> Option<u32> {
+        self.unacked
+    }
+
+    /// Returns the pacing rate, in bytes per second.
+    ///
+    /// This is `None` if pacing is not limited.
+    pub fn pacing_rate(&self) -> Option<u64> {
+        self.pacing_rate
+    }
+
+    /// Returns the most recent delivery rate, in bytes per second.
+    pub fn delivery_rate(&self) -> Option<u64> {
+        self.delivery_rate
+    }
+}
@@ -0,0 +1,219 @@
+#[cfg(any(
+    target_os = "android",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "netbsd",
+    target_os = "tvos",
+    target_os = "watchos",
+    target_os = "windows",
+))]
+use std::time::Duration;
+
+/// Configuration of TCP keepalive, used in [`TcpStream::set_keepalive`].
+///
+/// Keepalive probes are sent after the connection has been idle for the
+/// configured time, repeated every interval until the peer responds or the
+/// number of retries is exhausted, at which point the connection is dropped.
+/// Parameters that are not set use the OS's defaults, which are usually in
+/// the order of hours for the idle time.
+///
+/// [`TcpStream::set_keepalive`]: crate::net::TcpStream::set_keepalive
+///
+/// # Examples
+///
+#[cfg_attr(all(feature = "os-poll", target_os = "linux"), doc = "```")]
+#[cfg_attr(not(all(feature = "os-poll", target_os = "linux")), doc = "```ignore")]
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use std::time::Duration;
+///
+/// use mio::net::{TcpKeepalive, TcpStream};
+///
+/// # let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+/// let stream = TcpStream::connect(listener.local_addr()?)?;
+///
+/// // Drop the connection after 60 + 10 * 5 seconds without response.
+/// let keepalive = TcpKeepalive::new()
+///     .with_time(Duration::from_secs(60))
+///     .with_interval(Duration::from_secs(10))
+///     .with_retries(5);
+/// stream.set_keepalive(Some(keepalive))?;
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct TcpKeepalive {
+    #[cfg(any(
+        target_os = "android",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "ios",
+        target_os = "linux",
+        target_os = "macos",
+        target_os = "netbsd",
+        target_os = "tvos",
+        target_os = "watchos",
+        target_os = "windows",
+    ))]
+    pub(crate) time: Option<Duration>,
+    #[cfg(any(
+        target_os = "android",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "ios",
+        target_os = "linux",
+        target_os = "macos",
+        target_os = "netbsd",
+        target_os = "tvos",
+        target_os = "watchos",
+        target_os = "windows",
+    ))]
+    pub(crate) interval: Option<Duration>,
+    #[cfg(any(
+        target_os = "android",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "ios",
+        target_os = "linux",
+        target_os = "macos",
+        target_os = "netbsd",
+        target_os = "tvos",
+        target_os = "watchos",
+    ))]
+    pub(crate) retries: Option<u32>,
+}
+
+impl TcpKeepalive {
+    /// Returns a new keepalive configuration, using the OS's defaults for all
+    /// parameters.
+    pub fn new() -> TcpKeepalive {
+        TcpKeepalive::default()
+    }
+
+    /// Set the time the connection must be idle before the first keepalive
+    /// probe is sent.
+    ///
+    /// This sets `TCP_KEEPIDLE` (`TCP_KEEPALIVE` on Apple platforms), with a
+    /// precision of seconds, rounding up to at least one second. On Windows
+    /// this is set together with the interval using `SIO_KEEPALIVE_VALS`,
+    /// with a precision of milliseconds.
+    #[cfg(any(
+        target_os = "android",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "ios",
+        target_os = "linux",
+        target_os = "macos",
+        target_os = "netbsd",
+        target_os = "tvos",
+        target_os = "watchos",
+        target_os = "windows",
+    ))]
+    pub fn with_time(self, time: Duration) -> TcpKeepalive {
+        TcpKeepalive {
+            time: Some(time),
+            ..self
+        }
+    }
+
+    /// Returns the idle time set using [`TcpKeepalive::with_time`].
+    #[cfg(any(
+        target_os = "android",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "ios",
+        target_os = "linux",
+        target_os = "macos",
+        target_os = "netbsd",
+        target_os = "tvos",
+        target_os = "watchos",
+        target_os = "windows",
+    ))]
+    pub fn time(&self) -> Option<Duration> {
+        self.time
+    }
+
+    /// Set the time between two keepalive probes.
+    ///
+    /// This sets `TCP_KEEPINTVL`, with a precision of seconds, rounding up to
+    /// at least one second. On Windows this is set together with the idle
+    /// time using `SIO_KEEPALIVE_VALS`, with a precision of milliseconds.
+    #[cfg(any(
+        target_os = "android",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "ios",
+        target_os = "linux",
+        target_os = "macos",
+        target_os = "netbsd",
+        target_os = "tvos",
+        target_os = "watchos",
+        target_os = "windows",
+    ))]
+    pub fn with_interval(self, interval: Duration) -> TcpKeepalive {
+        TcpKeepalive {
+            interval: Some(interval),
+            ..self
+        }
+    }
+
+    /// Returns the interval set using [`TcpKeepalive::with_interval`].
+    #[cfg(any(
+        target_os = "android",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "ios",
+        target_os = "linux",
+        target_os = "macos",
+        target_os = "netbsd",
+        target_os = "tvos",
+        target_os = "watchos",
+        target_os = "windows",
+    ))]
+    pub fn interval(&self) -> Option<Duration> {
+        self.interval
+    }
+
+    /// Set the number of unanswered keepalive probes before the connection
+    /// is dropped.
+    ///
+    /// This sets `TCP_KEEPCNT`. Not supported on Windows, where the number of
+    /// probes is fixed (10 since Windows Vista).
+    #[cfg(any(
+        target_os = "android",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "ios",
+        target_os = "linux",
+        target_os = "macos",
+        target_os = "netbsd",
+        target_os = "tvos",
+        target_os = "watchos",
+    ))]
+    pub fn with_retries(self, retries: u32) -> TcpKeepalive {
+        TcpKeepalive {
+            retries: Some(retries),
+            ..self
+        }
+    }
+
+    /// Returns the number of retries set using
+    /// [`TcpKeepalive::with_retries`].
+    #[cfg(any(
+        target_os = "android",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "ios",
+        target_os = "linux",
+        target_os = "macos",
+        target_os = "netbsd",
+        target_os = "tvos",
+        target_os = "watchos",
+    ))]
+    pub fn retries(&self) -> Option<u32> {
+        self.retries
+    }
+}
@@ -5,14 +5,16 @@ use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
 use std::os::wasi::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
 #[cfg(windows)]
 use std::os::windows::io::{AsRawSocket, FromRawSocket, IntoRawSocket, RawSocket};
+#[cfg(not(target_os = "wasi"))]
+use std::time::Duration;
 use std::{fmt, io};
 
 use crate::io_source::IoSource;
-use crate::net::TcpStream;
 #[cfg(unix)]
-use crate::sys::tcp::set_reuseaddr;
+use crate::net::FdReserve;
 #[cfg(not(target_os = "wasi"))]
-use crate::sys::tcp::{bind, listen, new_for_addr};
+use crate::net::TcpSocket;
+use crate::net::TcpStream;
 use crate::{event, sys, Interest, Registry, Token};
 
 /// A structure representing a socket server
@@ -55,13 +57,11 @@ impl TcpListener {
     /// 2. Set the `SO_REUSEADDR` option on the socket on Unix.
     /// 3. Bind the socket to the specified address.
     /// 4. Calls `listen` on the socket to prepare it to receive new connections.
+    ///
+    /// Use [`TcpSocket`] to configure the socket differently.
     #[cfg(not(target_os = "wasi"))]
     pub fn bind(addr: SocketAddr) -> io::Result<TcpListener> {
-        let socket = new_for_addr(addr)?;
-        #[cfg(unix)]
-        let listener = unsafe { TcpListener::from_raw_fd(socket) };
-        #[cfg(windows)]
-        let listener = unsafe { TcpListener::from_raw_socket(socket as _) };
+        let socket = TcpSocket::new_for_addr(addr)?;
 
         // On platforms with Berkeley-derived sockets, this allows to quickly
         // rebind a socket, without needing to wait for the OS to clean up the
@@ -71,11 +71,10 @@ impl TcpListener {
         // which allows “socket hijacking”, so we explicitly don't set it here.
         // https://docs.microsoft.com/en-us/windows/win32/winsock/using-so-reuseaddr-and-so-exclusiveaddruse
         #[cfg(not(windows))]
-        set_reuseaddr(&listener.inner, true)?;
+        socket.set_reuseaddr(true)?;
 
-        bind(&listener.inner, addr)?;
-        listen(&listener.inner, 1024)?;
-        Ok(listener)
+        socket.bind(addr)?;
+        socket.listen(1024)
     }
 
     /// Creates a new `TcpListener` from a standard `net::TcpListener`.
@@ -99,11 +98,66 @@ impl TcpListener {
     /// If an accepted stream is returned, the remote address of the peer is
     /// returned along with it.
     pub fn accept(&self) -> io::Result<(TcpStream, SocketAddr)> {
-        self.inner.do_io(|inner| {
+        self.inner.do_io_for(Interest::READABLE, |inner| {
             sys::tcp::accept(inner).map(|(stream, addr)| (TcpStream::from_std(stream), addr))
         })
     }
 
+    /// Accepts a new `TcpStream`, like [`TcpListener::accept`], using `reserve` to
+    /// handle running out of file descriptors.
+    ///
+    /// If accepting fails with `EMFILE` or `ENFILE` the pending connection is
+    /// accepted using the reserved file descriptor and closed immediately, so
+    /// it doesn't stay in the accept queue, and the error is returned. If no
+    /// connection was pending a `WouldBlock` error is returned instead, like
+    /// `accept` would. See [`FdReserve`] for details.
+    #[cfg(unix)]
+    #[cfg_attr(docsrs, doc(cfg(unix)))]
+    pub fn accept_reserved(&self, reserve: &mut FdReserve) -> io::Result<(TcpStream, SocketAddr)> {
+        match self.accept() {
+            Err(err) if sys::net::is_fd_exhaustion(&err) => {
+                Err(reserve.shed(err, || self.accept()))
+            }
+            res => res,
+        }
+    }
+
+    /// Accepts up to `max` pending connections, appending them to `streams`.
+    ///
+    /// This calls [`TcpListener::accept`] until the accept queue is drained, i.e.
+    /// until it returns a [`WouldBlock`] error, or `max` connections are
+    /// accepted. Returns the number of accepted connections, which is zero if
+    /// no connections are pending.
+    ///
+    /// Connections aborted by the peer before they could be accepted
+    /// (`ECONNABORTED`) are skipped. Any other error, e.g. `EMFILE` when the
+    /// process runs out of file descriptors, stops accepting and is returned
+    /// along with the number of connections accepted before it, which are kept
+    /// in `streams`.
+    ///
+    /// [`WouldBlock`]: io::ErrorKind::WouldBlock
+    pub fn accept_many(
+        &self,
+        streams: &mut Vec<(TcpStream, SocketAddr)>,
+        max: usize,
+    ) -> (usize, Option<io::Error>) {
+        let mut accepted = 0;
+        while accepted < max {
+            match self.accept() {
+                Ok(stream) => {
+                    streams.push(stream);
+                    accepted += 1;
+                }
+                Err(ref err) if err.kind() == io::ErrorKind::WouldBlock => break,
+                Err(ref err)
+                    if err.kind() == io::ErrorKind::ConnectionAborted
+                        || err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return (accepted, Some(err)),
+            }
+        }
+        (accepted, None)
+    }
+
     /// Returns the local socket address of this listener.
     pub fn local_addr(&self) -> io::Result<SocketAddr> {
         self.inner.local_addr()
@@ -126,6 +180,53 @@ impl TcpListener {
         self.inner.ttl()
     }
 
+    /// Sets the value of the `SO_LINGER` option on this socket.
+    ///
+    /// Accepted streams inherit this option, see
+    /// [`TcpStream::set_linger`] for the semantics of `linger`.
+    ///
+    /// [`TcpStream::set_linger`]: crate::net::TcpStream::set_linger
+    #[cfg(not(target_os = "wasi"))]
+    pub fn set_linger(&self, linger: Option<Duration>) -> io::Result<()> {
+        sys::net::set_linger(&*self.inner, linger)
+    }
+
+    /// Gets the value of the `SO_LINGER` option on this socket.
+    ///
+    /// For more information about this option, see [`TcpListener::set_linger`].
+    #[cfg(not(target_os = "wasi"))]
+    pub fn linger(&self) -> io::Result<Option<Duration>> {
+        sys::net::linger(&*self.inner)
+    }
+
+    /// Sets the value of the `SO_SNDBUF` option on this socket.
+    ///
+    /// The OS may round or limit the size, e.g. Linux doubles it.
+    #[cfg(not(target_os = "wasi"))]
+    pub fn set_send_buffer_size(&self, size: u32) -> io::Result<()> {
+        sys::net::set_send_buffer_size(&*self.inner, size)
+    }
+
+    /// Gets the value of the `SO_SNDBUF` option on this socket.
+    #[cfg(not(target_os = "wasi"))]
+    pub fn send_buffer_size(&self) -> io::Result<u32> {
+        sys::net::send_buffer_size(&*self.inner)
+    }
+
+    /// Sets the value of the `SO_RCVBUF` option on this socket.
+    ///
+    /// The OS may round or limit the size, e.g. Linux doubles it.
+    #[cfg(not(target_os = "wasi"))]
+    pub fn set_recv_buffer_size(&self, size: u32) -> io::Result<()> {
+        sys::net::set_recv_buffer_size(&*self.inner, size)
+    }
+
+    /// Gets the value of the `SO_RCVBUF` option on this socket.
+    #[cfg(not(target_os = "wasi"))]
+    pub fn recv_buffer_size(&self) -> io::Result<u32> {
+        sys::net::recv_buffer_size(&*self.inner)
+    }
+
     /// Get the value of the `SO_ERROR` option on this socket.
     ///
     /// This will retrieve the stored error in the underlying socket, clearing
@@ -158,6 +259,16 @@ impl event::Source for TcpListener {
     fn deregister(&mut self, registry: &Registry) -> io::Result<()> {
         self.inner.deregister(registry)
     }
+
+    fn transfer(
+        &mut self,
+        from: &Registry,
+        to: &Registry,
+        token: Token,
+        interests: Interest,
+    ) -> io::Result<()> {
+        self.inner.transfer(from, to, token, interests)
+    }
 }
 
 impl fmt::Debug for TcpListener {
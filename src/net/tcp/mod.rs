@@ -3,3 +3,39 @@ pub use self::listener::TcpListener;
 
 mod stream;
 pub use self::stream::TcpStream;
+
+#[cfg(not(target_os = "wasi"))]
+mod connector;
+#[cfg(not(target_os = "wasi"))]
+pub use self::connector::TcpConnector;
+
+#[cfg(any(
+    target_os = "android",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "tvos",
+    target_os = "watchos",
+))]
+mod info;
+#[cfg(any(
+    target_os = "android",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "tvos",
+    target_os = "watchos",
+))]
+pub use self::info::TcpInfo;
+
+#[cfg(not(target_os = "wasi"))]
+mod keepalive;
+#[cfg(not(target_os = "wasi"))]
+pub use self::keepalive::TcpKeepalive;
+
+#[cfg(not(target_os = "wasi"))]
+mod socket;
+#[cfg(not(target_os = "wasi"))]
+pub use self::socket::TcpSocket;
@@ -0,0 +1,298 @@
+use std::io;
+use std::mem;
+use std::net::{self, SocketAddr};
+#[cfg(unix)]
+use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
+#[cfg(windows)]
+use std::os::windows::io::{AsRawSocket, FromRawSocket, IntoRawSocket, RawSocket};
+
+use crate::net::{TcpListener, TcpStream};
+use crate::sys;
+
+/// A non-blocking TCP socket used to configure a stream or listener.
+///
+/// The `TcpSocket` type wraps the operating-system's socket handle. This type
+/// is used to configure the socket before establishing a connection or start
+/// listening for inbound connections, e.g. to set socket options that must be
+/// set before binding the socket.
+///
+/// The socket will be closed when the value is dropped.
+///
+/// # Examples
+///
+/// Setting up a listener.
+///
+#[cfg_attr(feature = "os-poll", doc = "```")]
+#[cfg_attr(not(feature = "os-poll"), doc = "```ignore")]
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use mio::net::{TcpListener, TcpSocket};
+///
+/// let addr = "127.0.0.1:0".parse()?;
+///
+/// let socket = TcpSocket::new_v4()?;
+/// socket.set_reuseaddr(true)?;
+/// socket.set_recv_buffer_size(64 * 1024)?;
+/// socket.bind(addr)?;
+///
+/// let listener: TcpListener = socket.listen(128)?;
+/// #     drop(listener);
+/// #     Ok(())
+/// # }
+/// ```
+///
+/// Connecting from a specific local address.
+///
+#[cfg_attr(feature = "os-poll", doc = "```")]
+#[cfg_attr(not(feature = "os-poll"), doc = "```ignore")]
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use mio::net::{TcpSocket, TcpStream};
+///
+/// # let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+/// # let addr = listener.local_addr()?;
+/// let socket = TcpSocket::new_for_addr(addr)?;
+/// socket.bind("127.0.0.1:0".parse()?)?;
+///
+/// let stream: TcpStream = socket.connect(addr)?;
+/// #     drop(stream);
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct TcpSocket {
+    sys: sys::tcp::TcpSocket,
+}
+
+impl TcpSocket {
+    /// Create a new IPv4 TCP socket.
+    ///
+    /// This calls `socket(2)`.
+    pub fn new_v4() -> io::Result<TcpSocket> {
+        sys::tcp::new_v4_socket().map(|sys| TcpSocket { sys })
+    }
+
+    /// Create a new IPv6 TCP socket.
+    ///
+    /// This calls `socket(2)`.
+    pub fn new_v6() -> io::Result<TcpSocket> {
+        sys::tcp::new_v6_socket().map(|sys| TcpSocket { sys })
+    }
+
+    /// Create a new TCP socket of the same family as `addr`, i.e. IPv4 or
+    /// IPv6.
+    pub fn new_for_addr(addr: SocketAddr) -> io::Result<TcpSocket> {
+        match addr {
+            SocketAddr::V4(_) => TcpSocket::new_v4(),
+            SocketAddr::V6(_) => TcpSocket::new_v6(),
+        }
+    }
+
+    /// Bind `addr` to the TCP socket.
+    ///
+    /// Before connecting this sets the local address used for the connection.
+    pub fn bind(&self, addr: SocketAddr) -> io::Result<()> {
+        sys::tcp::bind(self.sys, addr)
+    }
+
+    /// Connect the socket to `addr`.
+    ///
+    /// This consumes the socket and performs the connect operation. Once the
+    /// connection completes, the socket is now a non-blocking `TcpStream` and
+    /// can be used as such. See [`TcpStream::connect`] for how to determine
+    /// if the connection completed.
+    pub fn connect(self, addr: SocketAddr) -> io::Result<TcpStream> {
+        #[cfg(unix)]
+        let stream = unsafe { net::TcpStream::from_raw_fd(self.into_raw_fd()) };
+        #[cfg(windows)]
+        let stream = unsafe { net::TcpStream::from_raw_socket(self.into_raw_socket()) };
+        sys::tcp::connect(&stream, addr)?;
+        Ok(TcpStream::from_std(stream))
+    }
+
+    /// Listen for inbound connections, converting the socket to a
+    /// `TcpListener`.
+    ///
+    /// `backlog` is the maximum number of pending connections, the OS may
+    /// limit this further.
+    pub fn listen(self, backlog: u32) -> io::Result<TcpListener> {
+        #[cfg(unix)]
+        let listener = unsafe { net::TcpListener::from_raw_fd(self.into_raw_fd()) };
+        #[cfg(windows)]
+        let listener = unsafe { net::TcpListener::from_raw_socket(self.into_raw_socket()) };
+        sys::tcp::listen(&listener, backlog)?;
+        Ok(TcpListener::from_std(listener))
+    }
+
+    /// Sets the value of `SO_REUSEADDR` on this socket.
+    ///
+    /// # Notes
+    ///
+    /// On Windows this allows rebinding sockets which are actively in use,
+    /// which allows "socket hijacking", see the [Windows documentation].
+    ///
+    /// [Windows documentation]: https://docs.microsoft.com/en-us/windows/win32/winsock/using-so-reuseaddr-and-so-exclusiveaddruse
+    pub fn set_reuseaddr(&self, reuseaddr: bool) -> io::Result<()> {
+        sys::tcp::set_reuseaddr(self.sys, reuseaddr)
+    }
+
+    /// Gets the value of `SO_REUSEADDR` on this socket.
+    pub fn reuseaddr(&self) -> io::Result<bool> {
+        sys::tcp::reuseaddr(self.sys)
+    }
+
+    /// Sets the value of `SO_REUSEPORT` on this socket.
+    ///
+    /// Only supported on Unix platforms, excluding illumos and Solaris.
+    #[cfg(all(unix, not(any(target_os = "illumos", target_os = "solaris"))))]
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(all(unix, not(any(target_os = "illumos", target_os = "solaris")))))
+    )]
+    pub fn set_reuseport(&self, reuseport: bool) -> io::Result<()> {
+        sys::tcp::set_reuseport(self.sys, reuseport)
+    }
+
+    /// Gets the value of `SO_REUSEPORT` on this socket.
+    ///
+    /// Only supported on Unix platforms, excluding illumos and Solaris.
+    #[cfg(all(unix, not(any(target_os = "illumos", target_os = "solaris"))))]
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(all(unix, not(any(target_os = "illumos", target_os = "solaris")))))
+    )]
+    pub fn reuseport(&self) -> io::Result<bool> {
+        sys::tcp::reuseport(self.sys)
+    }
+
+    /// Sets the value of `SO_SNDBUF` on this socket.
+    ///
+    /// The OS may round or limit the size, e.g. Linux doubles it.
+    pub fn set_send_buffer_size(&self, size: u32) -> io::Result<()> {
+        sys::net::set_send_buffer_size(self, size)
+    }
+
+    /// Gets the value of `SO_SNDBUF` on this socket.
+    pub fn send_buffer_size(&self) -> io::Result<u32> {
+        sys::net::send_buffer_size(self)
+    }
+
+    /// Sets the value of `SO_RCVBUF` on this socket.
+    ///
+    /// The OS may round or limit the size, e.g. Linux doubles it.
+    pub fn set_recv_buffer_size(&self, size: u32) -> io::Result<()> {
+        sys::net::set_recv_buffer_size(self, size)
+    }
+
+    /// Gets the value of `SO_RCVBUF` on this socket.
+    pub fn recv_buffer_size(&self) -> io::Result<u32> {
+        sys::net::recv_buffer_size(self)
+    }
+
+    /// Sets the value of `IPV6_V6ONLY` on this socket.
+    ///
+    /// If this is set to `true` the socket is restricted to IPv6
+    /// communication only, otherwise it can also be used for IPv4 using
+    /// IPv4-mapped addresses. Only valid for IPv6 sockets, and must be set
+    /// before binding the socket.
+    pub fn set_only_v6(&self, only_v6: bool) -> io::Result<()> {
+        sys::tcp::set_only_v6(self.sys, only_v6)
+    }
+
+    /// Gets the value of `IPV6_V6ONLY` on this socket.
+    pub fn only_v6(&self) -> io::Result<bool> {
+        sys::tcp::only_v6(self.sys)
+    }
+
+    /// Bind the socket to the network interface named `interface`, using the
+    /// `SO_BINDTODEVICE` socket option. If `interface` is `None` the binding
+    /// is removed.
+    ///
+    /// This usually requires the `CAP_NET_RAW` capability.
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    #[cfg_attr(docsrs, doc(cfg(any(target_os = "android", target_os = "linux"))))]
+    pub fn bind_device(&self, interface: Option<&[u8]>) -> io::Result<()> {
+        sys::tcp::bind_device(self.sys, interface)
+    }
+
+    /// Gets the name of the network interface the socket is bound to, see
+    /// [`TcpSocket::bind_device`].
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    #[cfg_attr(docsrs, doc(cfg(any(target_os = "android", target_os = "linux"))))]
+    pub fn device(&self) -> io::Result<Option<Vec<u8>>> {
+        sys::tcp::device(self.sys)
+    }
+
+    /// Returns the local address of this socket.
+    ///
+    /// Will return an error if the socket is not bound.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        sys::tcp::local_addr(self.sys)
+    }
+}
+
+impl Drop for TcpSocket {
+    fn drop(&mut self) {
+        sys::tcp::close(self.sys);
+    }
+}
+
+#[cfg(unix)]
+impl IntoRawFd for TcpSocket {
+    fn into_raw_fd(self) -> RawFd {
+        let fd = self.sys;
+        // Avoid closing the socket.
+        mem::forget(self);
+        fd
+    }
+}
+
+#[cfg(unix)]
+impl AsRawFd for TcpSocket {
+    fn as_raw_fd(&self) -> RawFd {
+        self.sys
+    }
+}
+
+#[cfg(unix)]
+impl FromRawFd for TcpSocket {
+    /// Converts a `RawFd` to a `TcpSocket`.
+    ///
+    /// # Notes
+    ///
+    /// The caller is responsible for ensuring that the socket is in
+    /// non-blocking mode.
+    unsafe fn from_raw_fd(fd: RawFd) -> TcpSocket {
+        TcpSocket { sys: fd }
+    }
+}
+
+#[cfg(windows)]
+impl IntoRawSocket for TcpSocket {
+    fn into_raw_socket(self) -> RawSocket {
+        let socket = self.sys as RawSocket;
+        // Avoid closing the socket.
+        mem::forget(self);
+        socket
+    }
+}
+
+#[cfg(windows)]
+impl AsRawSocket for TcpSocket {
+    fn as_raw_socket(&self) -> RawSocket {
+        self.sys as RawSocket
+    }
+}
+
+#[cfg(windows)]
+impl FromRawSocket for TcpSocket {
+    /// Converts a `RawSocket` to a `TcpSocket`.
+    ///
+    /// # Notes
+    ///
+    /// The caller is responsible for ensuring that the socket is in
+    /// non-blocking mode.
+    unsafe fn from_raw_socket(socket: RawSocket) -> TcpSocket {
+        TcpSocket { sys: socket as _ }
+    }
+}
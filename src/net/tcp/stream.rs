@@ -1,4 +1,15 @@
 use std::fmt;
+#[cfg(any(
+    target_os = "android",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "tvos",
+    target_os = "watchos",
+))]
+use std::fs::File;
 use std::io::{self, IoSlice, IoSliceMut, Read, Write};
 use std::net::{self, Shutdown, SocketAddr};
 #[cfg(unix)]
@@ -7,10 +18,26 @@ use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
 use std::os::wasi::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
 #[cfg(windows)]
 use std::os::windows::io::{AsRawSocket, FromRawSocket, IntoRawSocket, RawSocket};
+#[cfg(not(target_os = "wasi"))]
+use std::time::Duration;
 
 use crate::io_source::IoSource;
+#[cfg(any(
+    target_os = "android",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "tvos",
+    target_os = "watchos",
+))]
+use crate::net::TcpInfo;
+#[cfg(not(target_os = "wasi"))]
+use crate::net::{TcpKeepalive, TcpSocket};
+#[cfg(any(target_os = "android", target_os = "linux"))]
+use crate::net::{ZeroCopyCompletions, ZeroCopyQueue};
 #[cfg(not(target_os = "wasi"))]
-use crate::sys::tcp::{connect, new_for_addr};
+use crate::sys;
 use crate::{event, Interest, Registry, Token};
 
 /// A non-blocking TCP stream between a local socket and a remote socket.
@@ -53,6 +80,8 @@ impl TcpStream {
     /// Create a new TCP stream and issue a non-blocking connect to the
     /// specified address.
     ///
+    /// Use [`TcpSocket`] to configure the socket before connecting.
+    ///
     /// # Notes
     ///
     /// The returned `TcpStream` may not be connected (and thus usable), unlike
@@ -82,13 +111,7 @@ impl TcpStream {
     /// [write interest]: Interest::WRITABLE
     #[cfg(not(target_os = "wasi"))]
     pub fn connect(addr: SocketAddr) -> io::Result<TcpStream> {
-        let socket = new_for_addr(addr)?;
-        #[cfg(unix)]
-        let stream = unsafe { TcpStream::from_raw_fd(socket) };
-        #[cfg(windows)]
-        let stream = unsafe { TcpStream::from_raw_socket(socket as _) };
-        connect(&stream.inner, addr)?;
-        Ok(stream)
+        TcpSocket::new_for_addr(addr)?.connect(addr)
     }
 
     /// Creates a new `TcpStream` from a standard `net::TcpStream`.
@@ -189,6 +212,234 @@ impl TcpStream {
         self.inner.ttl()
     }
 
+    /// Sets the TCP keepalive configuration of this socket, disabling
+    /// keepalive if `keepalive` is `None`.
+    ///
+    /// This sets `SO_KEEPALIVE` and the parameters of [`TcpKeepalive`] that
+    /// are set.
+    ///
+    /// # Notes
+    ///
+    /// On Windows make sure the stream is connected before calling this method,
+    /// by receiving an (writable) event.
+    #[cfg(not(target_os = "wasi"))]
+    pub fn set_keepalive(&self, keepalive: Option<TcpKeepalive>) -> io::Result<()> {
+        sys::tcp::set_keepalive(&self.inner, keepalive)
+    }
+
+    /// Gets the TCP keepalive configuration of this socket, `None` if
+    /// keepalive is disabled.
+    ///
+    /// On Windows the parameters can't be retrieved, so if keepalive is
+    /// enabled this returns the default [`TcpKeepalive`].
+    #[cfg(not(target_os = "wasi"))]
+    pub fn keepalive(&self) -> io::Result<Option<TcpKeepalive>> {
+        sys::tcp::keepalive(&self.inner)
+    }
+
+    /// Sets the value of the `TCP_USER_TIMEOUT` option on this socket.
+    ///
+    /// This is the maximum amount of time transmitted data may remain
+    /// unacknowledged before the connection is forcibly closed, with a
+    /// precision of milliseconds, rounding up. It also applies to keepalive
+    /// probes, detecting dead peers even when keepalive is configured with a
+    /// large number of retries. `None` (or zero) uses the system default.
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    #[cfg_attr(docsrs, doc(cfg(any(target_os = "android", target_os = "linux"))))]
+    pub fn set_user_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        sys::tcp::set_user_timeout(&self.inner, timeout)
+    }
+
+    /// Gets the value of the `TCP_USER_TIMEOUT` option on this socket.
+    ///
+    /// For more information about this option, see
+    /// [`TcpStream::set_user_timeout`].
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    #[cfg_attr(docsrs, doc(cfg(any(target_os = "android", target_os = "linux"))))]
+    pub fn user_timeout(&self) -> io::Result<Option<Duration>> {
+        sys::tcp::user_timeout(&self.inner)
+    }
+
+    /// Returns information about the connection, such as the round-trip time
+    /// and congestion window, using the `TCP_INFO` socket option.
+    ///
+    /// On Apple platforms this uses the `TCP_CONNECTION_INFO` socket option
+    /// instead. Not all platforms provide all values, see [`TcpInfo`] for
+    /// which values are missing where.
+    #[cfg(any(
+        target_os = "android",
+        target_os = "freebsd",
+        target_os = "ios",
+        target_os = "linux",
+        target_os = "macos",
+        target_os = "tvos",
+        target_os = "watchos",
+    ))]
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(any(
+            target_os = "android",
+            target_os = "freebsd",
+            target_os = "ios",
+            target_os = "linux",
+            target_os = "macos",
+            target_os = "tvos",
+            target_os = "watchos",
+        )))
+    )]
+    pub fn tcp_info(&self) -> io::Result<TcpInfo> {
+        sys::tcp::tcp_info(&self.inner)
+    }
+
+    /// Send up to `len` bytes from `file`, starting at `offset`, over the
+    /// socket using `sendfile(2)`, without copying the data through user
+    /// space.
+    ///
+    /// Returns the number of bytes sent, which may be less than `len`, e.g. if
+    /// the socket's send buffer is full. Returns zero if `offset` is at (or
+    /// beyond) the end of the file. If nothing could be sent an error of kind
+    /// `WouldBlock` is returned and, like the other I/O methods, a writable
+    /// event will be received once the socket is ready again.
+    ///
+    /// The file's offset (used by `read`) is not changed.
+    #[cfg(any(
+        target_os = "android",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "ios",
+        target_os = "linux",
+        target_os = "macos",
+        target_os = "tvos",
+        target_os = "watchos",
+    ))]
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(any(
+            target_os = "android",
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "ios",
+            target_os = "linux",
+            target_os = "macos",
+            target_os = "tvos",
+            target_os = "watchos",
+        )))
+    )]
+    pub fn send_file(&self, file: &File, offset: u64, len: usize) -> io::Result<usize> {
+        self.inner.do_io_for(Interest::WRITABLE, |inner| {
+            sys::net::send_file(inner, file, offset, len)
+        })
+    }
+
+    /// Sets the value of the `SO_ZEROCOPY` option on this socket.
+    ///
+    /// This must be enabled to use [`TcpStream::send_zerocopy`].
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    #[cfg_attr(docsrs, doc(cfg(any(target_os = "android", target_os = "linux"))))]
+    pub fn set_zerocopy(&self, zerocopy: bool) -> io::Result<()> {
+        sys::net::set_zerocopy(&*self.inner, zerocopy)
+    }
+
+    /// Gets the value of the `SO_ZEROCOPY` option on this socket.
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    #[cfg_attr(docsrs, doc(cfg(any(target_os = "android", target_os = "linux"))))]
+    pub fn zerocopy(&self) -> io::Result<bool> {
+        sys::net::zerocopy(&*self.inner)
+    }
+
+    /// Sends the next buffer in `queue` using `MSG_ZEROCOPY`, avoiding a copy
+    /// of the data into the kernel.
+    ///
+    /// Returns the number of bytes sent, zero if all buffers in the queue are
+    /// sent. If the socket's send buffer is full
+    /// only part of the buffer may be sent, the remainder is sent on the next
+    /// call. The buffers remain in the queue until
+    /// their completion is reported by [`TcpStream::zerocopy_completions`].
+    ///
+    /// Requires [`TcpStream::set_zerocopy`], otherwise an error of kind
+    /// `InvalidInput` is returned.
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    #[cfg_attr(docsrs, doc(cfg(any(target_os = "android", target_os = "linux"))))]
+    pub fn send_zerocopy<B: AsRef<[u8]>>(&self, queue: &mut ZeroCopyQueue<B>) -> io::Result<usize> {
+        queue.check_enabled(|| sys::net::zerocopy(&*self.inner))?;
+        let n = match queue.unsent_buf() {
+            Some(buf) => self.inner.do_io_for(Interest::WRITABLE, |inner| {
+                sys::net::send_zerocopy(inner, buf)
+            })?,
+            None => return Ok(0),
+        };
+        queue.sent(n);
+        Ok(n)
+    }
+
+    /// Returns an iterator over the buffers in `queue` of which the zerocopy
+    /// send completed.
+    ///
+    /// The kernel reports completions on the socket's error queue, which is
+    /// signalled as an error event, see [`Event::is_error`]. So if an error
+    /// event is received for a socket that uses zerocopy sends, read the
+    /// completions before treating it as a fatal error, e.g. using
+    /// [`TcpStream::take_error`].
+    ///
+    /// [`Event::is_error`]: crate::event::Event::is_error
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    #[cfg_attr(docsrs, doc(cfg(any(target_os = "android", target_os = "linux"))))]
+    pub fn zerocopy_completions<'a, B>(
+        &'a self,
+        queue: &'a mut ZeroCopyQueue<B>,
+    ) -> ZeroCopyCompletions<'a, B> {
+        ZeroCopyCompletions::new(&*self.inner, queue)
+    }
+
+    /// Sets the value of the `SO_LINGER` option on this socket.
+    ///
+    /// If `linger` is `Some`, closing the socket waits up to the given
+    /// duration, with a precision of seconds, for queued data to be sent. A
+    /// zero duration aborts the connection on close, discarding unsent data
+    /// and sending a `RST` to the peer instead of the usual `FIN`. `None`
+    /// (the default) lets the OS send the remaining data in the background
+    /// after close returns.
+    #[cfg(not(target_os = "wasi"))]
+    pub fn set_linger(&self, linger: Option<Duration>) -> io::Result<()> {
+        sys::net::set_linger(&*self.inner, linger)
+    }
+
+    /// Gets the value of the `SO_LINGER` option on this socket.
+    ///
+    /// For more information about this option, see [`TcpStream::set_linger`].
+    #[cfg(not(target_os = "wasi"))]
+    pub fn linger(&self) -> io::Result<Option<Duration>> {
+        sys::net::linger(&*self.inner)
+    }
+
+    /// Sets the value of the `SO_SNDBUF` option on this socket.
+    ///
+    /// The OS may round or limit the size, e.g. Linux doubles it.
+    #[cfg(not(target_os = "wasi"))]
+    pub fn set_send_buffer_size(&self, size: u32) -> io::Result<()> {
+        sys::net::set_send_buffer_size(&*self.inner, size)
+    }
+
+    /// Gets the value of the `SO_SNDBUF` option on this socket.
+    #[cfg(not(target_os = "wasi"))]
+    pub fn send_buffer_size(&self) -> io::Result<u32> {
+        sys::net::send_buffer_size(&*self.inner)
+    }
+
+    /// Sets the value of the `SO_RCVBUF` option on this socket.
+    ///
+    /// The OS may round or limit the size, e.g. Linux doubles it.
+    #[cfg(not(target_os = "wasi"))]
+    pub fn set_recv_buffer_size(&self, size: u32) -> io::Result<()> {
+        sys::net::set_recv_buffer_size(&*self.inner, size)
+    }
+
+    /// Gets the value of the `SO_RCVBUF` option on this socket.
+    #[cfg(not(target_os = "wasi"))]
+    pub fn recv_buffer_size(&self) -> io::Result<u32> {
+        sys::net::recv_buffer_size(&*self.inner)
+    }
+
     /// Get the value of the `SO_ERROR` option on this socket.
     ///
     /// This will retrieve the stored error in the underlying socket, clearing
@@ -208,6 +459,25 @@ impl TcpStream {
         self.inner.peek(buf)
     }
 
+    /// Returns the readiness of the last event received for this stream, or
+    /// `None` if it isn't known to be ready.
+    ///
+    /// The readiness for a direction is cleared once an I/O operation in that
+    /// direction returns a [`WouldBlock`] error, or when the stream is
+    /// (re)registered or deregistered. This can be used to skip I/O operations
+    /// that are known to block, without keeping track of the readiness per
+    /// [`Token`].
+    ///
+    /// I/O operations performed using [`TcpStream::try_io`] don't clear the
+    /// readiness, as their direction is unknown.
+    ///
+    /// [`WouldBlock`]: io::ErrorKind::WouldBlock
+    #[cfg(feature = "readiness-cache")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "readiness-cache")))]
+    pub fn readiness(&self) -> Option<Interest> {
+        self.inner.readiness()
+    }
+
     /// Execute an I/O operation ensuring that the socket receives more events
     /// if it hits a [`WouldBlock`] error.
     ///
@@ -217,6 +487,10 @@ impl TcpStream {
     /// ensure the user will receive events once the socket is ready again after
     /// returning a [`WouldBlock`] error.
     ///
+    /// As the direction of the I/O operation is unknown a [`WouldBlock`]
+    /// error doesn't clear the readiness returned by `TcpStream::readiness`
+    /// (with the `readiness-cache` feature enabled).
+    ///
     /// [`WouldBlock`]: io::ErrorKind::WouldBlock
     ///
     /// # Examples
@@ -269,49 +543,59 @@ impl TcpStream {
 
 impl Read for TcpStream {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
-        self.inner.do_io(|mut inner| inner.read(buf))
+        self.inner.do_read(buf.len(), |mut inner| inner.read(buf))
     }
 
     fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
-        self.inner.do_io(|mut inner| inner.read_vectored(bufs))
+        let len = bufs.iter().map(|buf| buf.len()).sum();
+        self.inner
+            .do_read(len, |mut inner| inner.read_vectored(bufs))
     }
 }
 
-impl<'a> Read for &'a TcpStream {
+impl Read for &TcpStream {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
-        self.inner.do_io(|mut inner| inner.read(buf))
+        self.inner.do_read(buf.len(), |mut inner| inner.read(buf))
     }
 
     fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
-        self.inner.do_io(|mut inner| inner.read_vectored(bufs))
+        let len = bufs.iter().map(|buf| buf.len()).sum();
+        self.inner
+            .do_read(len, |mut inner| inner.read_vectored(bufs))
     }
 }
 
 impl Write for TcpStream {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        self.inner.do_io(|mut inner| inner.write(buf))
+        self.inner
+            .do_io_for(Interest::WRITABLE, |mut inner| inner.write(buf))
     }
 
     fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
-        self.inner.do_io(|mut inner| inner.write_vectored(bufs))
+        self.inner
+            .do_io_for(Interest::WRITABLE, |mut inner| inner.write_vectored(bufs))
     }
 
     fn flush(&mut self) -> io::Result<()> {
-        self.inner.do_io(|mut inner| inner.flush())
+        self.inner
+            .do_io_for(Interest::WRITABLE, |mut inner| inner.flush())
     }
 }
 
-impl<'a> Write for &'a TcpStream {
+impl Write for &TcpStream {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        self.inner.do_io(|mut inner| inner.write(buf))
+        self.inner
+            .do_io_for(Interest::WRITABLE, |mut inner| inner.write(buf))
     }
 
     fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
-        self.inner.do_io(|mut inner| inner.write_vectored(bufs))
+        self.inner
+            .do_io_for(Interest::WRITABLE, |mut inner| inner.write_vectored(bufs))
     }
 
     fn flush(&mut self) -> io::Result<()> {
-        self.inner.do_io(|mut inner| inner.flush())
+        self.inner
+            .do_io_for(Interest::WRITABLE, |mut inner| inner.flush())
     }
 }
 
@@ -337,6 +621,16 @@ impl event::Source for TcpStream {
     fn deregister(&mut self, registry: &Registry) -> io::Result<()> {
         self.inner.deregister(registry)
     }
+
+    fn transfer(
+        &mut self,
+        from: &Registry,
+        to: &Registry,
+        token: Token,
+        interests: Interest,
+    ) -> io::Result<()> {
+        self.inner.transfer(from, to, token, interests)
+    }
 }
 
 impl fmt::Debug for TcpStream {
@@ -345,6 +639,22 @@ impl fmt::Debug for TcpStream {
     }
 }
 
+#[cfg(all(feature = "os-ext", any(target_os = "android", target_os = "linux")))]
+impl crate::sys::splice::private::Sealed for TcpStream {
+    fn splice_io<F, R>(&self, interest: Interest, f: F) -> io::Result<R>
+    where
+        F: FnOnce(RawFd) -> io::Result<R>,
+    {
+        self.inner.do_io_for(interest, |inner| f(inner.as_raw_fd()))
+    }
+}
+
+#[cfg(all(feature = "os-ext", any(target_os = "android", target_os = "linux")))]
+impl crate::unix::SpliceRead for TcpStream {}
+
+#[cfg(all(feature = "os-ext", any(target_os = "android", target_os = "linux")))]
+impl crate::unix::SpliceWrite for TcpStream {}
+
 #[cfg(unix)]
 impl IntoRawFd for TcpStream {
     fn into_raw_fd(self) -> RawFd {
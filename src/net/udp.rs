@@ -8,6 +8,8 @@
 //! [portability guidelines]: ../struct.Poll.html#portability
 
 use crate::io_source::IoSource;
+#[cfg(any(target_os = "android", target_os = "linux"))]
+use crate::net::{ZeroCopyCompletions, ZeroCopyQueue};
 use crate::{event, sys, Interest, Registry, Token};
 
 use std::fmt;
@@ -18,6 +20,7 @@ use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
 use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
 #[cfg(windows)]
 use std::os::windows::io::{AsRawSocket, FromRawSocket, IntoRawSocket, RawSocket};
+use std::time::Duration;
 
 /// A User Datagram Protocol socket.
 ///
@@ -79,7 +82,7 @@ use std::os::windows::io::{AsRawSocket, FromRawSocket, IntoRawSocket, RawSocket}
 ///                 let num_recv = echoer_socket.recv(&mut buffer)?;
 ///                 println!("echo {:?} -> {:?}", buffer, num_recv);
 ///                 buffer = [0; 9];
-///                 # drop(buffer); // Silence unused assignment warning.
+///                 # let _ = buffer; // Silence unused assignment warning.
 ///                 # return Ok(());
 ///             }
 ///             _ => unreachable!()
@@ -209,7 +212,8 @@ impl UdpSocket {
     /// # }
     /// ```
     pub fn send_to(&self, buf: &[u8], target: SocketAddr) -> io::Result<usize> {
-        self.inner.do_io(|inner| inner.send_to(buf, target))
+        self.inner
+            .do_io_for(Interest::WRITABLE, |inner| inner.send_to(buf, target))
     }
 
     /// Receives data from the socket. On success, returns the number of bytes
@@ -244,7 +248,8 @@ impl UdpSocket {
     /// # }
     /// ```
     pub fn recv_from(&self, buf: &mut [u8]) -> io::Result<(usize, SocketAddr)> {
-        self.inner.do_io(|inner| inner.recv_from(buf))
+        self.inner
+            .do_io_for(Interest::READABLE, |inner| inner.recv_from(buf))
     }
 
     /// Receives data from the socket, without removing it from the input queue.
@@ -280,13 +285,15 @@ impl UdpSocket {
     /// # }
     /// ```
     pub fn peek_from(&self, buf: &mut [u8]) -> io::Result<(usize, SocketAddr)> {
-        self.inner.do_io(|inner| inner.peek_from(buf))
+        self.inner
+            .do_io_for(Interest::READABLE, |inner| inner.peek_from(buf))
     }
 
     /// Sends data on the socket to the address previously bound via connect(). On success,
     /// returns the number of bytes written.
     pub fn send(&self, buf: &[u8]) -> io::Result<usize> {
-        self.inner.do_io(|inner| inner.send(buf))
+        self.inner
+            .do_io_for(Interest::WRITABLE, |inner| inner.send(buf))
     }
 
     /// Receives data from the socket previously bound with connect(). On success, returns
@@ -300,7 +307,8 @@ impl UdpSocket {
     /// Make sure to always use a sufficiently large buffer to hold the
     /// maximum UDP packet size, which can be up to 65536 bytes in size.
     pub fn recv(&self, buf: &mut [u8]) -> io::Result<usize> {
-        self.inner.do_io(|inner| inner.recv(buf))
+        self.inner
+            .do_io_for(Interest::READABLE, |inner| inner.recv(buf))
     }
 
     /// Receives data from the socket, without removing it from the input queue.
@@ -314,7 +322,8 @@ impl UdpSocket {
     /// Make sure to always use a sufficiently large buffer to hold the
     /// maximum UDP packet size, which can be up to 65536 bytes in size.
     pub fn peek(&self, buf: &mut [u8]) -> io::Result<usize> {
-        self.inner.do_io(|inner| inner.peek(buf))
+        self.inner
+            .do_io_for(Interest::READABLE, |inner| inner.peek(buf))
     }
 
     /// Connects the UDP socket setting the default destination for `send()`
@@ -544,6 +553,108 @@ impl UdpSocket {
         sys::udp::only_v6(&self.inner)
     }
 
+    /// Sets the value of the `SO_ZEROCOPY` option on this socket.
+    ///
+    /// This must be enabled to use [`UdpSocket::send_zerocopy`].
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    #[cfg_attr(docsrs, doc(cfg(any(target_os = "android", target_os = "linux"))))]
+    pub fn set_zerocopy(&self, zerocopy: bool) -> io::Result<()> {
+        sys::net::set_zerocopy(&*self.inner, zerocopy)
+    }
+
+    /// Gets the value of the `SO_ZEROCOPY` option on this socket.
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    #[cfg_attr(docsrs, doc(cfg(any(target_os = "android", target_os = "linux"))))]
+    pub fn zerocopy(&self) -> io::Result<bool> {
+        sys::net::zerocopy(&*self.inner)
+    }
+
+    /// Sends the next buffer in `queue` using `MSG_ZEROCOPY`, avoiding a copy
+    /// of the data into the kernel.
+    ///
+    /// Returns the number of bytes sent, zero if all buffers in the queue are
+    /// sent. Each buffer is sent as a single
+    /// datagram, the socket must be [connected]. The buffers remain in the queue until
+    /// their completion is reported by [`UdpSocket::zerocopy_completions`].
+    ///
+    /// [connected]: UdpSocket::connect
+    ///
+    /// Requires [`UdpSocket::set_zerocopy`], otherwise an error of kind
+    /// `InvalidInput` is returned.
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    #[cfg_attr(docsrs, doc(cfg(any(target_os = "android", target_os = "linux"))))]
+    pub fn send_zerocopy<B: AsRef<[u8]>>(&self, queue: &mut ZeroCopyQueue<B>) -> io::Result<usize> {
+        queue.check_enabled(|| sys::net::zerocopy(&*self.inner))?;
+        let n = match queue.unsent_buf() {
+            Some(buf) => self.inner.do_io_for(Interest::WRITABLE, |inner| {
+                sys::net::send_zerocopy(inner, buf)
+            })?,
+            None => return Ok(0),
+        };
+        queue.sent(n);
+        Ok(n)
+    }
+
+    /// Returns an iterator over the buffers in `queue` of which the zerocopy
+    /// send completed.
+    ///
+    /// The kernel reports completions on the socket's error queue, which is
+    /// signalled as an error event, see [`Event::is_error`]. So if an error
+    /// event is received for a socket that uses zerocopy sends, read the
+    /// completions before treating it as a fatal error, e.g. using
+    /// [`UdpSocket::take_error`].
+    ///
+    /// [`Event::is_error`]: crate::event::Event::is_error
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    #[cfg_attr(docsrs, doc(cfg(any(target_os = "android", target_os = "linux"))))]
+    pub fn zerocopy_completions<'a, B>(
+        &'a self,
+        queue: &'a mut ZeroCopyQueue<B>,
+    ) -> ZeroCopyCompletions<'a, B> {
+        ZeroCopyCompletions::new(&*self.inner, queue)
+    }
+
+    /// Sets the value of the `SO_LINGER` option on this socket.
+    ///
+    /// UDP has no connection to close gracefully, so this has little effect,
+    /// see [`TcpStream::set_linger`] for the semantics of `linger`.
+    ///
+    /// [`TcpStream::set_linger`]: crate::net::TcpStream::set_linger
+    pub fn set_linger(&self, linger: Option<Duration>) -> io::Result<()> {
+        sys::net::set_linger(&*self.inner, linger)
+    }
+
+    /// Gets the value of the `SO_LINGER` option on this socket.
+    ///
+    /// For more information about this option, see [`UdpSocket::set_linger`].
+    pub fn linger(&self) -> io::Result<Option<Duration>> {
+        sys::net::linger(&*self.inner)
+    }
+
+    /// Sets the value of the `SO_SNDBUF` option on this socket.
+    ///
+    /// The OS may round or limit the size, e.g. Linux doubles it.
+    pub fn set_send_buffer_size(&self, size: u32) -> io::Result<()> {
+        sys::net::set_send_buffer_size(&*self.inner, size)
+    }
+
+    /// Gets the value of the `SO_SNDBUF` option on this socket.
+    pub fn send_buffer_size(&self) -> io::Result<u32> {
+        sys::net::send_buffer_size(&*self.inner)
+    }
+
+    /// Sets the value of the `SO_RCVBUF` option on this socket.
+    ///
+    /// The OS may round or limit the size, e.g. Linux doubles it.
+    pub fn set_recv_buffer_size(&self, size: u32) -> io::Result<()> {
+        sys::net::set_recv_buffer_size(&*self.inner, size)
+    }
+
+    /// Gets the value of the `SO_RCVBUF` option on this socket.
+    pub fn recv_buffer_size(&self) -> io::Result<u32> {
+        sys::net::recv_buffer_size(&*self.inner)
+    }
+
     /// Get the value of the `SO_ERROR` option on this socket.
     ///
     /// This will retrieve the stored error in the underlying socket, clearing
@@ -553,6 +664,25 @@ impl UdpSocket {
         self.inner.take_error()
     }
 
+    /// Returns the readiness of the last event received for this socket, or
+    /// `None` if it isn't known to be ready.
+    ///
+    /// The readiness for a direction is cleared once an I/O operation in that
+    /// direction returns a [`WouldBlock`] error, or when the socket is
+    /// (re)registered or deregistered. This can be used to skip I/O operations
+    /// that are known to block, without keeping track of the readiness per
+    /// [`Token`].
+    ///
+    /// I/O operations performed using [`UdpSocket::try_io`] don't clear the
+    /// readiness, as their direction is unknown.
+    ///
+    /// [`WouldBlock`]: io::ErrorKind::WouldBlock
+    #[cfg(feature = "readiness-cache")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "readiness-cache")))]
+    pub fn readiness(&self) -> Option<Interest> {
+        self.inner.readiness()
+    }
+
     /// Execute an I/O operation ensuring that the socket receives more events
     /// if it hits a [`WouldBlock`] error.
     ///
@@ -562,6 +692,10 @@ impl UdpSocket {
     /// ensure the user will receive events once the socket is ready again after
     /// returning a [`WouldBlock`] error.
     ///
+    /// As the direction of the I/O operation is unknown a [`WouldBlock`]
+    /// error doesn't clear the readiness returned by `UdpSocket::readiness`
+    /// (with the `readiness-cache` feature enabled).
+    ///
     /// [`WouldBlock`]: io::ErrorKind::WouldBlock
     ///
     /// # Examples
@@ -634,6 +768,16 @@ impl event::Source for UdpSocket {
     fn deregister(&mut self, registry: &Registry) -> io::Result<()> {
         self.inner.deregister(registry)
     }
+
+    fn transfer(
+        &mut self,
+        from: &Registry,
+        to: &Registry,
+        token: Token,
+        interests: Interest,
+    ) -> io::Result<()> {
+        self.inner.transfer(from, to, token, interests)
+    }
 }
 
 impl fmt::Debug for UdpSocket {
@@ -5,6 +5,7 @@ use std::net::Shutdown;
 use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
 use std::os::unix::net;
 use std::path::Path;
+use std::time::Duration;
 use std::{fmt, io};
 
 /// A Unix datagram socket.
@@ -70,22 +71,25 @@ impl UnixDatagram {
     /// On success, returns the number of bytes read and the address from
     /// whence the data came.
     pub fn recv_from(&self, buf: &mut [u8]) -> io::Result<(usize, sys::SocketAddr)> {
-        self.inner
-            .do_io(|inner| sys::uds::datagram::recv_from(inner, buf))
+        self.inner.do_io_for(Interest::READABLE, |inner| {
+            sys::uds::datagram::recv_from(inner, buf)
+        })
     }
 
     /// Receives data from the socket.
     ///
     /// On success, returns the number of bytes read.
     pub fn recv(&self, buf: &mut [u8]) -> io::Result<usize> {
-        self.inner.do_io(|inner| inner.recv(buf))
+        self.inner
+            .do_io_for(Interest::READABLE, |inner| inner.recv(buf))
     }
 
     /// Sends data on the socket to the specified address.
     ///
     /// On success, returns the number of bytes written.
     pub fn send_to<P: AsRef<Path>>(&self, buf: &[u8], path: P) -> io::Result<usize> {
-        self.inner.do_io(|inner| inner.send_to(buf, path))
+        self.inner
+            .do_io_for(Interest::WRITABLE, |inner| inner.send_to(buf, path))
     }
 
     /// Sends data on the socket to the socket's peer.
@@ -95,7 +99,49 @@ impl UnixDatagram {
     ///
     /// On success, returns the number of bytes written.
     pub fn send(&self, buf: &[u8]) -> io::Result<usize> {
-        self.inner.do_io(|inner| inner.send(buf))
+        self.inner
+            .do_io_for(Interest::WRITABLE, |inner| inner.send(buf))
+    }
+
+    /// Sets the value of the `SO_LINGER` option on this socket.
+    ///
+    /// Most OSs ignore this option for Unix sockets, see
+    /// [`TcpStream::set_linger`] for the semantics of `linger`.
+    ///
+    /// [`TcpStream::set_linger`]: crate::net::TcpStream::set_linger
+    pub fn set_linger(&self, linger: Option<Duration>) -> io::Result<()> {
+        sys::net::set_linger(&*self.inner, linger)
+    }
+
+    /// Gets the value of the `SO_LINGER` option on this socket.
+    ///
+    /// For more information about this option, see [`UnixDatagram::set_linger`].
+    pub fn linger(&self) -> io::Result<Option<Duration>> {
+        sys::net::linger(&*self.inner)
+    }
+
+    /// Sets the value of the `SO_SNDBUF` option on this socket.
+    ///
+    /// The OS may round or limit the size, e.g. Linux doubles it.
+    pub fn set_send_buffer_size(&self, size: u32) -> io::Result<()> {
+        sys::net::set_send_buffer_size(&*self.inner, size)
+    }
+
+    /// Gets the value of the `SO_SNDBUF` option on this socket.
+    pub fn send_buffer_size(&self) -> io::Result<u32> {
+        sys::net::send_buffer_size(&*self.inner)
+    }
+
+    /// Sets the value of the `SO_RCVBUF` option on this socket.
+    ///
+    /// The OS may round or limit the size, e.g. Linux doubles it.
+    pub fn set_recv_buffer_size(&self, size: u32) -> io::Result<()> {
+        sys::net::set_recv_buffer_size(&*self.inner, size)
+    }
+
+    /// Gets the value of the `SO_RCVBUF` option on this socket.
+    pub fn recv_buffer_size(&self) -> io::Result<u32> {
+        sys::net::recv_buffer_size(&*self.inner)
     }
 
     /// Returns the value of the `SO_ERROR` option.
@@ -203,6 +249,16 @@ impl event::Source for UnixDatagram {
     fn deregister(&mut self, registry: &Registry) -> io::Result<()> {
         self.inner.deregister(registry)
     }
+
+    fn transfer(
+        &mut self,
+        from: &Registry,
+        to: &Registry,
+        token: Token,
+        interests: Interest,
+    ) -> io::Result<()> {
+        self.inner.transfer(from, to, token, interests)
+    }
 }
 
 impl fmt::Debug for UnixDatagram {
@@ -1,5 +1,5 @@
 use crate::io_source::IoSource;
-use crate::net::{SocketAddr, UnixStream};
+use crate::net::{FdReserve, SocketAddr, UnixStream};
 use crate::{event, sys, Interest, Registry, Token};
 
 use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
@@ -38,6 +38,59 @@ impl UnixListener {
         sys::uds::listener::accept(&self.inner)
     }
 
+    /// Accepts a new `UnixStream`, like [`UnixListener::accept`], using `reserve` to
+    /// handle running out of file descriptors.
+    ///
+    /// If accepting fails with `EMFILE` or `ENFILE` the pending connection is
+    /// accepted using the reserved file descriptor and closed immediately, so
+    /// it doesn't stay in the accept queue, and the error is returned. If no
+    /// connection was pending a `WouldBlock` error is returned instead, like
+    /// `accept` would. See [`FdReserve`] for details.
+    pub fn accept_reserved(&self, reserve: &mut FdReserve) -> io::Result<(UnixStream, SocketAddr)> {
+        match self.accept() {
+            Err(err) if sys::net::is_fd_exhaustion(&err) => {
+                Err(reserve.shed(err, || self.accept()))
+            }
+            res => res,
+        }
+    }
+
+    /// Accepts up to `max` pending connections, appending them to `streams`.
+    ///
+    /// This calls [`UnixListener::accept`] until the accept queue is drained, i.e.
+    /// until it returns a [`WouldBlock`] error, or `max` connections are
+    /// accepted. Returns the number of accepted connections, which is zero if
+    /// no connections are pending.
+    ///
+    /// Connections aborted by the peer before they could be accepted
+    /// (`ECONNABORTED`) are skipped. Any other error, e.g. `EMFILE` when the
+    /// process runs out of file descriptors, stops accepting and is returned
+    /// along with the number of connections accepted before it, which are kept
+    /// in `streams`.
+    ///
+    /// [`WouldBlock`]: io::ErrorKind::WouldBlock
+    pub fn accept_many(
+        &self,
+        streams: &mut Vec<(UnixStream, SocketAddr)>,
+        max: usize,
+    ) -> (usize, Option<io::Error>) {
+        let mut accepted = 0;
+        while accepted < max {
+            match self.accept() {
+                Ok(stream) => {
+                    streams.push(stream);
+                    accepted += 1;
+                }
+                Err(ref err) if err.kind() == io::ErrorKind::WouldBlock => break,
+                Err(ref err)
+                    if err.kind() == io::ErrorKind::ConnectionAborted
+                        || err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return (accepted, Some(err)),
+            }
+        }
+        (accepted, None)
+    }
+
     /// Returns the local socket address of this listener.
     pub fn local_addr(&self) -> io::Result<sys::SocketAddr> {
         sys::uds::listener::local_addr(&self.inner)
@@ -71,6 +124,16 @@ impl event::Source for UnixListener {
     fn deregister(&mut self, registry: &Registry) -> io::Result<()> {
         self.inner.deregister(registry)
     }
+
+    fn transfer(
+        &mut self,
+        from: &Registry,
+        to: &Registry,
+        token: Token,
+        interests: Interest,
+    ) -> io::Result<()> {
+        self.inner.transfer(from, to, token, interests)
+    }
 }
 
 impl fmt::Debug for UnixListener {
@@ -2,11 +2,23 @@ use crate::io_source::IoSource;
 use crate::{event, sys, Interest, Registry, Token};
 
 use std::fmt;
+#[cfg(any(
+    target_os = "android",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "tvos",
+    target_os = "watchos",
+))]
+use std::fs::File;
 use std::io::{self, IoSlice, IoSliceMut, Read, Write};
 use std::net::Shutdown;
 use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
 use std::os::unix::net;
 use std::path::Path;
+use std::time::Duration;
 
 /// A non-blocking Unix stream socket.
 pub struct UnixStream {
@@ -59,6 +71,87 @@ impl UnixStream {
         sys::uds::stream::peer_addr(&self.inner)
     }
 
+    /// Send up to `len` bytes from `file`, starting at `offset`, over the
+    /// socket using `sendfile(2)`, without copying the data through user
+    /// space.
+    ///
+    /// Returns the number of bytes sent, which may be less than `len`, e.g. if
+    /// the socket's send buffer is full. Returns zero if `offset` is at (or
+    /// beyond) the end of the file. If nothing could be sent an error of kind
+    /// `WouldBlock` is returned and, like the other I/O methods, a writable
+    /// event will be received once the socket is ready again.
+    ///
+    /// The file's offset (used by `read`) is not changed.
+    #[cfg(any(
+        target_os = "android",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "ios",
+        target_os = "linux",
+        target_os = "macos",
+        target_os = "tvos",
+        target_os = "watchos",
+    ))]
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(any(
+            target_os = "android",
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "ios",
+            target_os = "linux",
+            target_os = "macos",
+            target_os = "tvos",
+            target_os = "watchos",
+        )))
+    )]
+    pub fn send_file(&self, file: &File, offset: u64, len: usize) -> io::Result<usize> {
+        self.inner.do_io_for(Interest::WRITABLE, |inner| {
+            sys::net::send_file(inner, file, offset, len)
+        })
+    }
+
+    /// Sets the value of the `SO_LINGER` option on this socket.
+    ///
+    /// Most OSs ignore this option for Unix sockets, see
+    /// [`TcpStream::set_linger`] for the semantics of `linger`.
+    ///
+    /// [`TcpStream::set_linger`]: crate::net::TcpStream::set_linger
+    pub fn set_linger(&self, linger: Option<Duration>) -> io::Result<()> {
+        sys::net::set_linger(&*self.inner, linger)
+    }
+
+    /// Gets the value of the `SO_LINGER` option on this socket.
+    ///
+    /// For more information about this option, see [`UnixStream::set_linger`].
+    pub fn linger(&self) -> io::Result<Option<Duration>> {
+        sys::net::linger(&*self.inner)
+    }
+
+    /// Sets the value of the `SO_SNDBUF` option on this socket.
+    ///
+    /// The OS may round or limit the size, e.g. Linux doubles it.
+    pub fn set_send_buffer_size(&self, size: u32) -> io::Result<()> {
+        sys::net::set_send_buffer_size(&*self.inner, size)
+    }
+
+    /// Gets the value of the `SO_SNDBUF` option on this socket.
+    pub fn send_buffer_size(&self) -> io::Result<u32> {
+        sys::net::send_buffer_size(&*self.inner)
+    }
+
+    /// Sets the value of the `SO_RCVBUF` option on this socket.
+    ///
+    /// The OS may round or limit the size, e.g. Linux doubles it.
+    pub fn set_recv_buffer_size(&self, size: u32) -> io::Result<()> {
+        sys::net::set_recv_buffer_size(&*self.inner, size)
+    }
+
+    /// Gets the value of the `SO_RCVBUF` option on this socket.
+    pub fn recv_buffer_size(&self) -> io::Result<u32> {
+        sys::net::recv_buffer_size(&*self.inner)
+    }
+
     /// Returns the value of the `SO_ERROR` option.
     pub fn take_error(&self) -> io::Result<Option<io::Error>> {
         self.inner.take_error()
@@ -144,49 +237,59 @@ impl UnixStream {
 
 impl Read for UnixStream {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
-        self.inner.do_io(|mut inner| inner.read(buf))
+        self.inner.do_read(buf.len(), |mut inner| inner.read(buf))
     }
 
     fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
-        self.inner.do_io(|mut inner| inner.read_vectored(bufs))
+        let len = bufs.iter().map(|buf| buf.len()).sum();
+        self.inner
+            .do_read(len, |mut inner| inner.read_vectored(bufs))
     }
 }
 
-impl<'a> Read for &'a UnixStream {
+impl Read for &UnixStream {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
-        self.inner.do_io(|mut inner| inner.read(buf))
+        self.inner.do_read(buf.len(), |mut inner| inner.read(buf))
     }
 
     fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
-        self.inner.do_io(|mut inner| inner.read_vectored(bufs))
+        let len = bufs.iter().map(|buf| buf.len()).sum();
+        self.inner
+            .do_read(len, |mut inner| inner.read_vectored(bufs))
     }
 }
 
 impl Write for UnixStream {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        self.inner.do_io(|mut inner| inner.write(buf))
+        self.inner
+            .do_io_for(Interest::WRITABLE, |mut inner| inner.write(buf))
     }
 
     fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
-        self.inner.do_io(|mut inner| inner.write_vectored(bufs))
+        self.inner
+            .do_io_for(Interest::WRITABLE, |mut inner| inner.write_vectored(bufs))
     }
 
     fn flush(&mut self) -> io::Result<()> {
-        self.inner.do_io(|mut inner| inner.flush())
+        self.inner
+            .do_io_for(Interest::WRITABLE, |mut inner| inner.flush())
     }
 }
 
-impl<'a> Write for &'a UnixStream {
+impl Write for &UnixStream {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        self.inner.do_io(|mut inner| inner.write(buf))
+        self.inner
+            .do_io_for(Interest::WRITABLE, |mut inner| inner.write(buf))
     }
 
     fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
-        self.inner.do_io(|mut inner| inner.write_vectored(bufs))
+        self.inner
+            .do_io_for(Interest::WRITABLE, |mut inner| inner.write_vectored(bufs))
     }
 
     fn flush(&mut self) -> io::Result<()> {
-        self.inner.do_io(|mut inner| inner.flush())
+        self.inner
+            .do_io_for(Interest::WRITABLE, |mut inner| inner.flush())
     }
 }
 
@@ -212,6 +315,16 @@ impl event::Source for UnixStream {
     fn deregister(&mut self, registry: &Registry) -> io::Result<()> {
         self.inner.deregister(registry)
     }
+
+    fn transfer(
+        &mut self,
+        from: &Registry,
+        to: &Registry,
+        token: Token,
+        interests: Interest,
+    ) -> io::Result<()> {
+        self.inner.transfer(from, to, token, interests)
+    }
 }
 
 impl fmt::Debug for UnixStream {
@@ -220,6 +333,22 @@ impl fmt::Debug for UnixStream {
     }
 }
 
+#[cfg(all(feature = "os-ext", any(target_os = "android", target_os = "linux")))]
+impl crate::sys::splice::private::Sealed for UnixStream {
+    fn splice_io<F, R>(&self, interest: Interest, f: F) -> io::Result<R>
+    where
+        F: FnOnce(RawFd) -> io::Result<R>,
+    {
+        self.inner.do_io_for(interest, |inner| f(inner.as_raw_fd()))
+    }
+}
+
+#[cfg(all(feature = "os-ext", any(target_os = "android", target_os = "linux")))]
+impl crate::unix::SpliceRead for UnixStream {}
+
+#[cfg(all(feature = "os-ext", any(target_os = "android", target_os = "linux")))]
+impl crate::unix::SpliceWrite for UnixStream {}
+
 impl IntoRawFd for UnixStream {
     fn into_raw_fd(self) -> RawFd {
         self.inner.into_inner().into_raw_fd()
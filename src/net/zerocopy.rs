@@ -0,0 +1,299 @@
+use std::collections::VecDeque;
+use std::io;
+use std::os::unix::io::AsRawFd;
+
+use crate::sys;
+
+/// Buffers sent (or to be sent) using `MSG_ZEROCOPY`.
+///
+/// With zerocopy sends the kernel references the buffers' memory until the
+/// data is transmitted, rather than copying it. The queue owns the buffers
+/// for that period and hands them back once the kernel reports their
+/// completion, see [`TcpStream::zerocopy_completions`].
+///
+/// Buffers are sent in the order they're [pushed], using
+/// [`TcpStream::send_zerocopy`] or [`UdpSocket::send_zerocopy`]. A queue
+/// must be used with a single socket, and all zerocopy sends on that socket
+/// must go through the queue, as completions are matched to buffers using a
+/// per-socket counter maintained by the kernel.
+///
+/// [`TcpStream::zerocopy_completions`]: crate::net::TcpStream::zerocopy_completions
+/// [pushed]: ZeroCopyQueue::push
+/// [`TcpStream::send_zerocopy`]: crate::net::TcpStream::send_zerocopy
+/// [`UdpSocket::send_zerocopy`]: crate::net::UdpSocket::send_zerocopy
+///
+/// # Examples
+///
+#[cfg_attr(feature = "os-poll", doc = "```")]
+#[cfg_attr(not(feature = "os-poll"), doc = "```ignore")]
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use mio::net::{TcpStream, ZeroCopyQueue};
+///
+/// # let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+/// let stream = TcpStream::connect(listener.local_addr()?)?;
+/// # let _peer = listener.accept()?;
+/// stream.set_zerocopy(true)?;
+///
+/// let mut queue = ZeroCopyQueue::new();
+/// queue.push(vec![0; 64 * 1024]);
+///
+/// // Once the stream is writable.
+/// match stream.send_zerocopy(&mut queue) {
+///     Ok(n) => println!("sent {} bytes", n),
+///     // Try again once the stream is writable.
+///     Err(ref err) if err.kind() == std::io::ErrorKind::WouldBlock => {}
+///     Err(err) => return Err(err.into()),
+/// }
+///
+/// // Once an event with `Event::is_error` is received.
+/// for completion in stream.zerocopy_completions(&mut queue) {
+///     let buf: Vec<u8> = completion?.into_buffer();
+///     // The buffer can be reused.
+/// #   drop(buf);
+/// }
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct ZeroCopyQueue<B> {
+    entries: VecDeque<Entry<B>>,
+    /// Index in `entries` of the first buffer that is not completely sent.
+    unsent: usize,
+    /// Number of bytes of `entries[unsent]` already sent.
+    offset: usize,
+    /// Id the kernel assigns to the next zerocopy send.
+    next_id: u32,
+    /// Whether `SO_ZEROCOPY` was found to be enabled on the socket.
+    enabled: bool,
+}
+
+#[derive(Debug)]
+struct Entry<B> {
+    buf: B,
+    /// Ids of the first and last sends of (part of) this buffer.
+    first_id: u32,
+    last_id: u32,
+    /// Number of sends not yet completed.
+    outstanding: u32,
+    copied: bool,
+}
+
+impl<B> ZeroCopyQueue<B> {
+    /// Create an empty queue.
+    pub fn new() -> ZeroCopyQueue<B> {
+        ZeroCopyQueue {
+            entries: VecDeque::new(),
+            unsent: 0,
+            offset: 0,
+            next_id: 0,
+            enabled: false,
+        }
+    }
+
+    /// Add `buf` to the end of the queue, to be sent after all previously
+    /// pushed buffers.
+    pub fn push(&mut self, buf: B) {
+        self.entries.push_back(Entry {
+            buf,
+            first_id: 0,
+            last_id: 0,
+            outstanding: 0,
+            copied: false,
+        });
+    }
+
+    /// Returns the number of buffers in the queue, i.e. buffers that are not
+    /// sent or not yet completed.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if all buffers are sent and completed.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns `true` if the queue holds buffers that are not (completely)
+    /// sent.
+    pub fn has_unsent(&self) -> bool {
+        self.unsent < self.entries.len()
+    }
+
+    /// Applies a completion notification for sends `lo..=hi`.
+    fn complete(&mut self, lo: u32, hi: u32, copied: bool) {
+        let range = i64::from(hi.wrapping_sub(lo));
+        for entry in self.entries.iter_mut().take(self.unsent + 1) {
+            if entry.outstanding == 0 {
+                continue;
+            }
+            // Relative to `lo`, the ids are close together so this handles the
+            // ids wrapping around.
+            let first = i64::from(entry.first_id.wrapping_sub(lo) as i32);
+            let last = i64::from(entry.last_id.wrapping_sub(lo) as i32);
+            let overlap = last.min(range) - first.max(0) + 1;
+            if overlap > 0 {
+                entry.outstanding = entry.outstanding.saturating_sub(overlap as u32);
+                entry.copied |= copied;
+            }
+        }
+    }
+
+    /// Removes the first buffer if it's completely sent and completed.
+    fn pop_completed(&mut self) -> Option<ZeroCopyCompletion<B>> {
+        match self.entries.front() {
+            Some(entry) if self.unsent > 0 && entry.outstanding == 0 => {
+                self.unsent -= 1;
+                self.entries.pop_front().map(|entry| ZeroCopyCompletion {
+                    buf: entry.buf,
+                    copied: entry.copied,
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+impl<B: AsRef<[u8]>> ZeroCopyQueue<B> {
+    /// Returns an error if `SO_ZEROCOPY` isn't enabled on the socket, as the
+    /// kernel then silently copies the data and never reports completions.
+    /// `zerocopy` is only called until it returns `true`, as the queue is
+    /// used with a single socket.
+    pub(crate) fn check_enabled<F>(&mut self, zerocopy: F) -> io::Result<()>
+    where
+        F: FnOnce() -> io::Result<bool>,
+    {
+        if !self.enabled {
+            if !zerocopy()? {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "`SO_ZEROCOPY` is not enabled on the socket",
+                ));
+            }
+            self.enabled = true;
+        }
+        Ok(())
+    }
+
+    /// Returns the unsent part of the first buffer not completely sent, if
+    /// any.
+    pub(crate) fn unsent_buf(&mut self) -> Option<&[u8]> {
+        // Empty buffers are never sent and can be completed right away.
+        while let Some(entry) = self.entries.get(self.unsent) {
+            if !entry.buf.as_ref().is_empty() {
+                break;
+            }
+            self.unsent += 1;
+        }
+        let offset = self.offset;
+        self.entries
+            .get(self.unsent)
+            .map(|entry| &entry.buf.as_ref()[offset..])
+    }
+
+    /// Marks `n` bytes of the buffer returned by `unsent_buf` as sent.
+    pub(crate) fn sent(&mut self, n: usize) {
+        let id = self.next_id;
+        self.next_id = self.next_id.wrapping_add(1);
+        let entry = &mut self.entries[self.unsent];
+        if self.offset == 0 {
+            entry.first_id = id;
+        }
+        entry.last_id = id;
+        entry.outstanding += 1;
+        self.offset += n;
+        if self.offset >= entry.buf.as_ref().len() {
+            self.unsent += 1;
+            self.offset = 0;
+        }
+    }
+}
+
+impl<B> Default for ZeroCopyQueue<B> {
+    fn default() -> ZeroCopyQueue<B> {
+        ZeroCopyQueue::new()
+    }
+}
+
+/// A buffer of which the zerocopy send completed, returned by
+/// [`ZeroCopyCompletions`].
+#[derive(Debug)]
+pub struct ZeroCopyCompletion<B> {
+    buf: B,
+    copied: bool,
+}
+
+impl<B> ZeroCopyCompletion<B> {
+    /// Returns a reference to the buffer.
+    pub fn buffer(&self) -> &B {
+        &self.buf
+    }
+
+    /// Returns the buffer, which is no longer referenced by the kernel.
+    pub fn into_buffer(self) -> B {
+        self.buf
+    }
+
+    /// Returns `true` if the kernel fell back to copying (part of) the
+    /// buffer, e.g. because the destination is a loopback address or the
+    /// network device doesn't support scatter-gather I/O.
+    ///
+    /// If this happens consistently it's often cheaper to not use zerocopy
+    /// sends at all.
+    pub fn copied(&self) -> bool {
+        self.copied
+    }
+}
+
+/// Iterator over the completed zerocopy sends of a socket.
+///
+/// Reads completion notifications from the socket's error queue
+/// (`MSG_ERRQUEUE`) and returns the buffers of which all sends completed, in
+/// the order they were pushed into the [`ZeroCopyQueue`]. The iterator ends
+/// once the error queue is empty. Other errors in the error queue are
+/// returned as `Err`.
+///
+/// Created by [`TcpStream::zerocopy_completions`] and
+/// [`UdpSocket::zerocopy_completions`].
+///
+/// [`TcpStream::zerocopy_completions`]: crate::net::TcpStream::zerocopy_completions
+/// [`UdpSocket::zerocopy_completions`]: crate::net::UdpSocket::zerocopy_completions
+pub struct ZeroCopyCompletions<'a, B> {
+    socket: &'a dyn AsRawFd,
+    queue: &'a mut ZeroCopyQueue<B>,
+}
+
+impl<'a, B> ZeroCopyCompletions<'a, B> {
+    pub(crate) fn new(
+        socket: &'a dyn AsRawFd,
+        queue: &'a mut ZeroCopyQueue<B>,
+    ) -> ZeroCopyCompletions<'a, B> {
+        ZeroCopyCompletions { socket, queue }
+    }
+}
+
+impl<'a, B> Iterator for ZeroCopyCompletions<'a, B> {
+    type Item = io::Result<ZeroCopyCompletion<B>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(completion) = self.queue.pop_completed() {
+                return Some(Ok(completion));
+            }
+            match sys::net::recv_zerocopy(self.socket.as_raw_fd()) {
+                Ok((lo, hi, copied)) => self.queue.complete(lo, hi, copied),
+                Err(ref err) if err.kind() == io::ErrorKind::WouldBlock => return None,
+                Err(err) => return Some(Err(err)),
+            }
+        }
+    }
+}
+
+impl<'a, B: std::fmt::Debug> std::fmt::Debug for ZeroCopyCompletions<'a, B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ZeroCopyCompletions")
+            .field("socket", &self.socket.as_raw_fd())
+            .field("queue", &self.queue)
+            .finish()
+    }
+}
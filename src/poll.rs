@@ -1,8 +1,21 @@
+#[cfg(all(
+    any(feature = "net", all(unix, feature = "os-ext")),
+    any(
+        all(debug_assertions, feature = "drain-check"),
+        feature = "readiness-cache"
+    )
+))]
+use crate::io_source::SharedStates;
 use crate::{event, sys, Events, Interest, Token};
 use log::trace;
+#[cfg(all(unix, feature = "registry-introspection"))]
+use std::collections::BTreeMap;
 #[cfg(unix)]
 use std::os::unix::io::{AsRawFd, RawFd};
-use std::time::Duration;
+use std::sync::Arc;
+#[cfg(all(unix, feature = "registry-introspection"))]
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
 use std::{fmt, io};
 
 /// Polls for readiness events on all registered values.
@@ -248,60 +261,254 @@ use std::{fmt, io};
 /// [`Poll::poll`]: struct.Poll.html#method.poll
 pub struct Poll {
     registry: Registry,
+    /// Duration to spin for in `poll` before blocking, see
+    /// `Poll::set_busy_poll`.
+    busy_poll: Option<Duration>,
 }
 
 /// Registers I/O resources.
 pub struct Registry {
     selector: sys::Selector,
+    #[cfg(all(unix, feature = "registry-introspection"))]
+    registrations: Registrations,
+    /// State of the registered `IoSource`s, updated in `Poll::poll`.
+    #[cfg(all(
+        any(feature = "net", all(unix, feature = "os-ext")),
+        any(
+            all(debug_assertions, feature = "drain-check"),
+            feature = "readiness-cache"
+        )
+    ))]
+    sources: Arc<SharedStates>,
+    /// Events of the in-process sources, see the `mem` module.
+    #[cfg(feature = "os-poll")]
+    mem: Arc<sys::mem::Queue>,
 }
 
-impl Poll {
-    cfg_os_poll! {
-        /// Return a new `Poll` handle.
-        ///
-        /// This function will make a syscall to the operating system to create
-        /// the system selector. If this syscall fails, `Poll::new` will return
-        /// with the error.
-        ///
-        /// close-on-exec flag is set on the file descriptors used by the selector to prevent
-        /// leaking it to executed processes. However, on some systems such as
-        /// old Linux systems that don't support `epoll_create1` syscall it is done
-        /// non-atomically, so a separate thread executing in parallel to this
-        /// function may accidentally leak the file descriptor if it executes a
-        /// new process before this function returns.
-        ///
-        /// See [struct] level docs for more details.
-        ///
-        /// [struct]: struct.Poll.html
-        ///
-        /// # Examples
-        ///
-        /// ```
-        /// # use std::error::Error;
-        /// # fn main() -> Result<(), Box<dyn Error>> {
-        /// use mio::{Poll, Events};
-        /// use std::time::Duration;
-        ///
-        /// let mut poll = match Poll::new() {
-        ///     Ok(poll) => poll,
-        ///     Err(e) => panic!("failed to create Poll instance; err={:?}", e),
-        /// };
-        ///
-        /// // Create a structure to receive polled events
-        /// let mut events = Events::with_capacity(1024);
-        ///
-        /// // Wait for events, but none will be received because no
-        /// // `event::Source`s have been registered with this `Poll` instance.
-        /// poll.poll(&mut events, Some(Duration::from_millis(500)))?;
-        /// assert!(events.is_empty());
-        /// #     Ok(())
-        /// # }
-        /// ```
-        pub fn new() -> io::Result<Poll> {
-            sys::Selector::new().map(|selector| Poll {
-                registry: Registry { selector },
-            })
+/// A file descriptor registered with a [`Registry`].
+///
+/// Returned by [`Registry::registrations`].
+#[cfg(all(unix, feature = "registry-introspection"))]
+#[cfg_attr(docsrs, doc(cfg(all(unix, feature = "registry-introspection"))))]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Registration {
+    fd: RawFd,
+    token: Token,
+    interests: Interest,
+}
+
+/// Error returned when registering, reregistering or deregistering a file
+/// descriptor fails because of a misuse detected by Mio itself.
+///
+/// Errors returned by the OS are returned as is by the [`Registry`] methods,
+/// so [`io::Error::raw_os_error`] keeps working. Their context, i.e. the
+/// operation, file descriptor, token and interests, is only logged at the
+/// debug level.
+///
+/// The errors for misuses detected by Mio itself wrap this type in an
+/// [`io::Error`], it can be retrieved using [`io::Error::get_ref`] and
+/// downcasting. These are:
+///  * using an I/O source with a different `Registry` than the one it's
+///    registered with, only detected for Mio's own types and only when debug
+///    assertions are enabled,
+///  * registering a file descriptor that is already registered, when the
+///    `registry-introspection` feature is enabled. The error names the token
+///    the file descriptor is registered with.
+///
+/// The underlying error is available as [`Error::source`].
+///
+/// This type is only available on Unix, on other platforms the errors are
+/// returned without this context.
+///
+/// [`Error::source`]: std::error::Error::source
+///
+/// Common misuses of the `Registry` map to the following kinds:
+///  * registering a file descriptor twice: [`io::ErrorKind::AlreadyExists`],
+///  * reregistering or deregistering a file descriptor that isn't registered:
+///    [`io::ErrorKind::NotFound`],
+///  * using an I/O source with a different `Registry` than the one it's
+///    registered with: [`io::ErrorKind::InvalidInput`], if detected, otherwise
+///    it shows up as one of the kinds above.
+///
+/// # Examples
+///
+#[cfg_attr(all(feature = "os-poll", feature = "os-ext"), doc = "```")]
+#[cfg_attr(not(all(feature = "os-poll", feature = "os-ext")), doc = "```ignore")]
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use std::io;
+/// use std::os::unix::io::AsRawFd;
+///
+/// use mio::unix::SourceFd;
+/// use mio::{Interest, Poll, RegistrationError, Token};
+///
+/// let poll = Poll::new()?;
+/// let socket = std::net::UdpSocket::bind("127.0.0.1:0")?;
+/// let fd = socket.as_raw_fd();
+///
+/// poll.registry().register(&mut SourceFd(&fd), Token(0), Interest::READABLE)?;
+/// let err = poll.registry()
+///     .register(&mut SourceFd(&fd), Token(1), Interest::READABLE)
+///     .unwrap_err();
+/// assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+///
+/// match err.get_ref().and_then(|err| err.downcast_ref::<RegistrationError>()) {
+///     // Detected by Mio, with the context of the failed operation.
+///     Some(context) => {
+///         assert_eq!(context.fd(), fd);
+///         assert_eq!(context.token(), Some(Token(1)));
+///     }
+///     // Returned by the OS, i.e. `EEXIST`.
+///     None => assert!(err.raw_os_error().is_some()),
+/// }
+/// #     Ok(())
+/// # }
+/// ```
+#[cfg(all(unix, any(feature = "os-poll", feature = "os-ext", feature = "net")))]
+#[cfg_attr(docsrs, doc(cfg(unix)))]
+#[derive(Debug)]
+pub struct RegistrationError {
+    operation: Operation,
+    fd: RawFd,
+    token: Option<Token>,
+    interests: Option<Interest>,
+    /// Token `fd` is registered with, if known.
+    existing: Option<Token>,
+    err: io::Error,
+}
+
+#[cfg(all(unix, any(feature = "os-poll", feature = "os-ext", feature = "net")))]
+impl RegistrationError {
+    /// Returns the file descriptor the operation failed for.
+    pub fn fd(&self) -> RawFd {
+        self.fd
+    }
+
+    /// Returns the token passed to the operation, `None` for deregistering.
+    pub fn token(&self) -> Option<Token> {
+        self.token
+    }
+
+    /// Returns the interests passed to the operation, `None` for
+    /// deregistering.
+    pub fn interests(&self) -> Option<Interest> {
+        self.interests
+    }
+}
+
+#[cfg(all(unix, any(feature = "os-poll", feature = "os-ext", feature = "net")))]
+impl fmt::Display for RegistrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "failed to {} file descriptor {}",
+            self.operation, self.fd
+        )?;
+        if let (Some(token), Some(interests)) = (self.token, self.interests) {
+            write!(f, " (token: {:?}, interests: {:?})", token, interests)?;
+        }
+        match (self.err.kind(), self.existing) {
+            (io::ErrorKind::AlreadyExists, Some(existing)) => {
+                write!(f, ": already registered with {:?}", existing)?
+            }
+            (io::ErrorKind::AlreadyExists, None) => {
+                f.write_str(": already registered with this `Registry`")?
+            }
+            (io::ErrorKind::NotFound, _) => f.write_str(": not registered with this `Registry`")?,
+            _ => {}
         }
+        write!(f, ": {}", self.err)
+    }
+}
+
+#[cfg(all(unix, any(feature = "os-poll", feature = "os-ext", feature = "net")))]
+impl std::error::Error for RegistrationError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.err)
+    }
+}
+
+/// Operation of a [`RegistrationError`].
+#[cfg(all(unix, any(feature = "os-poll", feature = "os-ext", feature = "net")))]
+#[derive(Copy, Clone, Debug)]
+pub(crate) enum Operation {
+    Register,
+    Reregister,
+    Deregister,
+}
+
+#[cfg(all(unix, any(feature = "os-poll", feature = "os-ext", feature = "net")))]
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Operation::Register => "register",
+            Operation::Reregister => "reregister",
+            Operation::Deregister => "deregister",
+        })
+    }
+}
+
+impl Poll {
+    /// Return a new `Poll` handle.
+    ///
+    /// This function will make a syscall to the operating system to create
+    /// the system selector. If this syscall fails, `Poll::new` will return
+    /// with the error. Without the `os-poll` feature this creates a selector
+    /// implemented in user space instead, see the `mem` module.
+    ///
+    /// close-on-exec flag is set on the file descriptors used by the selector to prevent
+    /// leaking it to executed processes. However, on some systems such as
+    /// old Linux systems that don't support `epoll_create1` syscall it is done
+    /// non-atomically, so a separate thread executing in parallel to this
+    /// function may accidentally leak the file descriptor if it executes a
+    /// new process before this function returns.
+    ///
+    /// See [struct] level docs for more details.
+    ///
+    /// [struct]: struct.Poll.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use mio::{Poll, Events};
+    /// use std::time::Duration;
+    ///
+    /// let mut poll = match Poll::new() {
+    ///     Ok(poll) => poll,
+    ///     Err(e) => panic!("failed to create Poll instance; err={:?}", e),
+    /// };
+    ///
+    /// // Create a structure to receive polled events
+    /// let mut events = Events::with_capacity(1024);
+    ///
+    /// // Wait for events, but none will be received because no
+    /// // `event::Source`s have been registered with this `Poll` instance.
+    /// poll.poll(&mut events, Some(Duration::from_millis(500)))?;
+    /// assert!(events.is_empty());
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn new() -> io::Result<Poll> {
+        sys::Selector::new().map(|selector| Poll {
+            registry: Registry {
+                selector,
+                #[cfg(all(unix, feature = "registry-introspection"))]
+                registrations: Registrations::default(),
+                #[cfg(all(
+                    any(feature = "net", all(unix, feature = "os-ext")),
+                    any(
+                        all(debug_assertions, feature = "drain-check"),
+                        feature = "readiness-cache"
+                    )
+                ))]
+                sources: Arc::new(SharedStates::default()),
+                #[cfg(feature = "os-poll")]
+                mem: Arc::new(sys::mem::Queue::new()),
+            },
+            busy_poll: None,
+        })
     }
 
     /// Create a separate `Registry` which can be used to register
@@ -310,6 +517,136 @@ impl Poll {
         &self.registry
     }
 
+    /// Enable or disable fair re-delivery of events.
+    ///
+    /// Normally an edge-triggered event is only delivered once, so a source
+    /// must be drained, i.e. its I/O operations must be performed until they
+    /// return a [`WouldBlock`] error, before polling again. That allows a
+    /// single busy source to starve all other sources.
+    ///
+    /// With fair re-delivery enabled `poll` remembers the sources that
+    /// received a readable event and read from it without draining it, i.e.
+    /// sources that yielded before reading everything. On the following calls
+    /// to `poll` these sources receive a readable event again, in round-robin
+    /// order after the new events, so the caller can limit the amount of work
+    /// done per event (e.g. read at most 64 KiB) without losing readiness.
+    /// While sources are waiting to be re-delivered `poll` doesn't block.
+    ///
+    /// Only reads are taken into account. A source is considered drained, and
+    /// is no longer re-delivered, once a read returns `Ok(0)`, fewer bytes
+    /// than the buffer can hold, or an error (including [`WouldBlock`]). After
+    /// the source received a read closed event data can still be pending, so
+    /// a short read no longer ends the re-delivery, only reading the end of
+    /// the stream (`Ok(0)`) or an error does. A source also isn't re-delivered
+    /// if it isn't read from after the event, or once it's deregistered.
+    /// Writes don't affect the re-delivery.
+    ///
+    /// This only applies to Mio's own I/O sources, which track their I/O
+    /// operations, and is disabled by default. It's built on the readiness
+    /// tracked by the sources, so it's only available with the
+    /// `readiness-cache` feature, see the [`features`] module.
+    ///
+    /// [`features`]: crate::features
+    /// [`WouldBlock`]: io::ErrorKind::WouldBlock
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(all(feature = "os-poll", feature = "net"), doc = "```")]
+    #[cfg_attr(not(all(feature = "os-poll", feature = "net")), doc = "```ignore")]
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use std::io::{self, Read};
+    ///
+    /// use mio::net::TcpStream;
+    /// use mio::{Events, Interest, Poll, Token};
+    ///
+    /// let mut poll = Poll::new()?;
+    /// poll.set_fair_redelivery(true);
+    /// let mut events = Events::with_capacity(128);
+    ///
+    /// # let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+    /// let mut stream = TcpStream::connect(listener.local_addr()?)?;
+    /// poll.registry().register(&mut stream, Token(0), Interest::READABLE)?;
+    /// # let (mut peer, _) = listener.accept()?;
+    /// # std::io::Write::write_all(&mut peer, b"hello world")?;
+    /// # drop(peer);
+    ///
+    /// let mut buf = [0; 4];
+    /// let mut received = Vec::new();
+    /// 'outer: loop {
+    ///     poll.poll(&mut events, None)?;
+    ///     for event in events.iter() {
+    ///         if event.token() != Token(0) {
+    ///             continue;
+    ///         }
+    ///         // Read at most 4 bytes per event, then yield to other sources.
+    ///         match stream.read(&mut buf) {
+    ///             Ok(0) => break 'outer,
+    ///             Ok(n) => received.extend_from_slice(&buf[..n]),
+    ///             Err(ref err) if err.kind() == io::ErrorKind::WouldBlock => {}
+    ///             Err(err) => return Err(err.into()),
+    ///         }
+    ///     }
+    /// }
+    /// assert_eq!(received, b"hello world");
+    /// #     Ok(())
+    /// # }
+    /// ```
+    #[cfg(all(
+        feature = "readiness-cache",
+        any(feature = "net", all(unix, feature = "os-ext"))
+    ))]
+    #[cfg_attr(docsrs, doc(cfg(feature = "readiness-cache")))]
+    pub fn set_fair_redelivery(&mut self, enabled: bool) {
+        self.registry.sources.set_fair(enabled);
+    }
+
+    /// Busy poll for up to `spin` before blocking in [`Poll::poll`].
+    ///
+    /// With busy polling enabled `poll` first checks for events without
+    /// blocking in a loop, until it receives events or `spin` elapses, before
+    /// falling back to a blocking wait for the remainder of the timeout. This
+    /// trades CPU time for a lower latency, as the thread never goes to sleep
+    /// if events arrive within `spin`. Passing `None` disables busy polling,
+    /// which is the default.
+    ///
+    /// On Linux and Android this also sets the busy poll parameters of the
+    /// epoll instance (`EPIOCSPARAMS`), allowing the kernel to busy poll the
+    /// network device queues of the registered sockets, where supported
+    /// (Linux 6.9 and later). On older kernels and other platforms only the
+    /// loop described above is used. Note that busy polling of the device
+    /// queues can also be enabled per socket using the `SO_BUSY_POLL` socket
+    /// option, which isn't set by this method.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(feature = "os-poll", doc = "```")]
+    #[cfg_attr(not(feature = "os-poll"), doc = "```ignore")]
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use std::time::Duration;
+    ///
+    /// use mio::{Events, Poll};
+    ///
+    /// let mut poll = Poll::new()?;
+    /// let mut events = Events::with_capacity(128);
+    ///
+    /// // Spin for 50 microseconds before going to sleep.
+    /// poll.set_busy_poll(Some(Duration::from_micros(50)))?;
+    ///
+    /// // Register `event::Source`s with `poll`.
+    ///
+    /// poll.poll(&mut events, Some(Duration::from_millis(10)))?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn set_busy_poll(&mut self, spin: Option<Duration>) -> io::Result<()> {
+        #[cfg(all(feature = "os-poll", any(target_os = "android", target_os = "linux")))]
+        self.registry.selector.set_busy_poll(spin)?;
+        self.busy_poll = spin;
+        Ok(())
+    }
+
     /// Wait for readiness events
     ///
     /// Blocks the current thread and waits for readiness events for any of the
@@ -351,7 +688,12 @@ impl Poll {
     ///
     /// Currently if the `timeout` elapses without any readiness events
     /// triggering this will return `Ok(())`. However we're not guaranteeing
-    /// this behaviour as this depends on the OS.
+    /// this behaviour as this depends on the OS. Use [`Events::timed_out`] to
+    /// determine if the timeout elapsed, and [`Events::is_full`] to determine
+    /// if more events may be ready than fitted in `events`.
+    ///
+    /// [`Events::timed_out`]: ./event/struct.Events.html#method.timed_out
+    /// [`Events::is_full`]: ./event/struct.Events.html#method.is_full
     ///
     /// # Examples
     ///
@@ -408,7 +750,116 @@ impl Poll {
     ///
     /// [struct]: #
     pub fn poll(&mut self, events: &mut Events, timeout: Option<Duration>) -> io::Result<()> {
-        self.registry.selector.select(events.sys(), timeout)
+        #[cfg(all(
+            debug_assertions,
+            feature = "drain-check",
+            any(feature = "net", all(unix, feature = "os-ext"))
+        ))]
+        self.registry.sources.check_pending();
+        events.set_timed_out(false);
+        let started = timeout.map(|timeout| (Instant::now(), timeout));
+        #[cfg(all(
+            feature = "readiness-cache",
+            any(feature = "net", all(unix, feature = "os-ext"))
+        ))]
+        let timeout = self.registry.sources.poll_timeout(timeout);
+        #[cfg(feature = "os-poll")]
+        let timeout = self.registry.mem.poll_timeout(timeout);
+        self.select(events, timeout)?;
+        #[cfg(feature = "os-poll")]
+        self.registry.mem.received(events);
+        #[cfg(all(
+            any(feature = "net", all(unix, feature = "os-ext")),
+            any(
+                all(debug_assertions, feature = "drain-check"),
+                feature = "readiness-cache"
+            )
+        ))]
+        self.registry.sources.received(events);
+        // The OS may return early without any events, only report a timeout
+        // if it actually elapsed.
+        if let (true, Some((started, timeout))) = (events.is_empty(), started) {
+            events.set_timed_out(started.elapsed() >= timeout);
+        }
+        Ok(())
+    }
+
+    /// Select events from the `sys::Selector`, busy polling first if enabled.
+    fn select(&mut self, events: &mut Events, timeout: Option<Duration>) -> io::Result<()> {
+        let no_wait = Duration::from_secs(0);
+        let spin = match self.busy_poll {
+            Some(spin) if timeout != Some(no_wait) => spin,
+            _ => return self.registry.selector.select(events.sys(), timeout),
+        };
+
+        let start = Instant::now();
+        loop {
+            self.registry.selector.select(events.sys(), Some(no_wait))?;
+            if !events.is_empty() {
+                return Ok(());
+            }
+
+            let elapsed = start.elapsed();
+            match timeout {
+                Some(timeout) if elapsed >= timeout => return Ok(()),
+                _ if elapsed >= spin => {
+                    let timeout = timeout.map(|timeout| timeout - elapsed);
+                    return self.registry.selector.select(events.sys(), timeout);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Wait for readiness events until `deadline`.
+    ///
+    /// This is the same as [`Poll::poll`], but with an absolute deadline
+    /// instead of a relative timeout. The timeout is recomputed on each call,
+    /// so calling this in a loop, e.g. after it returns an error of kind
+    /// [`Interrupted`], doesn't extend the total time spent waiting. If
+    /// `deadline` already passed this doesn't block.
+    ///
+    /// [`Interrupted`]: std::io::ErrorKind::Interrupted
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(feature = "os-poll", doc = "```")]
+    #[cfg_attr(not(feature = "os-poll"), doc = "```ignore")]
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use std::io;
+    /// use std::time::{Duration, Instant};
+    ///
+    /// use mio::{Events, Poll};
+    ///
+    /// let mut poll = Poll::new()?;
+    /// let mut events = Events::with_capacity(1024);
+    ///
+    /// // Register `event::Source`s with `poll`.
+    ///
+    /// let deadline = Instant::now() + Duration::from_millis(10);
+    /// loop {
+    ///     match poll.poll_until(&mut events, deadline) {
+    ///         Ok(()) => {}
+    ///         Err(ref err) if err.kind() == io::ErrorKind::Interrupted => continue,
+    ///         Err(err) => return Err(err.into()),
+    ///     }
+    ///
+    ///     for event in events.iter() {
+    ///         println!("Got an event for {:?}", event.token());
+    ///     }
+    ///
+    ///     if events.timed_out() {
+    ///         break;
+    ///     }
+    /// }
+    /// assert!(Instant::now() >= deadline);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn poll_until(&mut self, events: &mut Events, deadline: Instant) -> io::Result<()> {
+        let timeout = deadline.saturating_duration_since(Instant::now());
+        self.poll(events, Some(timeout))
     }
 }
 
@@ -419,6 +870,104 @@ impl AsRawFd for Poll {
     }
 }
 
+/// Registering a `Poll` instance with another `Poll` instance.
+///
+/// This allows an event loop to be nested inside another, e.g. to compose the
+/// private event loop of a library into the main event loop of an application.
+/// The inner `Poll` is reported as readable by the outer `Poll` once any of
+/// the event sources registered with the inner `Poll` have events. Only
+/// [readable interest] is supported.
+///
+/// As with all event sources the readiness is edge-triggered. After receiving
+/// an event for the inner `Poll`, it must be polled with a zero timeout until
+/// it returns no more events, otherwise the outer `Poll` may not report it as
+/// readable again.
+///
+/// [readable interest]: Interest::READABLE
+///
+/// # Examples
+///
+#[cfg_attr(feature = "os-poll", doc = "```")]
+#[cfg_attr(not(feature = "os-poll"), doc = "```ignore")]
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use mio::{Events, Interest, Poll, Token, Waker};
+/// use std::time::Duration;
+///
+/// const INNER: Token = Token(0);
+/// const WAKER: Token = Token(1);
+///
+/// let mut outer = Poll::new()?;
+/// let mut outer_events = Events::with_capacity(8);
+///
+/// // For example the event loop of a library.
+/// let mut inner = Poll::new()?;
+/// let mut inner_events = Events::with_capacity(8);
+/// let waker = Waker::new(inner.registry(), WAKER)?;
+///
+/// outer.registry().register(&mut inner, INNER, Interest::READABLE)?;
+///
+/// waker.wake()?;
+/// outer.poll(&mut outer_events, None)?;
+/// for event in outer_events.iter() {
+///     if event.token() == INNER {
+///         // Process all events of the inner `Poll`.
+///         loop {
+///             inner.poll(&mut inner_events, Some(Duration::from_millis(0)))?;
+///             if inner_events.is_empty() {
+///                 break;
+///             }
+///             for event in inner_events.iter() {
+///                 assert_eq!(event.token(), WAKER);
+///             }
+///         }
+///     }
+/// }
+/// #     Ok(())
+/// # }
+/// ```
+#[cfg(all(unix, feature = "os-poll"))]
+#[cfg_attr(docsrs, doc(cfg(all(unix, feature = "os-poll"))))]
+impl event::Source for Poll {
+    fn register(
+        &mut self,
+        registry: &Registry,
+        token: Token,
+        interests: Interest,
+    ) -> io::Result<()> {
+        check_nested_interests(interests)?;
+        registry.register_fd(self.as_raw_fd(), token, interests)
+    }
+
+    fn reregister(
+        &mut self,
+        registry: &Registry,
+        token: Token,
+        interests: Interest,
+    ) -> io::Result<()> {
+        check_nested_interests(interests)?;
+        registry.reregister_fd(self.as_raw_fd(), token, interests)
+    }
+
+    fn deregister(&mut self, registry: &Registry) -> io::Result<()> {
+        registry.deregister_fd(self.as_raw_fd())
+    }
+}
+
+/// A nested `Poll` can only become readable, any other interest would never
+/// trigger an event.
+#[cfg(all(unix, feature = "os-poll"))]
+fn check_nested_interests(interests: Interest) -> io::Result<()> {
+    if interests == Interest::READABLE {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "`Poll` can only be registered with readable interest",
+        ))
+    }
+}
+
 impl fmt::Debug for Poll {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
         fmt.debug_struct("Poll").finish()
@@ -664,14 +1213,164 @@ impl Registry {
         source.deregister(self)
     }
 
+    /// Move an [`event::Source`] from this `Registry` to `to`, registering it
+    /// with `token` and `interests`.
+    ///
+    /// This is the same as deregistering the source and registering it with
+    /// `to`, except that Mio's own types register with `to` *before*
+    /// deregistering, so the source is never left unregistered. Because the
+    /// registration with `to` is a new registration its first poll reports
+    /// the current readiness of the source, which means no readiness is lost
+    /// when using edge-triggered events. Readiness already received by this
+    /// `Registry`'s `Poll` may be reported again by `to`.
+    ///
+    /// If the transfer fails Mio's own types remain registered with this
+    /// `Registry`, except on Windows. There a source only supports a single
+    /// registration, so it's deregistered first and is left unregistered if
+    /// registering with `to` fails.
+    ///
+    /// `to` must be a `Registry` of a different `Poll` instance.
+    ///
+    /// See [`event::Source::transfer`] for how custom event sources should
+    /// implement this.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(all(feature = "os-poll", feature = "net"), doc = "```")]
+    #[cfg_attr(not(all(feature = "os-poll", feature = "net")), doc = "```ignore")]
+    /// # use std::error::Error;
+    /// # use std::net;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use mio::{Events, Poll, Interest, Token};
+    /// use mio::net::TcpStream;
+    /// use std::time::Duration;
+    ///
+    /// let mut poll1 = Poll::new()?;
+    /// let mut poll2 = Poll::new()?;
+    ///
+    /// let listener = net::TcpListener::bind("127.0.0.1:0")?;
+    /// let mut socket = TcpStream::connect(listener.local_addr()?)?;
+    /// poll1.registry().register(&mut socket, Token(0), Interest::WRITABLE)?;
+    ///
+    /// // Move the socket to `poll2`, using a different token.
+    /// poll1.registry().transfer(&mut socket, poll2.registry(), Token(1), Interest::WRITABLE)?;
+    ///
+    /// let mut events = Events::with_capacity(8);
+    /// poll1.poll(&mut events, Some(Duration::from_millis(100)))?;
+    /// assert!(events.is_empty());
+    /// poll2.poll(&mut events, None)?;
+    /// assert_eq!(events.iter().next().unwrap().token(), Token(1));
+    /// #     poll2.registry().deregister(&mut socket)?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`event::Source`]: ./event/trait.Source.html
+    /// [`event::Source::transfer`]: ./event/trait.Source.html#method.transfer
+    pub fn transfer<S>(
+        &self,
+        source: &mut S,
+        to: &Registry,
+        token: Token,
+        interests: Interest,
+    ) -> io::Result<()>
+    where
+        S: event::Source + ?Sized,
+    {
+        trace!(
+            "transferring event source to other poller: token={:?}, interests={:?}",
+            token,
+            interests
+        );
+        source.transfer(self, to, token, interests)
+    }
+
     /// Creates a new independently owned `Registry`.
     ///
     /// Event sources registered with this `Registry` will be registered with
     /// the original `Registry` and `Poll` instance.
     pub fn try_clone(&self) -> io::Result<Registry> {
-        self.selector
-            .try_clone()
-            .map(|selector| Registry { selector })
+        self.selector.try_clone().map(|selector| Registry {
+            selector,
+            #[cfg(all(unix, feature = "registry-introspection"))]
+            registrations: self.registrations.clone(),
+            #[cfg(all(
+                any(feature = "net", all(unix, feature = "os-ext")),
+                any(
+                    all(debug_assertions, feature = "drain-check"),
+                    feature = "readiness-cache"
+                )
+            ))]
+            sources: self.sources.clone(),
+            #[cfg(feature = "os-poll")]
+            mem: self.mem.clone(),
+        })
+    }
+
+    /// Returns all file descriptors currently registered with this `Registry`,
+    /// ordered by file descriptor.
+    ///
+    /// The returned list is shared between all `Registry`s created using
+    /// [`try_clone`] and the original `Poll` instance.
+    ///
+    /// # Notes
+    ///
+    /// Only registrations made via a `Registry`, e.g. for Mio's own types or
+    /// [`SourceFd`], are tracked.
+    ///
+    /// **An event source that is closed without being [deregistered] can be
+    /// reported as still registered.** The OS removes a closed file
+    /// descriptor from the selector, so the registrations of file descriptors
+    /// that are closed are removed from the list when it's retrieved. However
+    /// if the file descriptor number is already reused, e.g. for a newly
+    /// accepted connection, the stale registration (with the token of the old
+    /// event source) stays in the list until the file descriptor is
+    /// registered again or deregistered. Always deregister event sources
+    /// before closing them to keep the list accurate.
+    ///
+    /// [`try_clone`]: Registry::try_clone
+    /// [`SourceFd`]: crate::unix::SourceFd
+    /// [deregistered]: Registry::deregister
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(all(feature = "os-poll", feature = "net"), doc = "```")]
+    #[cfg_attr(not(all(feature = "os-poll", feature = "net")), doc = "```ignore")]
+    /// # use std::error::Error;
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// use mio::{Interest, Poll, Token};
+    /// use mio::net::TcpListener;
+    /// use std::os::unix::io::AsRawFd;
+    ///
+    /// let poll = Poll::new()?;
+    /// let mut listener = TcpListener::bind("127.0.0.1:0".parse()?)?;
+    /// poll.registry().register(&mut listener, Token(0), Interest::READABLE)?;
+    ///
+    /// let registrations = poll.registry().registrations();
+    /// assert_eq!(registrations.len(), 1);
+    /// assert_eq!(registrations[0].fd(), listener.as_raw_fd());
+    /// assert_eq!(registrations[0].token(), Token(0));
+    /// assert_eq!(registrations[0].interests(), Interest::READABLE);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    #[cfg(all(unix, feature = "registry-introspection"))]
+    #[cfg_attr(docsrs, doc(cfg(all(unix, feature = "registry-introspection"))))]
+    pub fn registrations(&self) -> Vec<Registration> {
+        self.registrations.pruned().values().copied().collect()
+    }
+
+    /// Returns `true` if `fd` is registered with this `Registry`.
+    ///
+    /// See [`registrations`] for the limitations of the bookkeeping, most
+    /// notably this can return `true` for a file descriptor that was closed
+    /// without being deregistered and since reused.
+    ///
+    /// [`registrations`]: Registry::registrations
+    #[cfg(all(unix, feature = "registry-introspection"))]
+    #[cfg_attr(docsrs, doc(cfg(all(unix, feature = "registry-introspection"))))]
+    pub fn is_registered(&self, fd: RawFd) -> bool {
+        self.registrations.pruned().contains_key(&fd)
     }
 
     /// Internal check to ensure only a single `Waker` is active per [`Poll`]
@@ -689,14 +1388,237 @@ impl Registry {
     pub(crate) fn selector(&self) -> &sys::Selector {
         &self.selector
     }
+
+    /// Get access to the state of the registered I/O sources.
+    #[cfg(all(
+        any(feature = "net", all(unix, feature = "os-ext")),
+        any(
+            all(debug_assertions, feature = "drain-check"),
+            feature = "readiness-cache"
+        )
+    ))]
+    pub(crate) fn sources(&self) -> &Arc<SharedStates> {
+        &self.sources
+    }
+
+    /// Get access to the queue of the in-process sources.
+    pub(crate) fn mem_queue(&self) -> &Arc<sys::mem::Queue> {
+        #[cfg(feature = "os-poll")]
+        {
+            &self.mem
+        }
+        #[cfg(not(feature = "os-poll"))]
+        {
+            self.selector.queue()
+        }
+    }
+}
+
+#[cfg(all(unix, any(feature = "os-poll", feature = "os-ext", feature = "net")))]
+impl Registry {
+    /// Register `fd` with the `sys::Selector`.
+    ///
+    /// All registrations of Unix file descriptors must go through this
+    /// method (and [`Registry::reregister_fd`] and
+    /// [`Registry::deregister_fd`]) to keep our own bookkeeping in sync.
+    pub(crate) fn register_fd(
+        &self,
+        fd: RawFd,
+        token: Token,
+        interests: Interest,
+    ) -> io::Result<()> {
+        match self.selector.register(fd, token, interests) {
+            Ok(()) => {
+                #[cfg(feature = "registry-introspection")]
+                self.registrations.insert(fd, token, interests);
+                Ok(())
+            }
+            Err(err) => Err(self.registration_error(
+                Operation::Register,
+                fd,
+                Some(token),
+                Some(interests),
+                err,
+            )),
+        }
+    }
+
+    /// Re-register `fd` with the `sys::Selector`.
+    pub(crate) fn reregister_fd(
+        &self,
+        fd: RawFd,
+        token: Token,
+        interests: Interest,
+    ) -> io::Result<()> {
+        match self.selector.reregister(fd, token, interests) {
+            Ok(()) => {
+                #[cfg(feature = "registry-introspection")]
+                self.registrations.insert(fd, token, interests);
+                Ok(())
+            }
+            Err(err) => Err(self.registration_error(
+                Operation::Reregister,
+                fd,
+                Some(token),
+                Some(interests),
+                err,
+            )),
+        }
+    }
+
+    /// Deregister `fd` from the `sys::Selector`.
+    pub(crate) fn deregister_fd(&self, fd: RawFd) -> io::Result<()> {
+        match self.selector.deregister(fd) {
+            Ok(()) => {
+                #[cfg(feature = "registry-introspection")]
+                self.registrations.remove(fd);
+                Ok(())
+            }
+            Err(err) => Err(self.registration_error(Operation::Deregister, fd, None, None, err)),
+        }
+    }
+
+    /// Add the context of `operation` on `fd` to `err`, returned by the
+    /// `sys::Selector` or detected by Mio.
+    ///
+    /// Errors of the OS are returned as is, so that `raw_os_error` keeps
+    /// working, only logging the context. Other errors are wrapped in a
+    /// [`RegistrationError`], keeping the kind of `err`.
+    pub(crate) fn registration_error(
+        &self,
+        operation: Operation,
+        fd: RawFd,
+        token: Option<Token>,
+        interests: Option<Interest>,
+        err: io::Error,
+    ) -> io::Error {
+        #[cfg(feature = "registry-introspection")]
+        let existing = self.registrations.token(fd);
+        #[cfg(not(feature = "registry-introspection"))]
+        let existing = None;
+        // If we know the file descriptor is already registered the error names
+        // the token it's registered with, rather than being a bare `EEXIST`.
+        let already_registered = err.kind() == io::ErrorKind::AlreadyExists && existing.is_some();
+        let err = RegistrationError {
+            operation,
+            fd,
+            token,
+            interests,
+            existing,
+            err,
+        };
+        if err.err.raw_os_error().is_some() && !already_registered {
+            log::debug!("{}", err);
+            return err.err;
+        }
+        io::Error::new(err.err.kind(), err)
+    }
+
+    /// Move `fd` from this `sys::Selector` to the one of `to`.
+    ///
+    /// `fd` is registered with `to` before it's deregistered, so it's always
+    /// registered with at least one of the selectors. If deregistering fails
+    /// the registration with `to` is undone.
+    pub(crate) fn transfer_fd(
+        &self,
+        to: &Registry,
+        fd: RawFd,
+        token: Token,
+        interests: Interest,
+    ) -> io::Result<()> {
+        to.register_fd(fd, token, interests)?;
+        if let Err(err) = self.deregister_fd(fd) {
+            let _ = to.deregister_fd(fd);
+            return Err(err);
+        }
+        Ok(())
+    }
 }
 
 impl fmt::Debug for Registry {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
-        fmt.debug_struct("Registry").finish()
+        let mut d = fmt.debug_struct("Registry");
+        #[cfg(all(unix, feature = "registry-introspection"))]
+        d.field("registrations", &self.registrations());
+        d.finish()
+    }
+}
+
+#[cfg(all(unix, feature = "registry-introspection"))]
+impl Registration {
+    /// Returns the registered file descriptor.
+    pub fn fd(&self) -> RawFd {
+        self.fd
+    }
+
+    /// Returns the token the file descriptor is registered with.
+    pub fn token(&self) -> Token {
+        self.token
+    }
+
+    /// Returns the interests the file descriptor is registered with.
+    pub fn interests(&self) -> Interest {
+        self.interests
     }
 }
 
+/// Bookkeeping of the file descriptors registered with a `Registry`, shared
+/// between all clones of it.
+#[cfg(all(unix, feature = "registry-introspection"))]
+#[derive(Clone, Default)]
+struct Registrations {
+    table: Arc<Mutex<BTreeMap<RawFd, Registration>>>,
+}
+
+#[cfg(all(unix, feature = "registry-introspection"))]
+impl Registrations {
+    fn lock(&self) -> MutexGuard<'_, BTreeMap<RawFd, Registration>> {
+        // The table is always left in a consistent state, so we can safely
+        // ignore poisoning.
+        self.table.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Same as `lock`, but first removes the registrations of file
+    /// descriptors that are closed, which the OS removed from the selector.
+    fn pruned(&self) -> MutexGuard<'_, BTreeMap<RawFd, Registration>> {
+        let mut table = self.lock();
+        // NOTE: not using `BTreeMap::retain` as it requires Rust 1.53.
+        let closed: Vec<RawFd> = table.keys().copied().filter(|fd| !is_open(*fd)).collect();
+        for fd in closed {
+            let _ = table.remove(&fd);
+        }
+        table
+    }
+
+    /// Returns the token `fd` is registered with, if any.
+    #[cfg(any(feature = "os-poll", feature = "os-ext", feature = "net"))]
+    fn token(&self, fd: RawFd) -> Option<Token> {
+        self.lock().get(&fd).map(|registration| registration.token)
+    }
+
+    #[cfg(any(feature = "os-poll", feature = "os-ext", feature = "net"))]
+    fn insert(&self, fd: RawFd, token: Token, interests: Interest) {
+        let registration = Registration {
+            fd,
+            token,
+            interests,
+        };
+        let _ = self.lock().insert(fd, registration);
+    }
+
+    #[cfg(any(feature = "os-poll", feature = "os-ext", feature = "net"))]
+    fn remove(&self, fd: RawFd) {
+        let _ = self.lock().remove(&fd);
+    }
+}
+
+/// Returns `false` if `fd` is not an open file descriptor.
+#[cfg(all(unix, feature = "registry-introspection"))]
+fn is_open(fd: RawFd) -> bool {
+    let res = unsafe { libc::fcntl(fd, libc::F_GETFD) };
+    res != -1 || io::Error::last_os_error().raw_os_error() != Some(libc::EBADF)
+}
+
 #[cfg(unix)]
 impl AsRawFd for Registry {
     fn as_raw_fd(&self) -> RawFd {
@@ -0,0 +1,210 @@
+//! In-memory channel.
+//!
+//! See the [`new`] function for documentation.
+
+use std::collections::VecDeque;
+use std::sync::mpsc::{SendError, TryRecvError};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::{fmt, io};
+
+use crate::sys::mem::{Registration, READABLE, READ_CLOSED};
+use crate::{event, Interest, Registry, Token};
+
+/// Create a new unbounded in-memory channel.
+///
+/// The [`Receiver`] can be registered with [`READABLE`] interest, it receives
+/// a [readable event] once a value is sent using one of the [`Sender`]s.
+/// Values are received using [`Receiver::try_recv`], which returns
+/// [`TryRecvError::Empty`] once all values are received. Like the OS selectors
+/// events are edge-triggered: the `Receiver` only receives another event once
+/// a value is sent after `try_recv` returned `TryRecvError::Empty`.
+///
+/// Once all `Sender`s are dropped the `Receiver` receives an event for which
+/// [`is_read_closed`] returns true, and `try_recv` returns
+/// [`TryRecvError::Disconnected`] after all values are received.
+///
+/// [`READABLE`]: Interest::READABLE
+/// [readable event]: event::Event::is_readable
+/// [`is_read_closed`]: event::Event::is_read_closed
+///
+/// # Examples
+///
+/// ```
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use std::thread;
+///
+/// use mio::{Poll, Events, Interest, Token};
+/// use mio::mem::channel;
+///
+/// const CHANNEL: Token = Token(0);
+///
+/// let mut poll = Poll::new()?;
+/// let mut events = Events::with_capacity(8);
+///
+/// let (sender, mut receiver) = channel::new();
+/// poll.registry().register(&mut receiver, CHANNEL, Interest::READABLE)?;
+///
+/// thread::spawn(move || sender.send("Hello world").unwrap());
+///
+/// poll.poll(&mut events, None)?;
+/// for event in events.iter() {
+///     assert_eq!(event.token(), CHANNEL);
+///     assert_eq!(receiver.try_recv()?, "Hello world");
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn new<T>() -> (Sender<T>, Receiver<T>) {
+    let shared = Arc::new(Mutex::new(State {
+        queue: VecDeque::new(),
+        senders: 1,
+        receiver_closed: false,
+        receiver: Registration::default(),
+    }));
+    let sender = Sender {
+        shared: shared.clone(),
+    };
+    let receiver = Receiver { shared };
+    (sender, receiver)
+}
+
+/// State shared between the `Sender`s and `Receiver`.
+struct State<T> {
+    queue: VecDeque<T>,
+    /// Number of `Sender`s alive.
+    senders: usize,
+    receiver_closed: bool,
+    receiver: Registration,
+}
+
+impl<T> State<T> {
+    /// Readiness of the receiving end.
+    fn receiver_readiness(&self) -> u8 {
+        let mut readiness = 0;
+        if !self.queue.is_empty() {
+            readiness |= READABLE;
+        }
+        if self.senders == 0 {
+            readiness |= READABLE | READ_CLOSED;
+        }
+        readiness
+    }
+}
+
+fn lock<T>(shared: &Mutex<State<T>>) -> MutexGuard<'_, State<T>> {
+    shared.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Sending end of an in-memory channel.
+///
+/// See [`new`] for documentation, including examples.
+pub struct Sender<T> {
+    shared: Arc<Mutex<State<T>>>,
+}
+
+impl<T> Sender<T> {
+    /// Send `value` to the `Receiver`.
+    ///
+    /// Returns an error containing `value` if the `Receiver` is dropped.
+    pub fn send(&self, value: T) -> Result<(), SendError<T>> {
+        let mut state = lock(&self.shared);
+        if state.receiver_closed {
+            return Err(SendError(value));
+        }
+        let was_empty = state.queue.is_empty();
+        state.queue.push_back(value);
+        if was_empty {
+            state.receiver.notify(READABLE);
+        }
+        Ok(())
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Sender<T> {
+        lock(&self.shared).senders += 1;
+        Sender {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sender").finish()
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let mut state = lock(&self.shared);
+        state.senders -= 1;
+        if state.senders == 0 {
+            state.receiver.notify(READABLE | READ_CLOSED);
+        }
+    }
+}
+
+/// Receiving end of an in-memory channel.
+///
+/// See [`new`] for documentation, including examples.
+pub struct Receiver<T> {
+    shared: Arc<Mutex<State<T>>>,
+}
+
+impl<T> Receiver<T> {
+    /// Attempt to receive a value, without blocking.
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        let mut state = lock(&self.shared);
+        match state.queue.pop_front() {
+            Some(value) => Ok(value),
+            None if state.senders == 0 => Err(TryRecvError::Disconnected),
+            None => Err(TryRecvError::Empty),
+        }
+    }
+}
+
+impl<T> event::Source for Receiver<T> {
+    fn register(
+        &mut self,
+        registry: &Registry,
+        token: Token,
+        interests: Interest,
+    ) -> io::Result<()> {
+        let mut state = lock(&self.shared);
+        state.receiver.register(registry, token, interests)?;
+        state.receiver.notify(state.receiver_readiness());
+        Ok(())
+    }
+
+    fn reregister(
+        &mut self,
+        registry: &Registry,
+        token: Token,
+        interests: Interest,
+    ) -> io::Result<()> {
+        let mut state = lock(&self.shared);
+        state.receiver.reregister(registry, token, interests)?;
+        state.receiver.notify(state.receiver_readiness());
+        Ok(())
+    }
+
+    fn deregister(&mut self, registry: &Registry) -> io::Result<()> {
+        lock(&self.shared).receiver.deregister(registry)
+    }
+}
+
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Receiver").finish()
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        let mut state = lock(&self.shared);
+        state.receiver_closed = true;
+        state.receiver.clear();
+    }
+}
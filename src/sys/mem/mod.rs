@@ -0,0 +1,259 @@
+//! In-process event sources, see the `mem` module.
+//!
+//! The sources report their readiness to the `Queue` of the `Registry` they
+//! are registered with. Without the `os-poll` feature the `Queue` is polled by
+//! the user space selector of the shell implementation. With the `os-poll`
+//! feature `Poll::poll` adds the queued events to the events returned by the
+//! OS selector, which is woken up using a `Waker` registered with
+//! `WAKE_TOKEN`.
+
+use std::io;
+#[cfg(feature = "os-poll")]
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(not(feature = "os-poll"))]
+use std::sync::Condvar;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError, Weak};
+#[cfg(feature = "os-poll")]
+use std::time::Duration;
+#[cfg(not(feature = "os-poll"))]
+use std::time::Instant;
+
+#[cfg(feature = "os-poll")]
+use crate::Events;
+use crate::{Interest, Registry, Token};
+
+pub(crate) mod channel;
+pub(crate) mod pipe;
+
+pub(crate) const READABLE: u8 = 0b0_0001;
+pub(crate) const WRITABLE: u8 = 0b0_0010;
+pub(crate) const READ_CLOSED: u8 = 0b0_0100;
+pub(crate) const WRITE_CLOSED: u8 = 0b0_1000;
+pub(crate) const ERROR: u8 = 0b1_0000;
+
+/// Token of the `Waker` used to wake up the OS selector. `TokenSlab` never
+/// hands out tokens with all index bits set, so this doesn't conflict with it.
+#[cfg(all(feature = "os-poll", not(target_os = "wasi")))]
+pub(crate) const WAKE_TOKEN: Token = Token(usize::MAX >> 1);
+
+/// Events of the in-process sources registered with a `Registry`.
+#[derive(Debug)]
+pub(crate) struct Queue {
+    /// Events not yet returned by `Poll::poll`, at most one per token.
+    pending: Mutex<Vec<(Token, u8)>>,
+    /// Signalled when an event is added to `pending`.
+    #[cfg(not(feature = "os-poll"))]
+    condvar: Condvar,
+    /// Set once a source is registered, until then `Poll::poll` doesn't have
+    /// to check `pending`.
+    #[cfg(feature = "os-poll")]
+    used: AtomicBool,
+    /// Wakes up the OS selector when an event is added to `pending`, created
+    /// when the first source is registered.
+    #[cfg(all(feature = "os-poll", not(target_os = "wasi")))]
+    waker: Mutex<Option<crate::sys::Waker>>,
+}
+
+impl Queue {
+    pub(crate) fn new() -> Queue {
+        Queue {
+            pending: Mutex::new(Vec::new()),
+            #[cfg(not(feature = "os-poll"))]
+            condvar: Condvar::new(),
+            #[cfg(feature = "os-poll")]
+            used: AtomicBool::new(false),
+            #[cfg(all(feature = "os-poll", not(target_os = "wasi")))]
+            waker: Mutex::new(None),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<(Token, u8)>> {
+        self.pending.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Add an event with `readiness` for `token`.
+    pub(crate) fn notify(&self, token: Token, readiness: u8) {
+        let mut pending = self.lock();
+        match pending.iter_mut().find(|(t, _)| *t == token) {
+            Some((_, r)) => *r |= readiness,
+            None => pending.push((token, readiness)),
+        }
+        drop(pending);
+
+        #[cfg(not(feature = "os-poll"))]
+        self.condvar.notify_all();
+        #[cfg(all(feature = "os-poll", not(target_os = "wasi")))]
+        if let Some(waker) = &*self.waker.lock().unwrap_or_else(PoisonError::into_inner) {
+            // If this fails we can't do much but report the event on the next
+            // call to `Poll::poll`.
+            let _ = waker.wake();
+        }
+    }
+
+    /// Wait until an event is added, or `deadline` passes, returning the
+    /// pending events.
+    #[cfg(not(feature = "os-poll"))]
+    pub(crate) fn wait(&self, deadline: Option<Instant>) -> MutexGuard<'_, Vec<(Token, u8)>> {
+        let mut pending = self.lock();
+        while pending.is_empty() {
+            pending = match deadline {
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        break;
+                    }
+                    self.condvar
+                        .wait_timeout(pending, deadline - now)
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .0
+                }
+                None => self
+                    .condvar
+                    .wait(pending)
+                    .unwrap_or_else(PoisonError::into_inner),
+            };
+        }
+        pending
+    }
+
+    /// Prepare for a source being registered with `registry`.
+    #[cfg_attr(
+        not(all(feature = "os-poll", not(target_os = "wasi"))),
+        allow(unused_variables)
+    )]
+    fn registering(&self, registry: &Registry) -> io::Result<()> {
+        #[cfg(all(feature = "os-poll", not(target_os = "wasi")))]
+        {
+            let mut waker = self.waker.lock().unwrap_or_else(PoisonError::into_inner);
+            if waker.is_none() {
+                *waker = Some(crate::sys::Waker::new(registry.selector(), WAKE_TOKEN)?);
+            }
+        }
+        #[cfg(feature = "os-poll")]
+        self.used.store(true, Ordering::Release);
+        Ok(())
+    }
+
+    /// Returns the timeout to use for the OS selector, which must not block
+    /// if events are pending.
+    #[cfg(feature = "os-poll")]
+    pub(crate) fn poll_timeout(&self, timeout: Option<Duration>) -> Option<Duration> {
+        if self.used.load(Ordering::Acquire) && !self.lock().is_empty() {
+            Some(Duration::from_millis(0))
+        } else {
+            timeout
+        }
+    }
+
+    /// Remove the events of the `Waker` from `events` and add the pending
+    /// events, for as long as `events` has capacity.
+    #[cfg(feature = "os-poll")]
+    pub(crate) fn received(&self, events: &mut Events) {
+        if !self.used.load(Ordering::Acquire) {
+            return;
+        }
+        #[cfg(not(target_os = "wasi"))]
+        events.retain(|event| event.token() != WAKE_TOKEN);
+
+        let mut pending = self.lock();
+        let n = pending
+            .iter()
+            .take_while(|(token, readiness)| events.push(*token, *readiness))
+            .count();
+        let _ = pending.drain(..n);
+    }
+}
+
+/// Registration of an in-process source with a `Registry`.
+///
+/// Doesn't keep the `Registry` alive, events for a dropped `Registry` are
+/// ignored.
+#[derive(Debug, Default)]
+pub(crate) struct Registration {
+    inner: Option<(Weak<Queue>, Token, Interest)>,
+}
+
+impl Registration {
+    pub(crate) fn register(
+        &mut self,
+        registry: &Registry,
+        token: Token,
+        interests: Interest,
+    ) -> io::Result<()> {
+        let queue = registry.mem_queue();
+        match &self.inner {
+            Some((q, ..)) if q.ptr_eq(&Arc::downgrade(queue)) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::AlreadyExists,
+                    "I/O source already registered with this `Registry`",
+                ))
+            }
+            Some(_) => return Err(different_registry()),
+            None => {}
+        }
+        queue.registering(registry)?;
+        self.inner = Some((Arc::downgrade(queue), token, interests));
+        Ok(())
+    }
+
+    pub(crate) fn reregister(
+        &mut self,
+        registry: &Registry,
+        token: Token,
+        interests: Interest,
+    ) -> io::Result<()> {
+        self.check_registry(registry)?;
+        self.inner = Some((Arc::downgrade(registry.mem_queue()), token, interests));
+        Ok(())
+    }
+
+    pub(crate) fn deregister(&mut self, registry: &Registry) -> io::Result<()> {
+        self.check_registry(registry)?;
+        self.inner = None;
+        Ok(())
+    }
+
+    fn check_registry(&self, registry: &Registry) -> io::Result<()> {
+        match &self.inner {
+            Some((queue, ..)) if queue.ptr_eq(&Arc::downgrade(registry.mem_queue())) => Ok(()),
+            Some(_) => Err(different_registry()),
+            None => Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                "I/O source not registered with `Registry`",
+            )),
+        }
+    }
+
+    /// Add an event with `readiness` for the registered token, if any. The
+    /// readiness is limited to the registered interests, errors are always
+    /// reported.
+    pub(crate) fn notify(&self, readiness: u8) {
+        if let Some((queue, token, interests)) = &self.inner {
+            let mut mask = ERROR;
+            if interests.is_readable() {
+                mask |= READABLE | READ_CLOSED;
+            }
+            if interests.is_writable() {
+                mask |= WRITABLE | WRITE_CLOSED;
+            }
+            let readiness = readiness & mask;
+            if readiness != 0 {
+                if let Some(queue) = queue.upgrade() {
+                    queue.notify(*token, readiness);
+                }
+            }
+        }
+    }
+
+    /// Drop the registration, if any.
+    pub(crate) fn clear(&mut self) {
+        self.inner = None;
+    }
+}
+
+fn different_registry() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        "I/O source already registered with a different `Registry`",
+    )
+}
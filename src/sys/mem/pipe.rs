@@ -0,0 +1,290 @@
+//! In-memory pipe.
+//!
+//! See the [`new`] function for documentation.
+
+use std::collections::VecDeque;
+use std::io::{self, Read, Write};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use crate::sys::mem::{Registration, ERROR, READABLE, READ_CLOSED, WRITABLE, WRITE_CLOSED};
+use crate::{event, Interest, Registry, Token};
+
+/// Number of bytes that can be buffered in a pipe, the default on Linux.
+const CAPACITY: usize = 64 * 1024;
+
+/// Create a new in-memory pipe.
+///
+/// This is an in-process replacement for [`mio::unix::pipe`], it can be used
+/// to communicate between threads and to test code using Mio without the
+/// `os-poll` feature. Up to 64 KiB can be buffered in the pipe, after which
+/// writing returns a [`WouldBlock`] error until the data is read.
+///
+/// [`mio::unix::pipe`]: https://docs.rs/mio/latest/mio/unix/pipe/index.html
+/// [`WouldBlock`]: io::ErrorKind::WouldBlock
+///
+/// # Events
+///
+/// The [`Sender`] can be registered with [`WRITABLE`] interest to receive
+/// [writable events], the [`Receiver`] with [`READABLE`] interest. Once data is
+/// written to the `Sender` the `Receiver` will receive an [readable event].
+///
+/// If the `Sender` is dropped the `Receiver` receives an event for which
+/// [`is_read_closed`] returns true, reading will return zero bytes once all
+/// data is read. If the `Receiver` is dropped the `Sender` receives an event
+/// for which [`is_write_closed`] returns true, writing will return a
+/// [`BrokenPipe`] error.
+///
+/// Like the OS selectors events are edge-triggered: the `Receiver` only
+/// receives another event once data is written after it was read until it
+/// returned a `WouldBlock` error.
+///
+/// [`WRITABLE`]: Interest::WRITABLE
+/// [writable events]: event::Event::is_writable
+/// [`READABLE`]: Interest::READABLE
+/// [readable event]: event::Event::is_readable
+/// [`is_read_closed`]: event::Event::is_read_closed
+/// [`is_write_closed`]: event::Event::is_write_closed
+/// [`BrokenPipe`]: io::ErrorKind::BrokenPipe
+///
+/// # Examples
+///
+/// ```
+/// use std::io::{self, Read, Write};
+///
+/// use mio::{Poll, Events, Interest, Token};
+/// use mio::mem::pipe;
+///
+/// const PIPE_RECV: Token = Token(0);
+///
+/// # fn main() -> io::Result<()> {
+/// let mut poll = Poll::new()?;
+/// let mut events = Events::with_capacity(8);
+///
+/// let (mut sender, mut receiver) = pipe::new()?;
+/// poll.registry().register(&mut receiver, PIPE_RECV, Interest::READABLE)?;
+///
+/// sender.write_all(b"Hello world")?;
+///
+/// poll.poll(&mut events, None)?;
+/// for event in events.iter() {
+///     assert_eq!(event.token(), PIPE_RECV);
+///     let mut buf = [0; 11];
+///     let n = receiver.read(&mut buf)?;
+///     assert_eq!(&buf[..n], b"Hello world");
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn new() -> io::Result<(Sender, Receiver)> {
+    let shared = Arc::new(Mutex::new(State {
+        buf: VecDeque::new(),
+        sender_closed: false,
+        receiver_closed: false,
+        sender: Registration::default(),
+        receiver: Registration::default(),
+    }));
+    let sender = Sender {
+        shared: shared.clone(),
+    };
+    let receiver = Receiver { shared };
+    Ok((sender, receiver))
+}
+
+/// State shared between the `Sender` and `Receiver`.
+#[derive(Debug)]
+struct State {
+    buf: VecDeque<u8>,
+    sender_closed: bool,
+    receiver_closed: bool,
+    sender: Registration,
+    receiver: Registration,
+}
+
+impl State {
+    /// Readiness of the sending end.
+    fn sender_readiness(&self) -> u8 {
+        if self.receiver_closed {
+            WRITABLE | WRITE_CLOSED | ERROR
+        } else if self.buf.len() < CAPACITY {
+            WRITABLE
+        } else {
+            0
+        }
+    }
+
+    /// Readiness of the receiving end.
+    fn receiver_readiness(&self) -> u8 {
+        let mut readiness = 0;
+        if !self.buf.is_empty() {
+            readiness |= READABLE;
+        }
+        if self.sender_closed {
+            readiness |= READABLE | READ_CLOSED;
+        }
+        readiness
+    }
+}
+
+fn lock(shared: &Mutex<State>) -> MutexGuard<'_, State> {
+    shared.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Sending end of an in-memory pipe.
+///
+/// See [`new`] for documentation, including examples.
+#[derive(Debug)]
+pub struct Sender {
+    shared: Arc<Mutex<State>>,
+}
+
+impl event::Source for Sender {
+    fn register(
+        &mut self,
+        registry: &Registry,
+        token: Token,
+        interests: Interest,
+    ) -> io::Result<()> {
+        let mut state = lock(&self.shared);
+        state.sender.register(registry, token, interests)?;
+        state.sender.notify(state.sender_readiness());
+        Ok(())
+    }
+
+    fn reregister(
+        &mut self,
+        registry: &Registry,
+        token: Token,
+        interests: Interest,
+    ) -> io::Result<()> {
+        let mut state = lock(&self.shared);
+        state.sender.reregister(registry, token, interests)?;
+        state.sender.notify(state.sender_readiness());
+        Ok(())
+    }
+
+    fn deregister(&mut self, registry: &Registry) -> io::Result<()> {
+        lock(&self.shared).sender.deregister(registry)
+    }
+}
+
+impl Write for Sender {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        (&*self).write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        (&*self).flush()
+    }
+}
+
+impl Write for &Sender {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut state = lock(&self.shared);
+        if state.receiver_closed {
+            return Err(io::ErrorKind::BrokenPipe.into());
+        }
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        let n = buf.len().min(CAPACITY - state.buf.len());
+        if n == 0 {
+            return Err(io::ErrorKind::WouldBlock.into());
+        }
+        let was_empty = state.buf.is_empty();
+        state.buf.extend(&buf[..n]);
+        if was_empty {
+            state.receiver.notify(READABLE);
+        }
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for Sender {
+    fn drop(&mut self) {
+        let mut state = lock(&self.shared);
+        state.sender_closed = true;
+        state.sender.clear();
+        state.receiver.notify(READABLE | READ_CLOSED);
+    }
+}
+
+/// Receiving end of an in-memory pipe.
+///
+/// See [`new`] for documentation, including examples.
+#[derive(Debug)]
+pub struct Receiver {
+    shared: Arc<Mutex<State>>,
+}
+
+impl event::Source for Receiver {
+    fn register(
+        &mut self,
+        registry: &Registry,
+        token: Token,
+        interests: Interest,
+    ) -> io::Result<()> {
+        let mut state = lock(&self.shared);
+        state.receiver.register(registry, token, interests)?;
+        state.receiver.notify(state.receiver_readiness());
+        Ok(())
+    }
+
+    fn reregister(
+        &mut self,
+        registry: &Registry,
+        token: Token,
+        interests: Interest,
+    ) -> io::Result<()> {
+        let mut state = lock(&self.shared);
+        state.receiver.reregister(registry, token, interests)?;
+        state.receiver.notify(state.receiver_readiness());
+        Ok(())
+    }
+
+    fn deregister(&mut self, registry: &Registry) -> io::Result<()> {
+        lock(&self.shared).receiver.deregister(registry)
+    }
+}
+
+impl Read for Receiver {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        (&*self).read(buf)
+    }
+}
+
+impl Read for &Receiver {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut state = lock(&self.shared);
+        if state.buf.is_empty() {
+            return if state.sender_closed || buf.is_empty() {
+                Ok(0)
+            } else {
+                Err(io::ErrorKind::WouldBlock.into())
+            };
+        }
+
+        let was_full = state.buf.len() == CAPACITY;
+        let n = state.buf.len().min(buf.len());
+        for (dst, src) in buf.iter_mut().zip(state.buf.drain(..n)) {
+            *dst = src;
+        }
+        if was_full && n != 0 {
+            state.sender.notify(WRITABLE);
+        }
+        Ok(n)
+    }
+}
+
+impl Drop for Receiver {
+    fn drop(&mut self) {
+        let mut state = lock(&self.shared);
+        state.receiver_closed = true;
+        state.receiver.clear();
+        state.sender.notify(WRITABLE | WRITE_CLOSED | ERROR);
+    }
+}
@@ -1,3 +1,5 @@
+// Unused on Windows without the `net` feature.
+#[allow(unused_macros)]
 macro_rules! os_required {
     () => {
         panic!("mio must be compiled with `os-poll` to run.")
@@ -13,6 +15,8 @@ mod waker;
 pub(crate) use self::waker::Waker;
 
 cfg_net! {
+    #[cfg(not(target_os = "wasi"))]
+    pub(crate) mod net;
     pub(crate) mod tcp;
     pub(crate) mod udp;
     #[cfg(unix)]
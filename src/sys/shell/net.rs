@@ -0,0 +1,76 @@
+#[cfg(any(
+    target_os = "android",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "tvos",
+    target_os = "watchos",
+))]
+use std::fs::File;
+use std::io;
+use std::time::Duration;
+
+#[cfg(unix)]
+pub(crate) fn is_fd_exhaustion(_: &io::Error) -> bool {
+    os_required!();
+}
+
+pub(crate) fn set_linger<S>(_: &S, _: Option<Duration>) -> io::Result<()> {
+    os_required!();
+}
+
+pub(crate) fn linger<S>(_: &S) -> io::Result<Option<Duration>> {
+    os_required!();
+}
+
+pub(crate) fn set_send_buffer_size<S>(_: &S, _: u32) -> io::Result<()> {
+    os_required!();
+}
+
+pub(crate) fn send_buffer_size<S>(_: &S) -> io::Result<u32> {
+    os_required!();
+}
+
+pub(crate) fn set_recv_buffer_size<S>(_: &S, _: u32) -> io::Result<()> {
+    os_required!();
+}
+
+pub(crate) fn recv_buffer_size<S>(_: &S) -> io::Result<u32> {
+    os_required!();
+}
+
+#[cfg(any(
+    target_os = "android",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "tvos",
+    target_os = "watchos",
+))]
+pub(crate) fn send_file<S>(_: &S, _: &File, _: u64, _: usize) -> io::Result<usize> {
+    os_required!();
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub(crate) fn set_zerocopy<S>(_: &S, _: bool) -> io::Result<()> {
+    os_required!();
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub(crate) fn zerocopy<S>(_: &S) -> io::Result<bool> {
+    os_required!();
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub(crate) fn send_zerocopy<S>(_: &S, _: &[u8]) -> io::Result<usize> {
+    os_required!();
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub(crate) fn recv_zerocopy(_: std::os::unix::io::RawFd) -> io::Result<(u32, u32, bool)> {
+    os_required!();
+}
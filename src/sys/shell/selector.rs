@@ -1,34 +1,103 @@
+//! User space selector used when the `os-poll` feature is disabled.
+//!
+//! It can't poll OS resources, but it does support the in-process sources
+//! found in the `mem` module and `Waker`.
+
+use std::cmp::min;
 use std::io;
 #[cfg(unix)]
 use std::os::unix::io::{AsRawFd, RawFd};
-use std::time::Duration;
-
-pub type Event = usize;
+#[cfg(debug_assertions)]
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::sys::mem::Queue;
+#[cfg(all(
+    any(unix, target_os = "wasi"),
+    any(feature = "os-ext", feature = "net")
+))]
+use crate::Interest;
+use crate::Token;
+
+/// Unique id for use as `SelectorId`.
+#[cfg(debug_assertions)]
+static NEXT_ID: AtomicUsize = AtomicUsize::new(1);
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Event {
+    token: Token,
+    readiness: u8,
+}
 
 pub type Events = Vec<Event>;
 
 #[derive(Debug)]
-pub struct Selector {}
+pub struct Selector {
+    #[cfg(debug_assertions)]
+    id: usize,
+    /// Events of the in-process sources and `Waker`s, shared with the clones
+    /// of the selector and the sources registered with it.
+    queue: Arc<Queue>,
+    #[cfg(debug_assertions)]
+    has_waker: Arc<AtomicBool>,
+}
 
 impl Selector {
-    pub fn try_clone(&self) -> io::Result<Selector> {
-        os_required!();
+    pub fn new() -> io::Result<Selector> {
+        Ok(Selector {
+            #[cfg(debug_assertions)]
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            queue: Arc::new(Queue::new()),
+            #[cfg(debug_assertions)]
+            has_waker: Arc::new(AtomicBool::new(false)),
+        })
     }
 
-    pub fn select(&self, _: &mut Events, _: Option<Duration>) -> io::Result<()> {
-        os_required!();
+    pub fn try_clone(&self) -> io::Result<Selector> {
+        Ok(Selector {
+            #[cfg(debug_assertions)]
+            id: self.id,
+            queue: self.queue.clone(),
+            #[cfg(debug_assertions)]
+            has_waker: self.has_waker.clone(),
+        })
+    }
+
+    pub fn select(&self, events: &mut Events, timeout: Option<Duration>) -> io::Result<()> {
+        events.clear();
+
+        // If the deadline overflows we'll wait forever, like we do without a
+        // timeout.
+        let deadline = timeout.and_then(|timeout| Instant::now().checked_add(timeout));
+        let mut pending = self.queue.wait(deadline);
+        let n = min(pending.len(), events.capacity());
+        events.extend(
+            pending
+                .drain(..n)
+                .map(|(token, readiness)| Event { token, readiness }),
+        );
+        Ok(())
     }
 
     #[cfg(all(debug_assertions, not(target_os = "wasi")))]
     pub fn register_waker(&self) -> bool {
-        os_required!();
+        self.has_waker.swap(true, Ordering::AcqRel)
+    }
+
+    /// Add an event with `readiness` for `token`.
+    pub(crate) fn notify(&self, token: Token, readiness: u8) {
+        self.queue.notify(token, readiness)
+    }
+
+    /// Returns the queue of the in-process sources.
+    pub(crate) fn queue(&self) -> &Arc<Queue> {
+        &self.queue
     }
 }
 
 #[cfg(unix)]
 cfg_any_os_ext! {
-    use crate::{Interest, Token};
-
     impl Selector {
         pub fn register(&self, _: RawFd, _: Token, _: Interest) -> io::Result<()> {
             os_required!();
@@ -46,8 +115,6 @@ cfg_any_os_ext! {
 
 #[cfg(target_os = "wasi")]
 cfg_any_os_ext! {
-    use crate::{Interest, Token};
-
     impl Selector {
         pub fn register(&self, _: wasi::Fd, _: Token, _: Interest) -> io::Result<()> {
             os_required!();
@@ -67,7 +134,7 @@ cfg_io_source! {
     #[cfg(debug_assertions)]
     impl Selector {
         pub fn id(&self) -> usize {
-            os_required!();
+            self.id
         }
     }
 }
@@ -81,47 +148,71 @@ impl AsRawFd for Selector {
 
 #[allow(clippy::trivially_copy_pass_by_ref)]
 pub mod event {
+    use crate::sys::mem::{ERROR, READABLE, READ_CLOSED, WRITABLE, WRITE_CLOSED};
     use crate::sys::Event;
     use crate::Token;
     use std::fmt;
 
-    pub fn token(_: &Event) -> Token {
-        os_required!();
+    pub fn token(event: &Event) -> Token {
+        event.token
     }
 
-    pub fn is_readable(_: &Event) -> bool {
-        os_required!();
+    pub fn is_readable(event: &Event) -> bool {
+        event.readiness & READABLE != 0
     }
 
-    pub fn is_writable(_: &Event) -> bool {
-        os_required!();
+    pub fn is_writable(event: &Event) -> bool {
+        event.readiness & WRITABLE != 0
     }
 
-    pub fn is_error(_: &Event) -> bool {
-        os_required!();
+    pub fn is_error(event: &Event) -> bool {
+        event.readiness & ERROR != 0
     }
 
-    pub fn is_read_closed(_: &Event) -> bool {
-        os_required!();
+    pub fn is_read_closed(event: &Event) -> bool {
+        event.readiness & READ_CLOSED != 0
     }
 
-    pub fn is_write_closed(_: &Event) -> bool {
-        os_required!();
+    pub fn is_write_closed(event: &Event) -> bool {
+        event.readiness & WRITE_CLOSED != 0
     }
 
     pub fn is_priority(_: &Event) -> bool {
-        os_required!();
+        // Not supported.
+        false
     }
 
     pub fn is_aio(_: &Event) -> bool {
-        os_required!();
+        // Not supported.
+        false
     }
 
     pub fn is_lio(_: &Event) -> bool {
-        os_required!();
+        // Not supported.
+        false
+    }
+
+    pub fn merge(event: &mut Event, other: &Event) -> bool {
+        event.readiness |= other.readiness;
+        true
+    }
+
+    #[cfg(all(
+        feature = "readiness-cache",
+        any(feature = "net", all(unix, feature = "os-ext"))
+    ))]
+    pub fn push(events: &mut Vec<Event>, token: Token, readiness: u8) -> bool {
+        if events.len() >= events.capacity() {
+            return false;
+        }
+        events.push(Event { token, readiness });
+        true
     }
 
-    pub fn debug_details(_: &mut fmt::Formatter<'_>, _: &Event) -> fmt::Result {
-        os_required!();
+    pub fn debug_details(f: &mut fmt::Formatter<'_>, event: &Event) -> fmt::Result {
+        f.debug_struct("shell_event")
+            .field("token", &event.token)
+            .field("readiness", &format_args!("{:#07b}", event.readiness))
+            .finish()
     }
 }
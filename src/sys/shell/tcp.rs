@@ -1,13 +1,36 @@
 use std::io;
 use std::net::{self, SocketAddr};
+#[cfg(any(target_os = "android", target_os = "linux"))]
+use std::time::Duration;
 
+#[cfg(any(
+    target_os = "android",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "tvos",
+    target_os = "watchos",
+))]
+use crate::net::TcpInfo;
 #[cfg(not(target_os = "wasi"))]
-pub(crate) fn new_for_addr(_: SocketAddr) -> io::Result<i32> {
+use crate::net::TcpKeepalive;
+
+#[cfg(not(target_os = "wasi"))]
+pub(crate) type TcpSocket = i32;
+
+#[cfg(not(target_os = "wasi"))]
+pub(crate) fn new_v4_socket() -> io::Result<TcpSocket> {
+    os_required!();
+}
+
+#[cfg(not(target_os = "wasi"))]
+pub(crate) fn new_v6_socket() -> io::Result<TcpSocket> {
     os_required!();
 }
 
 #[cfg(not(target_os = "wasi"))]
-pub(crate) fn bind(_: &net::TcpListener, _: SocketAddr) -> io::Result<()> {
+pub(crate) fn bind(_: TcpSocket, _: SocketAddr) -> io::Result<()> {
     os_required!();
 }
 
@@ -21,8 +44,86 @@ pub(crate) fn listen(_: &net::TcpListener, _: u32) -> io::Result<()> {
     os_required!();
 }
 
-#[cfg(unix)]
-pub(crate) fn set_reuseaddr(_: &net::TcpListener, _: bool) -> io::Result<()> {
+#[cfg(not(target_os = "wasi"))]
+pub(crate) fn close(_: TcpSocket) {
+    os_required!();
+}
+
+#[cfg(not(target_os = "wasi"))]
+pub(crate) fn set_reuseaddr(_: TcpSocket, _: bool) -> io::Result<()> {
+    os_required!();
+}
+
+#[cfg(not(target_os = "wasi"))]
+pub(crate) fn reuseaddr(_: TcpSocket) -> io::Result<bool> {
+    os_required!();
+}
+
+#[cfg(all(unix, not(any(target_os = "illumos", target_os = "solaris"))))]
+pub(crate) fn set_reuseport(_: TcpSocket, _: bool) -> io::Result<()> {
+    os_required!();
+}
+
+#[cfg(all(unix, not(any(target_os = "illumos", target_os = "solaris"))))]
+pub(crate) fn reuseport(_: TcpSocket) -> io::Result<bool> {
+    os_required!();
+}
+
+#[cfg(not(target_os = "wasi"))]
+pub(crate) fn set_only_v6(_: TcpSocket, _: bool) -> io::Result<()> {
+    os_required!();
+}
+
+#[cfg(not(target_os = "wasi"))]
+pub(crate) fn only_v6(_: TcpSocket) -> io::Result<bool> {
+    os_required!();
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub(crate) fn bind_device(_: TcpSocket, _: Option<&[u8]>) -> io::Result<()> {
+    os_required!();
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub(crate) fn device(_: TcpSocket) -> io::Result<Option<Vec<u8>>> {
+    os_required!();
+}
+
+#[cfg(not(target_os = "wasi"))]
+pub(crate) fn set_keepalive(_: &net::TcpStream, _: Option<TcpKeepalive>) -> io::Result<()> {
+    os_required!();
+}
+
+#[cfg(not(target_os = "wasi"))]
+pub(crate) fn keepalive(_: &net::TcpStream) -> io::Result<Option<TcpKeepalive>> {
+    os_required!();
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub(crate) fn set_user_timeout(_: &net::TcpStream, _: Option<Duration>) -> io::Result<()> {
+    os_required!();
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub(crate) fn user_timeout(_: &net::TcpStream) -> io::Result<Option<Duration>> {
+    os_required!();
+}
+
+#[cfg(any(
+    target_os = "android",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "tvos",
+    target_os = "watchos",
+))]
+pub(crate) fn tcp_info(_: &net::TcpStream) -> io::Result<TcpInfo> {
+    os_required!();
+}
+
+#[cfg(not(target_os = "wasi"))]
+pub(crate) fn local_addr(_: TcpSocket) -> io::Result<SocketAddr> {
     os_required!();
 }
 
@@ -1,16 +1,23 @@
+use crate::sys::mem::READABLE;
 use crate::sys::Selector;
 use crate::Token;
 use std::io;
 
 #[derive(Debug)]
-pub struct Waker {}
+pub struct Waker {
+    selector: Selector,
+    token: Token,
+}
 
 impl Waker {
-    pub fn new(_: &Selector, _: Token) -> io::Result<Waker> {
-        os_required!();
+    pub fn new(selector: &Selector, token: Token) -> io::Result<Waker> {
+        selector
+            .try_clone()
+            .map(|selector| Waker { selector, token })
     }
 
     pub fn wake(&self) -> io::Result<()> {
-        os_required!();
+        self.selector.notify(self.token, READABLE);
+        Ok(())
     }
 }
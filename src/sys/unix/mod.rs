@@ -24,7 +24,7 @@ cfg_os_poll! {
     pub(crate) use self::waker::Waker;
 
     cfg_net! {
-        mod net;
+        pub(crate) mod net;
 
         pub(crate) mod tcp;
         pub(crate) mod udp;
@@ -56,6 +56,9 @@ cfg_os_poll! {
 
     cfg_os_ext! {
         pub(crate) mod pipe;
+
+        #[cfg(any(target_os = "android", target_os = "linux"))]
+        pub(crate) mod splice;
     }
 }
 
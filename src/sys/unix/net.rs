@@ -1,6 +1,20 @@
-use std::io;
-use std::mem::size_of;
+use std::convert::TryInto;
+#[cfg(any(
+    target_os = "android",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "tvos",
+    target_os = "watchos",
+))]
+use std::fs::File;
+use std::mem::{size_of, MaybeUninit};
 use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::time::Duration;
+use std::{cmp, io};
 
 pub(crate) fn new_ip_socket(addr: SocketAddr, socket_type: libc::c_int) -> io::Result<libc::c_int> {
     let domain = match addr {
@@ -176,3 +190,280 @@ pub(crate) unsafe fn to_socket_addr(
         _ => Err(io::ErrorKind::InvalidInput.into()),
     }
 }
+
+/// Returns `true` if `err` is caused by the process or system running out of
+/// file descriptors.
+pub(crate) fn is_fd_exhaustion(err: &io::Error) -> bool {
+    matches!(err.raw_os_error(), Some(libc::EMFILE) | Some(libc::ENFILE))
+}
+
+pub(crate) fn set_linger<S: AsRawFd>(socket: &S, linger: Option<Duration>) -> io::Result<()> {
+    let linger = libc::linger {
+        l_onoff: libc::c_int::from(linger.is_some()),
+        l_linger: linger.map_or(0, |linger| {
+            cmp::min(linger.as_secs(), libc::c_int::MAX as u64) as libc::c_int
+        }),
+    };
+    set_option(socket.as_raw_fd(), libc::SOL_SOCKET, SO_LINGER, linger)
+}
+
+pub(crate) fn linger<S: AsRawFd>(socket: &S) -> io::Result<Option<Duration>> {
+    option::<libc::linger>(socket.as_raw_fd(), libc::SOL_SOCKET, SO_LINGER).map(|linger| {
+        if linger.l_onoff == 0 {
+            None
+        } else {
+            Some(Duration::from_secs(linger.l_linger as u64))
+        }
+    })
+}
+
+/// `SO_LINGER` is in ticks on Apple platforms, `SO_LINGER_SEC` is in seconds.
+#[cfg(any(
+    target_os = "ios",
+    target_os = "macos",
+    target_os = "tvos",
+    target_os = "watchos",
+))]
+const SO_LINGER: libc::c_int = libc::SO_LINGER_SEC;
+#[cfg(not(any(
+    target_os = "ios",
+    target_os = "macos",
+    target_os = "tvos",
+    target_os = "watchos",
+)))]
+const SO_LINGER: libc::c_int = libc::SO_LINGER;
+
+pub(crate) fn set_send_buffer_size<S: AsRawFd>(socket: &S, size: u32) -> io::Result<()> {
+    let size = size.try_into().unwrap_or(libc::c_int::MAX);
+    set_option(socket.as_raw_fd(), libc::SOL_SOCKET, libc::SO_SNDBUF, size)
+}
+
+pub(crate) fn send_buffer_size<S: AsRawFd>(socket: &S) -> io::Result<u32> {
+    option::<libc::c_int>(socket.as_raw_fd(), libc::SOL_SOCKET, libc::SO_SNDBUF)
+        .map(|size| size as u32)
+}
+
+pub(crate) fn set_recv_buffer_size<S: AsRawFd>(socket: &S, size: u32) -> io::Result<()> {
+    let size = size.try_into().unwrap_or(libc::c_int::MAX);
+    set_option(socket.as_raw_fd(), libc::SOL_SOCKET, libc::SO_RCVBUF, size)
+}
+
+pub(crate) fn recv_buffer_size<S: AsRawFd>(socket: &S) -> io::Result<u32> {
+    option::<libc::c_int>(socket.as_raw_fd(), libc::SOL_SOCKET, libc::SO_RCVBUF)
+        .map(|size| size as u32)
+}
+
+#[cfg(any(
+    target_os = "android",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "tvos",
+    target_os = "watchos",
+))]
+pub(crate) fn send_file<S: AsRawFd>(
+    socket: &S,
+    file: &File,
+    offset: u64,
+    len: usize,
+) -> io::Result<usize> {
+    if len == 0 {
+        // Zero means "until the end of the file" on some platforms.
+        return Ok(0);
+    }
+
+    let offset: libc::off_t = offset
+        .try_into()
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "offset too large"))?;
+
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    {
+        let mut offset = offset;
+        // Linux transfers at most 0x7ffff000 bytes in a single call.
+        let len = cmp::min(len, 0x7fff_f000);
+        syscall!(sendfile(
+            socket.as_raw_fd(),
+            file.as_raw_fd(),
+            &mut offset,
+            len
+        ))
+        .map(|n| n as usize)
+    }
+
+    // On the BSDs the number of bytes sent is also set if the call fails with
+    // `EAGAIN`, so report the partial progress.
+    #[cfg(any(target_os = "dragonfly", target_os = "freebsd"))]
+    {
+        let mut sent: libc::off_t = 0;
+        match syscall!(sendfile(
+            file.as_raw_fd(),
+            socket.as_raw_fd(),
+            offset,
+            len,
+            std::ptr::null_mut(),
+            &mut sent,
+            0
+        )) {
+            Err(ref err) if err.kind() == io::ErrorKind::WouldBlock && sent > 0 => {
+                Ok(sent as usize)
+            }
+            res => res.map(|_| sent as usize),
+        }
+    }
+
+    #[cfg(any(
+        target_os = "ios",
+        target_os = "macos",
+        target_os = "tvos",
+        target_os = "watchos",
+    ))]
+    {
+        let mut sent: libc::off_t = len.try_into().unwrap_or(libc::off_t::MAX);
+        match syscall!(sendfile(
+            file.as_raw_fd(),
+            socket.as_raw_fd(),
+            offset,
+            &mut sent,
+            std::ptr::null_mut(),
+            0
+        )) {
+            Err(ref err) if err.kind() == io::ErrorKind::WouldBlock && sent > 0 => {
+                Ok(sent as usize)
+            }
+            res => res.map(|_| sent as usize),
+        }
+    }
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub(crate) fn set_zerocopy<S: AsRawFd>(socket: &S, zerocopy: bool) -> io::Result<()> {
+    let zerocopy = zerocopy as libc::c_int;
+    set_option(socket.as_raw_fd(), libc::SOL_SOCKET, SO_ZEROCOPY, zerocopy)
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub(crate) fn zerocopy<S: AsRawFd>(socket: &S) -> io::Result<bool> {
+    option::<libc::c_int>(socket.as_raw_fd(), libc::SOL_SOCKET, SO_ZEROCOPY)
+        .map(|zerocopy| zerocopy != 0)
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub(crate) fn send_zerocopy<S: AsRawFd>(socket: &S, buf: &[u8]) -> io::Result<usize> {
+    syscall!(send(
+        socket.as_raw_fd(),
+        buf.as_ptr() as *const _,
+        buf.len(),
+        MSG_ZEROCOPY | libc::MSG_NOSIGNAL
+    ))
+    .map(|n| n as usize)
+}
+
+/// Receive a single `MSG_ZEROCOPY` completion notification from the error
+/// queue of `socket`, returning the range of completed sends (inclusive) and
+/// whether the kernel fell back to copying the data.
+///
+/// Returns a `WouldBlock` error if the error queue is empty. Other errors
+/// queued on the socket, e.g. ICMP errors, are returned as error.
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub(crate) fn recv_zerocopy(socket: RawFd) -> io::Result<(u32, u32, bool)> {
+    // Large enough for a `sock_extended_err` and the offending address.
+    let mut control = [0u64; 16];
+    let mut msg: libc::msghdr = unsafe { std::mem::zeroed() };
+    msg.msg_control = control.as_mut_ptr() as *mut _;
+    msg.msg_controllen = size_of::<[u64; 16]>() as _;
+    syscall!(recvmsg(socket, &mut msg, libc::MSG_ERRQUEUE))?;
+
+    // This is safe because `recvmsg` initialised the control messages.
+    let mut cmsg = unsafe { libc::CMSG_FIRSTHDR(&msg) };
+    while !cmsg.is_null() {
+        let (level, ty) = unsafe { ((*cmsg).cmsg_level, (*cmsg).cmsg_type) };
+        if (level == libc::SOL_IP && ty == IP_RECVERR)
+            || (level == libc::SOL_IPV6 && ty == IPV6_RECVERR)
+        {
+            let err = unsafe {
+                std::ptr::read_unaligned(libc::CMSG_DATA(cmsg) as *const sock_extended_err)
+            };
+            return if err.ee_origin == SO_EE_ORIGIN_ZEROCOPY && err.ee_errno == 0 {
+                let copied = err.ee_code & SO_EE_CODE_ZEROCOPY_COPIED != 0;
+                Ok((err.ee_info, err.ee_data, copied))
+            } else {
+                Err(io::Error::from_raw_os_error(err.ee_errno as i32))
+            };
+        }
+        cmsg = unsafe { libc::CMSG_NXTHDR(&msg, cmsg) };
+    }
+    Err(io::Error::new(
+        io::ErrorKind::InvalidData,
+        "unexpected message on the socket error queue",
+    ))
+}
+
+// Not all constants and types are available in (older versions of) the `libc`
+// crate, so we define them ourselves.
+#[cfg(all(
+    any(target_os = "android", target_os = "linux"),
+    not(target_arch = "sparc64")
+))]
+const SO_ZEROCOPY: libc::c_int = 60;
+#[cfg(all(
+    any(target_os = "android", target_os = "linux"),
+    target_arch = "sparc64"
+))]
+const SO_ZEROCOPY: libc::c_int = 0x3e;
+#[cfg(any(target_os = "android", target_os = "linux"))]
+const MSG_ZEROCOPY: libc::c_int = 0x400_0000;
+#[cfg(any(target_os = "android", target_os = "linux"))]
+const IP_RECVERR: libc::c_int = 11;
+#[cfg(any(target_os = "android", target_os = "linux"))]
+const IPV6_RECVERR: libc::c_int = 25;
+#[cfg(any(target_os = "android", target_os = "linux"))]
+const SO_EE_ORIGIN_ZEROCOPY: u8 = 5;
+#[cfg(any(target_os = "android", target_os = "linux"))]
+const SO_EE_CODE_ZEROCOPY_COPIED: u8 = 1;
+
+/// `struct sock_extended_err` from `linux/errqueue.h`.
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[repr(C)]
+#[allow(non_camel_case_types, dead_code)]
+struct sock_extended_err {
+    ee_errno: u32,
+    ee_origin: u8,
+    ee_type: u8,
+    ee_code: u8,
+    ee_pad: u8,
+    ee_info: u32,
+    ee_data: u32,
+}
+
+pub(crate) fn set_option<T>(
+    socket: RawFd,
+    level: libc::c_int,
+    name: libc::c_int,
+    value: T,
+) -> io::Result<()> {
+    syscall!(setsockopt(
+        socket,
+        level,
+        name,
+        &value as *const T as *const libc::c_void,
+        size_of::<T>() as libc::socklen_t,
+    ))?;
+    Ok(())
+}
+
+pub(crate) fn option<T>(socket: RawFd, level: libc::c_int, name: libc::c_int) -> io::Result<T> {
+    let mut value: MaybeUninit<T> = MaybeUninit::zeroed();
+    let mut length = size_of::<T>() as libc::socklen_t;
+    syscall!(getsockopt(
+        socket,
+        level,
+        name,
+        value.as_mut_ptr() as *mut libc::c_void,
+        &mut length,
+    ))?;
+    // This is safe because `value` is zeroed and (partially) initialised by
+    // `getsockopt`, only integers (or structs of them) are used for `T`.
+    Ok(unsafe { value.assume_init() })
+}
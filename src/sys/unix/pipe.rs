@@ -291,7 +291,7 @@ impl Sender {
     where
         F: FnOnce() -> io::Result<T>,
     {
-        self.inner.do_io(|_| f())
+        self.inner.do_io_for(Interest::WRITABLE, |_| f())
     }
 }
 
@@ -317,33 +317,49 @@ impl event::Source for Sender {
     fn deregister(&mut self, registry: &Registry) -> io::Result<()> {
         self.inner.deregister(registry)
     }
+
+    fn transfer(
+        &mut self,
+        from: &Registry,
+        to: &Registry,
+        token: Token,
+        interests: Interest,
+    ) -> io::Result<()> {
+        self.inner.transfer(from, to, token, interests)
+    }
 }
 
 impl Write for Sender {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        self.inner.do_io(|mut sender| sender.write(buf))
+        self.inner
+            .do_io_for(Interest::WRITABLE, |mut sender| sender.write(buf))
     }
 
     fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
-        self.inner.do_io(|mut sender| sender.write_vectored(bufs))
+        self.inner
+            .do_io_for(Interest::WRITABLE, |mut sender| sender.write_vectored(bufs))
     }
 
     fn flush(&mut self) -> io::Result<()> {
-        self.inner.do_io(|mut sender| sender.flush())
+        self.inner
+            .do_io_for(Interest::WRITABLE, |mut sender| sender.flush())
     }
 }
 
 impl Write for &Sender {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        self.inner.do_io(|mut sender| sender.write(buf))
+        self.inner
+            .do_io_for(Interest::WRITABLE, |mut sender| sender.write(buf))
     }
 
     fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
-        self.inner.do_io(|mut sender| sender.write_vectored(bufs))
+        self.inner
+            .do_io_for(Interest::WRITABLE, |mut sender| sender.write_vectored(bufs))
     }
 
     fn flush(&mut self) -> io::Result<()> {
-        self.inner.do_io(|mut sender| sender.flush())
+        self.inner
+            .do_io_for(Interest::WRITABLE, |mut sender| sender.flush())
     }
 }
 
@@ -357,6 +373,19 @@ impl From<ChildStdin> for Sender {
     }
 }
 
+#[cfg(any(target_os = "android", target_os = "linux"))]
+impl crate::sys::splice::private::Sealed for Sender {
+    fn splice_io<F, R>(&self, interest: Interest, f: F) -> io::Result<R>
+    where
+        F: FnOnce(RawFd) -> io::Result<R>,
+    {
+        self.inner.do_io_for(interest, |inner| f(inner.as_raw_fd()))
+    }
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+impl crate::unix::SpliceWrite for Sender {}
+
 impl FromRawFd for Sender {
     unsafe fn from_raw_fd(fd: RawFd) -> Sender {
         Sender {
@@ -456,7 +485,7 @@ impl Receiver {
     where
         F: FnOnce() -> io::Result<T>,
     {
-        self.inner.do_io(|_| f())
+        self.inner.do_io_for(Interest::READABLE, |_| f())
     }
 }
 
@@ -482,25 +511,41 @@ impl event::Source for Receiver {
     fn deregister(&mut self, registry: &Registry) -> io::Result<()> {
         self.inner.deregister(registry)
     }
+
+    fn transfer(
+        &mut self,
+        from: &Registry,
+        to: &Registry,
+        token: Token,
+        interests: Interest,
+    ) -> io::Result<()> {
+        self.inner.transfer(from, to, token, interests)
+    }
 }
 
 impl Read for Receiver {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
-        self.inner.do_io(|mut sender| sender.read(buf))
+        self.inner
+            .do_read(buf.len(), |mut receiver| receiver.read(buf))
     }
 
     fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
-        self.inner.do_io(|mut sender| sender.read_vectored(bufs))
+        let len = bufs.iter().map(|buf| buf.len()).sum();
+        self.inner
+            .do_read(len, |mut receiver| receiver.read_vectored(bufs))
     }
 }
 
 impl Read for &Receiver {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
-        self.inner.do_io(|mut sender| sender.read(buf))
+        self.inner
+            .do_read(buf.len(), |mut receiver| receiver.read(buf))
     }
 
     fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
-        self.inner.do_io(|mut sender| sender.read_vectored(bufs))
+        let len = bufs.iter().map(|buf| buf.len()).sum();
+        self.inner
+            .do_read(len, |mut receiver| receiver.read_vectored(bufs))
     }
 }
 
@@ -524,6 +569,19 @@ impl From<ChildStderr> for Receiver {
     }
 }
 
+#[cfg(any(target_os = "android", target_os = "linux"))]
+impl crate::sys::splice::private::Sealed for Receiver {
+    fn splice_io<F, R>(&self, interest: Interest, f: F) -> io::Result<R>
+    where
+        F: FnOnce(RawFd) -> io::Result<R>,
+    {
+        self.inner.do_io_for(interest, |inner| f(inner.as_raw_fd()))
+    }
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+impl crate::unix::SpliceRead for Receiver {}
+
 impl FromRawFd for Receiver {
     unsafe fn from_raw_fd(fd: RawFd) -> Receiver {
         Receiver {
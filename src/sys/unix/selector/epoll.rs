@@ -6,7 +6,7 @@ use std::os::unix::io::{AsRawFd, RawFd};
 #[cfg(debug_assertions)]
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::time::Duration;
-use std::{cmp, i32, io, ptr};
+use std::{cmp, io, ptr};
 
 /// Unique id for use as `SelectorId`.
 #[cfg(debug_assertions)]
@@ -77,6 +77,69 @@ impl Selector {
         })
     }
 
+    /// Set the busy poll parameters of the epoll instance using
+    /// `EPIOCSPARAMS`, disabling busy polling if `spin` is `None`.
+    ///
+    /// Kernels that don't support `EPIOCSPARAMS` (before Linux 6.9, or
+    /// without `CONFIG_NET_RX_BUSY_POLL`) are ignored.
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    pub fn set_busy_poll(&self, spin: Option<Duration>) -> io::Result<()> {
+        // Not (yet) defined in all libc versions we support.
+        #[repr(C)]
+        struct epoll_params {
+            busy_poll_usecs: u32,
+            busy_poll_budget: u16,
+            prefer_busy_poll: u8,
+            __pad: u8,
+        }
+        // `_IOW(0x8A, 0x01, struct epoll_params)`.
+        #[cfg(not(any(
+            target_arch = "mips",
+            target_arch = "mips64",
+            target_arch = "powerpc",
+            target_arch = "powerpc64",
+            target_arch = "sparc64",
+        )))]
+        const EPIOCSPARAMS: u32 = 0x4008_8A01;
+        #[cfg(any(
+            target_arch = "mips",
+            target_arch = "mips64",
+            target_arch = "powerpc",
+            target_arch = "powerpc64",
+            target_arch = "sparc64",
+        ))]
+        const EPIOCSPARAMS: u32 = 0x8008_8A01;
+        // Default budget used by the kernel (`BUSY_POLL_BUDGET`), larger
+        // values require `CAP_NET_ADMIN`.
+        const BUSY_POLL_BUDGET: u16 = 8;
+
+        let params = match spin {
+            Some(spin) => epoll_params {
+                // The kernel limits this to `S32_MAX`.
+                busy_poll_usecs: cmp::min(spin.as_micros(), i32::MAX as u128) as u32,
+                busy_poll_budget: BUSY_POLL_BUDGET,
+                prefer_busy_poll: 0,
+                __pad: 0,
+            },
+            None => epoll_params {
+                busy_poll_usecs: 0,
+                busy_poll_budget: 0,
+                prefer_busy_poll: 0,
+                __pad: 0,
+            },
+        };
+        match syscall!(ioctl(self.ep, EPIOCSPARAMS as _, &params)) {
+            Ok(_) => Ok(()),
+            Err(ref err)
+                if err.raw_os_error() == Some(libc::ENOTTY)
+                    || err.raw_os_error() == Some(libc::EOPNOTSUPP) =>
+            {
+                Ok(())
+            }
+            Err(err) => Err(err),
+        }
+    }
+
     pub fn select(&self, events: &mut Events, timeout: Option<Duration>) -> io::Result<()> {
         // A bug in kernels < 2.6.37 makes timeouts larger than LONG_MAX / CONFIG_HZ
         // (approx. 30 minutes with CONFIG_HZ=1200) effectively infinite on 32 bits
@@ -84,7 +147,7 @@ impl Selector {
         #[cfg(target_pointer_width = "32")]
         const MAX_SAFE_TIMEOUT: u128 = 1789569;
         #[cfg(not(target_pointer_width = "32"))]
-        const MAX_SAFE_TIMEOUT: u128 = libc::c_int::max_value() as u128;
+        const MAX_SAFE_TIMEOUT: u128 = libc::c_int::MAX as u128;
 
         let timeout = timeout
             .map(|to| {
@@ -193,6 +256,7 @@ pub type Events = Vec<Event>;
 pub mod event {
     use std::fmt;
 
+    use crate::sys::mem::{ERROR, READABLE, READ_CLOSED, WRITABLE, WRITE_CLOSED};
     use crate::sys::Event;
     use crate::Token;
 
@@ -245,6 +309,39 @@ pub mod event {
         false
     }
 
+    pub fn merge(event: &mut Event, other: &Event) -> bool {
+        event.events |= other.events;
+        true
+    }
+
+    pub fn push(events: &mut Vec<Event>, token: Token, readiness: u8) -> bool {
+        if events.len() >= events.capacity() {
+            return false;
+        }
+        let mut kind = 0;
+        if readiness & READABLE != 0 {
+            kind |= libc::EPOLLIN;
+        }
+        if readiness & READ_CLOSED != 0 {
+            kind |= libc::EPOLLIN | libc::EPOLLRDHUP;
+        }
+        if readiness & WRITABLE != 0 {
+            kind |= libc::EPOLLOUT;
+        }
+        if readiness & WRITE_CLOSED != 0 {
+            // Same as the write end of a pipe once the read end is closed.
+            kind |= libc::EPOLLOUT | libc::EPOLLERR;
+        }
+        if readiness & ERROR != 0 {
+            kind |= libc::EPOLLERR;
+        }
+        events.push(Event {
+            events: kind as u32,
+            u64: usize::from(token) as u64,
+        });
+        true
+    }
+
     pub fn debug_details(f: &mut fmt::Formatter<'_>, event: &Event) -> fmt::Result {
         #[allow(clippy::trivially_copy_pass_by_ref)]
         fn check_events(got: &u32, want: &libc::c_int) -> bool {
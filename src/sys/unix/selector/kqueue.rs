@@ -229,8 +229,9 @@ impl Selector {
     ))]
     pub fn setup_waker(&self, token: Token) -> io::Result<()> {
         // First attempt to accept user space notifications.
+        // Use the token as identifier, allowing multiple wakers per kqueue.
         let mut kevent = kevent!(
-            0,
+            token.0,
             libc::EVFILT_USER,
             libc::EV_ADD | libc::EV_CLEAR | libc::EV_RECEIPT,
             token.0
@@ -255,7 +256,7 @@ impl Selector {
     ))]
     pub fn wake(&self, token: Token) -> io::Result<()> {
         let mut kevent = kevent!(
-            0,
+            token.0,
             libc::EVFILT_USER,
             libc::EV_ADD | libc::EV_RECEIPT,
             token.0
@@ -370,8 +371,9 @@ unsafe impl Send for Events {}
 unsafe impl Sync for Events {}
 
 pub mod event {
-    use std::fmt;
+    use std::{fmt, mem};
 
+    use crate::sys::mem::{ERROR, READABLE, READ_CLOSED, WRITABLE, WRITE_CLOSED};
     use crate::sys::Event;
     use crate::Token;
 
@@ -471,6 +473,60 @@ pub mod event {
         }
     }
 
+    pub fn merge(event: &mut Event, other: &Event) -> bool {
+        // A `kevent` only holds the state of a single filter.
+        if event.filter != other.filter {
+            return false;
+        }
+        event.flags |= other.flags;
+        event.fflags |= other.fflags;
+        event.data = other.data;
+        true
+    }
+
+    pub fn push(events: &mut Vec<Event>, token: Token, readiness: u8) -> bool {
+        use super::UData;
+        let read = readiness & (READABLE | READ_CLOSED) != 0;
+        let write = readiness & (WRITABLE | WRITE_CLOSED) != 0;
+        // A `kevent` only holds the state of a single filter, so this may need
+        // two events.
+        if events.capacity() - events.len() < read as usize + write as usize {
+            return false;
+        }
+        let error: Flags = if readiness & ERROR != 0 {
+            libc::EV_ERROR
+        } else {
+            0
+        };
+        if read {
+            let eof: Flags = if readiness & READ_CLOSED != 0 {
+                libc::EV_EOF
+            } else {
+                0
+            };
+            events.push(kevent!(
+                0,
+                libc::EVFILT_READ,
+                eof | error,
+                usize::from(token)
+            ));
+        }
+        if write {
+            let eof: Flags = if readiness & WRITE_CLOSED != 0 {
+                libc::EV_EOF
+            } else {
+                0
+            };
+            events.push(kevent!(
+                0,
+                libc::EVFILT_WRITE,
+                eof | error,
+                usize::from(token)
+            ));
+        }
+        true
+    }
+
     pub fn debug_details(f: &mut fmt::Formatter<'_>, event: &Event) -> fmt::Result {
         debug_detail!(
             FilterDetails(Filter),
@@ -0,0 +1,202 @@
+//! Moving data between file descriptors inside the kernel, using `splice(2)`
+//! and `tee(2)`.
+
+use std::os::unix::io::RawFd;
+use std::{fmt, io, ptr};
+
+use crate::unix::pipe::{Receiver, Sender};
+use crate::Interest;
+
+/// A source of data for [`splice`].
+///
+/// Implemented for [`pipe::Receiver`] and, with the `net` feature,
+/// [`TcpStream`] and [`UnixStream`]. This trait is sealed and can't be
+/// implemented outside of Mio.
+///
+/// [`pipe::Receiver`]: crate::unix::pipe::Receiver
+/// [`TcpStream`]: crate::net::TcpStream
+/// [`UnixStream`]: crate::net::UnixStream
+pub trait SpliceRead: private::Sealed {}
+
+/// A destination of data for [`splice`].
+///
+/// Implemented for [`pipe::Sender`] and, with the `net` feature,
+/// [`TcpStream`] and [`UnixStream`]. This trait is sealed and can't be
+/// implemented outside of Mio.
+///
+/// [`pipe::Sender`]: crate::unix::pipe::Sender
+/// [`TcpStream`]: crate::net::TcpStream
+/// [`UnixStream`]: crate::net::UnixStream
+pub trait SpliceWrite: private::Sealed {}
+
+/// The side of a [`splice`] or [`tee`] that wasn't ready.
+///
+/// Both functions return an error of kind `WouldBlock` if no data could be
+/// moved, which wraps this type to tell which side to wait for. It can be
+/// retrieved using [`SpliceBlocked::from_error`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SpliceBlocked {
+    /// No data was available to read from `from`, wait for a readable event.
+    Read,
+    /// No data could be written to `to`, wait for a writable event.
+    Write,
+}
+
+impl SpliceBlocked {
+    /// Returns the side that wasn't ready if `err` is a `WouldBlock` error
+    /// returned by [`splice`] or [`tee`].
+    pub fn from_error(err: &io::Error) -> Option<SpliceBlocked> {
+        err.get_ref()
+            .and_then(|err| err.downcast_ref::<SpliceBlocked>())
+            .copied()
+    }
+
+    fn into_error(self) -> io::Error {
+        io::Error::new(io::ErrorKind::WouldBlock, self)
+    }
+}
+
+impl fmt::Display for SpliceBlocked {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SpliceBlocked::Read => "no data available to read",
+            SpliceBlocked::Write => "no room available to write",
+        })
+    }
+}
+
+impl std::error::Error for SpliceBlocked {}
+
+pub(crate) mod private {
+    use std::io;
+    use std::os::unix::io::RawFd;
+
+    use crate::Interest;
+
+    pub trait Sealed {
+        /// Perform I/O on the file descriptor, using the source's `do_io_for`
+        /// so its readiness is cleared if the operation returns `WouldBlock`.
+        fn splice_io<F, R>(&self, interest: Interest, f: F) -> io::Result<R>
+        where
+            F: FnOnce(RawFd) -> io::Result<R>;
+    }
+}
+
+/// Move up to `len` bytes from `from` to `to` without copying them through
+/// user space, using `splice(2)`.
+///
+/// One of `from` or `to` must be a pipe, otherwise an error is returned. To
+/// move data between two sockets, e.g. in a proxy, splice it from the first
+/// socket into a pipe and from the pipe into the second socket.
+///
+/// Returns the number of bytes moved, zero if `from` reached the end of the
+/// stream. If no bytes could be moved an error of kind `WouldBlock` is
+/// returned, wrapping a [`SpliceBlocked`] that says which side wasn't ready.
+/// Only the readiness of that side is cleared and an event for it will be
+/// received once it's ready again: a readable event for `from` or a writable
+/// event for `to`.
+///
+/// Which side wasn't ready is determined by checking whether `from` has data
+/// available before moving it (using `FIONREAD`), so this assumes nothing
+/// else reads from `from` at the same time.
+///
+/// # Examples
+///
+#[cfg_attr(all(feature = "os-poll", feature = "net"), doc = "```")]
+#[cfg_attr(not(all(feature = "os-poll", feature = "net")), doc = "```ignore")]
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use std::io::Read;
+///
+/// use mio::net::TcpStream;
+/// use mio::unix::{pipe, splice};
+///
+/// # let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+/// # let mut client = std::net::TcpStream::connect(listener.local_addr()?)?;
+/// # let (peer, _) = listener.accept()?;
+/// # peer.set_nonblocking(true)?;
+/// let stream = TcpStream::from_std(peer);
+/// let (sender, mut receiver) = pipe::new()?;
+/// # std::io::Write::write_all(&mut client, b"hello")?;
+///
+/// // Move the data received on the stream into the pipe.
+/// let n = splice(&stream, &sender, 4096)?;
+///
+/// let mut buf = vec![0; n];
+/// receiver.read_exact(&mut buf)?;
+/// #     assert_eq!(buf, b"hello");
+/// #     Ok(())
+/// # }
+/// ```
+pub fn splice<F, T>(from: &F, to: &T, len: usize) -> io::Result<usize>
+where
+    F: SpliceRead + ?Sized,
+    T: SpliceWrite + ?Sized,
+{
+    splice_io(from, to, |from, to| {
+        syscall!(splice(
+            from,
+            ptr::null_mut(),
+            to,
+            ptr::null_mut(),
+            len,
+            libc::SPLICE_F_NONBLOCK
+        ))
+        .map(|n| n as usize)
+    })
+}
+
+/// Duplicate up to `len` bytes from the pipe `from` to the pipe `to` without
+/// consuming them, using `tee(2)`.
+///
+/// The data can still be read from `from` afterwards, e.g. to [`splice`] it
+/// to a socket while the duplicate is used for logging. Returns the number of
+/// bytes duplicated, zero if no data is available and the sending side of
+/// `from` is closed. Like [`splice`], if no bytes could be duplicated an error
+/// of kind `WouldBlock` is returned, wrapping a [`SpliceBlocked`].
+pub fn tee(from: &Receiver, to: &Sender, len: usize) -> io::Result<usize> {
+    splice_io(from, to, |from, to| {
+        syscall!(tee(from, to, len, libc::SPLICE_F_NONBLOCK)).map(|n| n as usize)
+    })
+}
+
+/// Performs `op` using the `do_io_for` of both `from` and `to`, making sure
+/// that a `WouldBlock` error is only seen by the side that isn't ready.
+fn splice_io<F, T, O>(from: &F, to: &T, op: O) -> io::Result<usize>
+where
+    F: private::Sealed + ?Sized,
+    T: private::Sealed + ?Sized,
+    O: FnOnce(RawFd, RawFd) -> io::Result<usize>,
+{
+    from.splice_io(Interest::READABLE, |from| {
+        // Data available before the operation can't go away, so if there is
+        // any `to` must be the side that isn't ready. If data arrives after
+        // the check `from` is blamed, but then it also receives an event.
+        let available = bytes_available(from)? != 0;
+        // `to` only sees the error if it's the side that isn't ready.
+        let res = to.splice_io(Interest::WRITABLE, |to| match op(from, to) {
+            Err(ref err) if err.kind() == io::ErrorKind::WouldBlock && available => {
+                Err(SpliceBlocked::Write.into_error())
+            }
+            res => Ok(res),
+        });
+        match res {
+            Ok(Ok(n)) => Ok(Ok(n)),
+            Ok(Err(ref err)) if err.kind() == io::ErrorKind::WouldBlock => {
+                Err(SpliceBlocked::Read.into_error())
+            }
+            // Another error.
+            Ok(Err(err)) => Err(err),
+            // `to` isn't ready, `from` can still be read from.
+            Err(err) => Ok(Err(err)),
+        }
+    })
+    .and_then(|res| res)
+}
+
+/// Returns the number of bytes available to read from `fd`.
+fn bytes_available(fd: RawFd) -> io::Result<libc::c_int> {
+    let mut available: libc::c_int = 0;
+    syscall!(ioctl(fd, libc::FIONREAD, &mut available))?;
+    Ok(available)
+}
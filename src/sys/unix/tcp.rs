@@ -1,22 +1,61 @@
+#[cfg(any(
+    target_os = "android",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "netbsd",
+    target_os = "tvos",
+    target_os = "watchos",
+))]
+use std::cmp;
 use std::convert::TryInto;
 use std::io;
 use std::mem::{size_of, MaybeUninit};
 use std::net::{self, SocketAddr};
 use std::os::unix::io::{AsRawFd, FromRawFd};
+#[cfg(any(target_os = "android", target_os = "linux"))]
+use std::ptr;
+#[cfg(any(
+    target_os = "android",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "netbsd",
+    target_os = "tvos",
+    target_os = "watchos",
+))]
+use std::time::Duration;
 
-use crate::sys::unix::net::{new_socket, socket_addr, to_socket_addr};
+#[cfg(any(
+    target_os = "android",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "tvos",
+    target_os = "watchos",
+))]
+use crate::net::TcpInfo;
+use crate::net::TcpKeepalive;
+use crate::sys::unix::net::{new_socket, option, set_option, socket_addr, to_socket_addr};
 
-pub(crate) fn new_for_addr(address: SocketAddr) -> io::Result<libc::c_int> {
-    let domain = match address {
-        SocketAddr::V4(_) => libc::AF_INET,
-        SocketAddr::V6(_) => libc::AF_INET6,
-    };
-    new_socket(domain, libc::SOCK_STREAM)
+pub(crate) type TcpSocket = libc::c_int;
+
+pub(crate) fn new_v4_socket() -> io::Result<TcpSocket> {
+    new_socket(libc::AF_INET, libc::SOCK_STREAM)
+}
+
+pub(crate) fn new_v6_socket() -> io::Result<TcpSocket> {
+    new_socket(libc::AF_INET6, libc::SOCK_STREAM)
 }
 
-pub(crate) fn bind(socket: &net::TcpListener, addr: SocketAddr) -> io::Result<()> {
+pub(crate) fn bind(socket: TcpSocket, addr: SocketAddr) -> io::Result<()> {
     let (raw_addr, raw_addr_length) = socket_addr(&addr);
-    syscall!(bind(socket.as_raw_fd(), raw_addr.as_ptr(), raw_addr_length))?;
+    syscall!(bind(socket, raw_addr.as_ptr(), raw_addr_length))?;
     Ok(())
 }
 
@@ -34,23 +73,554 @@ pub(crate) fn connect(socket: &net::TcpStream, addr: SocketAddr) -> io::Result<(
 }
 
 pub(crate) fn listen(socket: &net::TcpListener, backlog: u32) -> io::Result<()> {
-    let backlog = backlog.try_into().unwrap_or(i32::max_value());
+    let backlog = backlog.try_into().unwrap_or(i32::MAX);
     syscall!(listen(socket.as_raw_fd(), backlog))?;
     Ok(())
 }
 
-pub(crate) fn set_reuseaddr(socket: &net::TcpListener, reuseaddr: bool) -> io::Result<()> {
-    let val: libc::c_int = i32::from(reuseaddr);
-    syscall!(setsockopt(
-        socket.as_raw_fd(),
+pub(crate) fn close(socket: TcpSocket) {
+    let _ = unsafe { net::TcpStream::from_raw_fd(socket) };
+}
+
+pub(crate) fn set_reuseaddr(socket: TcpSocket, reuseaddr: bool) -> io::Result<()> {
+    set_option(
+        socket,
         libc::SOL_SOCKET,
         libc::SO_REUSEADDR,
-        &val as *const libc::c_int as *const libc::c_void,
-        size_of::<libc::c_int>() as libc::socklen_t,
+        libc::c_int::from(reuseaddr),
+    )
+}
+
+pub(crate) fn reuseaddr(socket: TcpSocket) -> io::Result<bool> {
+    option::<libc::c_int>(socket, libc::SOL_SOCKET, libc::SO_REUSEADDR).map(|val| val != 0)
+}
+
+#[cfg(not(any(target_os = "illumos", target_os = "solaris")))]
+pub(crate) fn set_reuseport(socket: TcpSocket, reuseport: bool) -> io::Result<()> {
+    set_option(
+        socket,
+        libc::SOL_SOCKET,
+        libc::SO_REUSEPORT,
+        libc::c_int::from(reuseport),
+    )
+}
+
+#[cfg(not(any(target_os = "illumos", target_os = "solaris")))]
+pub(crate) fn reuseport(socket: TcpSocket) -> io::Result<bool> {
+    option::<libc::c_int>(socket, libc::SOL_SOCKET, libc::SO_REUSEPORT).map(|val| val != 0)
+}
+
+pub(crate) fn set_only_v6(socket: TcpSocket, only_v6: bool) -> io::Result<()> {
+    set_option(
+        socket,
+        libc::IPPROTO_IPV6,
+        libc::IPV6_V6ONLY,
+        libc::c_int::from(only_v6),
+    )
+}
+
+pub(crate) fn only_v6(socket: TcpSocket) -> io::Result<bool> {
+    option::<libc::c_int>(socket, libc::IPPROTO_IPV6, libc::IPV6_V6ONLY).map(|val| val != 0)
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub(crate) fn bind_device(socket: TcpSocket, interface: Option<&[u8]>) -> io::Result<()> {
+    let (value, length) = match interface {
+        Some(interface) => (interface.as_ptr(), interface.len()),
+        // An empty name removes the binding.
+        None => (ptr::null(), 0),
+    };
+    syscall!(setsockopt(
+        socket,
+        libc::SOL_SOCKET,
+        libc::SO_BINDTODEVICE,
+        value as *const libc::c_void,
+        length as libc::socklen_t,
     ))?;
     Ok(())
 }
 
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub(crate) fn device(socket: TcpSocket) -> io::Result<Option<Vec<u8>>> {
+    let mut buf = [0u8; libc::IFNAMSIZ];
+    let mut length = buf.len() as libc::socklen_t;
+    syscall!(getsockopt(
+        socket,
+        libc::SOL_SOCKET,
+        libc::SO_BINDTODEVICE,
+        buf.as_mut_ptr() as *mut libc::c_void,
+        &mut length,
+    ))?;
+    // The returned name is nul terminated, unless it's empty.
+    let name = &buf[..length as usize];
+    let name = name.split(|b| *b == 0).next().unwrap_or(name);
+    if name.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(name.to_vec()))
+    }
+}
+
+pub(crate) fn set_keepalive(
+    socket: &net::TcpStream,
+    keepalive: Option<TcpKeepalive>,
+) -> io::Result<()> {
+    let socket = socket.as_raw_fd();
+    if let Some(keepalive) = keepalive {
+        set_keepalive_params(socket, keepalive)?;
+    }
+    set_option(
+        socket,
+        libc::SOL_SOCKET,
+        libc::SO_KEEPALIVE,
+        libc::c_int::from(keepalive.is_some()),
+    )
+}
+
+pub(crate) fn keepalive(socket: &net::TcpStream) -> io::Result<Option<TcpKeepalive>> {
+    let socket = socket.as_raw_fd();
+    if option::<libc::c_int>(socket, libc::SOL_SOCKET, libc::SO_KEEPALIVE)? == 0 {
+        return Ok(None);
+    }
+    keepalive_params(socket).map(Some)
+}
+
+/// Option used to set the keepalive idle time.
+#[cfg(any(
+    target_os = "android",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "linux",
+    target_os = "netbsd",
+))]
+const KEEPALIVE_TIME: libc::c_int = libc::TCP_KEEPIDLE;
+#[cfg(any(
+    target_os = "ios",
+    target_os = "macos",
+    target_os = "tvos",
+    target_os = "watchos",
+))]
+const KEEPALIVE_TIME: libc::c_int = libc::TCP_KEEPALIVE;
+
+#[cfg(any(
+    target_os = "android",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "netbsd",
+    target_os = "tvos",
+    target_os = "watchos",
+))]
+fn set_keepalive_params(socket: TcpSocket, keepalive: TcpKeepalive) -> io::Result<()> {
+    // Rounds up, as the OS rejects zero.
+    fn secs(duration: Duration) -> libc::c_int {
+        let secs = duration.as_secs() + u64::from(duration.subsec_nanos() != 0);
+        cmp::min(cmp::max(secs, 1), libc::c_int::MAX as u64) as libc::c_int
+    }
+
+    if let Some(time) = keepalive.time {
+        set_option(socket, libc::IPPROTO_TCP, KEEPALIVE_TIME, secs(time))?;
+    }
+    if let Some(interval) = keepalive.interval {
+        set_option(
+            socket,
+            libc::IPPROTO_TCP,
+            libc::TCP_KEEPINTVL,
+            secs(interval),
+        )?;
+    }
+    if let Some(retries) = keepalive.retries {
+        let retries = retries.try_into().unwrap_or(libc::c_int::MAX);
+        set_option(socket, libc::IPPROTO_TCP, libc::TCP_KEEPCNT, retries)?;
+    }
+    Ok(())
+}
+
+#[cfg(any(
+    target_os = "android",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "netbsd",
+    target_os = "tvos",
+    target_os = "watchos",
+))]
+fn keepalive_params(socket: TcpSocket) -> io::Result<TcpKeepalive> {
+    let secs = |name| {
+        option::<libc::c_int>(socket, libc::IPPROTO_TCP, name)
+            .map(|secs| Duration::from_secs(secs as u64))
+    };
+    Ok(TcpKeepalive {
+        time: Some(secs(KEEPALIVE_TIME)?),
+        interval: Some(secs(libc::TCP_KEEPINTVL)?),
+        retries: Some(option::<libc::c_int>(socket, libc::IPPROTO_TCP, libc::TCP_KEEPCNT)? as u32),
+    })
+}
+
+/// The keepalive parameters can't be configured on these platforms.
+#[cfg(not(any(
+    target_os = "android",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "netbsd",
+    target_os = "tvos",
+    target_os = "watchos",
+)))]
+fn set_keepalive_params(_: TcpSocket, _: TcpKeepalive) -> io::Result<()> {
+    Ok(())
+}
+
+#[cfg(not(any(
+    target_os = "android",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "netbsd",
+    target_os = "tvos",
+    target_os = "watchos",
+)))]
+fn keepalive_params(_: TcpSocket) -> io::Result<TcpKeepalive> {
+    Ok(TcpKeepalive::new())
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub(crate) fn set_user_timeout(
+    socket: &net::TcpStream,
+    timeout: Option<Duration>,
+) -> io::Result<()> {
+    // Round up, zero means the system default.
+    let timeout = timeout.map_or(0, |timeout| {
+        let millis = timeout.as_millis() + u128::from(timeout.subsec_nanos() % 1_000_000 != 0);
+        cmp::min(millis, libc::c_uint::MAX as u128) as libc::c_uint
+    });
+    set_option(
+        socket.as_raw_fd(),
+        libc::IPPROTO_TCP,
+        libc::TCP_USER_TIMEOUT,
+        timeout,
+    )
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub(crate) fn user_timeout(socket: &net::TcpStream) -> io::Result<Option<Duration>> {
+    option::<libc::c_uint>(
+        socket.as_raw_fd(),
+        libc::IPPROTO_TCP,
+        libc::TCP_USER_TIMEOUT,
+    )
+    .map(|timeout| match timeout {
+        0 => None,
+        timeout => Some(Duration::from_millis(timeout.into())),
+    })
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub(crate) fn tcp_info(socket: &net::TcpStream) -> io::Result<TcpInfo> {
+    // Not all fields are returned by older kernels, see below.
+    let mut info: MaybeUninit<tcp_info> = MaybeUninit::zeroed();
+    let mut length = size_of::<tcp_info>() as libc::socklen_t;
+    syscall!(getsockopt(
+        socket.as_raw_fd(),
+        libc::IPPROTO_TCP,
+        libc::TCP_INFO,
+        info.as_mut_ptr() as *mut _,
+        &mut length,
+    ))?;
+    // This is safe because `info` is zeroed and (partially) initialised by
+    // `getsockopt`.
+    let info = unsafe { info.assume_init() };
+    let length = length as usize;
+
+    // Fields were appended to `struct tcp_info` over time, so only use the
+    // fields the kernel actually returned. The offsets are the end of the
+    // field in the structure.
+    let has = |end: usize| length >= end;
+    Ok(TcpInfo {
+        rtt: if has(72) {
+            Some(Duration::from_micros(info.tcpi_rtt.into()))
+        } else {
+            None
+        },
+        rtt_var: if has(76) {
+            Some(Duration::from_micros(info.tcpi_rttvar.into()))
+        } else {
+            None
+        },
+        congestion_window: if has(84) {
+            Some(info.tcpi_snd_cwnd)
+        } else {
+            None
+        },
+        retransmits: if has(104) {
+            Some(info.tcpi_total_retrans)
+        } else {
+            None
+        },
+        unacked: if has(28) {
+            Some(info.tcpi_unacked)
+        } else {
+            None
+        },
+        // `u64::MAX` means pacing is not limited.
+        pacing_rate: if has(112) && info.tcpi_pacing_rate != u64::MAX {
+            Some(info.tcpi_pacing_rate)
+        } else {
+            None
+        },
+        delivery_rate: if has(168) {
+            Some(info.tcpi_delivery_rate)
+        } else {
+            None
+        },
+    })
+}
+
+/// Start of `struct tcp_info` from `linux/tcp.h`, up to and including
+/// `tcpi_delivery_rate` (added in Linux 4.9).
+///
+/// Not all fields are available in (older versions of) the `libc` crate, so we
+/// define it ourselves.
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[repr(C)]
+#[allow(non_camel_case_types, dead_code)]
+struct tcp_info {
+    tcpi_state: u8,
+    tcpi_ca_state: u8,
+    tcpi_retransmits: u8,
+    tcpi_probes: u8,
+    tcpi_backoff: u8,
+    tcpi_options: u8,
+    // `tcpi_snd_wscale` and `tcpi_rcv_wscale`, 4 bits each.
+    tcpi_wscale: u8,
+    // `tcpi_delivery_rate_app_limited` and `tcpi_fastopen_client_fail`.
+    tcpi_flags: u8,
+
+    tcpi_rto: u32,
+    tcpi_ato: u32,
+    tcpi_snd_mss: u32,
+    tcpi_rcv_mss: u32,
+
+    tcpi_unacked: u32,
+    tcpi_sacked: u32,
+    tcpi_lost: u32,
+    tcpi_retrans: u32,
+    tcpi_fackets: u32,
+
+    tcpi_last_data_sent: u32,
+    tcpi_last_ack_sent: u32,
+    tcpi_last_data_recv: u32,
+    tcpi_last_ack_recv: u32,
+
+    tcpi_pmtu: u32,
+    tcpi_rcv_ssthresh: u32,
+    tcpi_rtt: u32,
+    tcpi_rttvar: u32,
+    tcpi_snd_ssthresh: u32,
+    tcpi_snd_cwnd: u32,
+    tcpi_advmss: u32,
+    tcpi_reordering: u32,
+
+    tcpi_rcv_rtt: u32,
+    tcpi_rcv_space: u32,
+
+    tcpi_total_retrans: u32,
+
+    tcpi_pacing_rate: u64,
+    tcpi_max_pacing_rate: u64,
+    tcpi_bytes_acked: u64,
+    tcpi_bytes_received: u64,
+    tcpi_segs_out: u32,
+    tcpi_segs_in: u32,
+
+    tcpi_notsent_bytes: u32,
+    tcpi_min_rtt: u32,
+    tcpi_data_segs_in: u32,
+    tcpi_data_segs_out: u32,
+
+    tcpi_delivery_rate: u64,
+}
+
+#[cfg(target_os = "freebsd")]
+pub(crate) fn tcp_info(socket: &net::TcpStream) -> io::Result<TcpInfo> {
+    /// `TCP_INFO` from `netinet/tcp.h`.
+    const TCP_INFO: libc::c_int = 32;
+
+    let info: tcp_info = tcp_option(socket, TCP_INFO)?;
+    Ok(TcpInfo {
+        rtt: Some(Duration::from_micros(info.tcpi_rtt.into())),
+        rtt_var: Some(Duration::from_micros(info.tcpi_rttvar.into())),
+        congestion_window: segments(info.tcpi_snd_cwnd, info.tcpi_snd_mss),
+        retransmits: Some(info.tcpi_snd_rexmitpack),
+        // Not filled in by FreeBSD.
+        unacked: None,
+        pacing_rate: None,
+        delivery_rate: None,
+    })
+}
+
+/// Start of `struct tcp_info` from `netinet/tcp.h`, up to and including
+/// `tcpi_snd_rexmitpack`. The kernel copies as much of the structure as fits.
+///
+/// Fields prefixed with `__` are not filled in by FreeBSD.
+#[cfg(target_os = "freebsd")]
+#[repr(C)]
+#[allow(non_camel_case_types, dead_code)]
+struct tcp_info {
+    tcpi_state: u8,
+    __tcpi_ca_state: u8,
+    __tcpi_retransmits: u8,
+    __tcpi_probes: u8,
+    __tcpi_backoff: u8,
+    tcpi_options: u8,
+    // `tcpi_snd_wscale` and `tcpi_rcv_wscale`, 4 bits each.
+    tcpi_wscale: u8,
+    __tcpi_pad: u8,
+
+    tcpi_rto: u32,
+    __tcpi_ato: u32,
+    tcpi_snd_mss: u32,
+    tcpi_rcv_mss: u32,
+
+    __tcpi_unacked: u32,
+    __tcpi_sacked: u32,
+    __tcpi_lost: u32,
+    __tcpi_retrans: u32,
+    __tcpi_fackets: u32,
+
+    __tcpi_last_data_sent: u32,
+    __tcpi_last_ack_sent: u32,
+    tcpi_last_data_recv: u32,
+    __tcpi_last_ack_recv: u32,
+
+    __tcpi_pmtu: u32,
+    __tcpi_rcv_ssthresh: u32,
+    tcpi_rtt: u32,
+    tcpi_rttvar: u32,
+    tcpi_snd_ssthresh: u32,
+    tcpi_snd_cwnd: u32,
+    __tcpi_advmss: u32,
+    __tcpi_reordering: u32,
+
+    __tcpi_rcv_rtt: u32,
+    tcpi_rcv_space: u32,
+
+    tcpi_snd_wnd: u32,
+    tcpi_snd_bwnd: u32,
+    tcpi_snd_nxt: u32,
+    tcpi_rcv_nxt: u32,
+    tcpi_toe_tid: u32,
+    tcpi_snd_rexmitpack: u32,
+}
+
+#[cfg(any(
+    target_os = "ios",
+    target_os = "macos",
+    target_os = "tvos",
+    target_os = "watchos",
+))]
+pub(crate) fn tcp_info(socket: &net::TcpStream) -> io::Result<TcpInfo> {
+    /// `TCP_CONNECTION_INFO` from `netinet/tcp.h`.
+    const TCP_CONNECTION_INFO: libc::c_int = 0x106;
+
+    let info: tcp_connection_info = tcp_option(socket, TCP_CONNECTION_INFO)?;
+    Ok(TcpInfo {
+        rtt: Some(Duration::from_millis(info.tcpi_srtt.into())),
+        rtt_var: Some(Duration::from_millis(info.tcpi_rttvar.into())),
+        congestion_window: segments(info.tcpi_snd_cwnd, info.tcpi_maxseg),
+        // Only the number of retransmitted bytes is available.
+        retransmits: None,
+        unacked: None,
+        pacing_rate: None,
+        delivery_rate: None,
+    })
+}
+
+/// Start of `struct tcp_connection_info` from `netinet/tcp.h`, up to and
+/// including `tcpi_rttvar`. The kernel copies as much of the structure as
+/// fits.
+#[cfg(any(
+    target_os = "ios",
+    target_os = "macos",
+    target_os = "tvos",
+    target_os = "watchos",
+))]
+#[repr(C)]
+#[allow(non_camel_case_types, dead_code)]
+struct tcp_connection_info {
+    tcpi_state: u8,
+    tcpi_snd_wscale: u8,
+    tcpi_rcv_wscale: u8,
+    __pad1: u8,
+    tcpi_options: u32,
+    tcpi_flags: u32,
+    tcpi_rto: u32,
+    tcpi_maxseg: u32,
+    tcpi_snd_ssthresh: u32,
+    tcpi_snd_cwnd: u32,
+    tcpi_snd_wnd: u32,
+    tcpi_snd_sbbytes: u32,
+    tcpi_rcv_wnd: u32,
+    tcpi_rttcur: u32,
+    tcpi_srtt: u32,
+    tcpi_rttvar: u32,
+}
+
+/// Get the `IPPROTO_TCP` level socket option `name`, which must be `T`.
+#[cfg(any(
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "macos",
+    target_os = "tvos",
+    target_os = "watchos",
+))]
+fn tcp_option<T>(socket: &net::TcpStream, name: libc::c_int) -> io::Result<T> {
+    let mut value: MaybeUninit<T> = MaybeUninit::zeroed();
+    let mut length = size_of::<T>() as libc::socklen_t;
+    syscall!(getsockopt(
+        socket.as_raw_fd(),
+        libc::IPPROTO_TCP,
+        name,
+        value.as_mut_ptr() as *mut _,
+        &mut length,
+    ))?;
+    // This is safe because `value` is zeroed and (partially) initialised by
+    // `getsockopt`, and only used for plain integer structures.
+    Ok(unsafe { value.assume_init() })
+}
+
+/// Converts a congestion window in bytes to segments of `mss` bytes.
+#[cfg(any(
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "macos",
+    target_os = "tvos",
+    target_os = "watchos",
+))]
+fn segments(bytes: u32, mss: u32) -> Option<u32> {
+    bytes.checked_div(mss)
+}
+
+pub(crate) fn local_addr(socket: TcpSocket) -> io::Result<SocketAddr> {
+    let mut addr: MaybeUninit<libc::sockaddr_storage> = MaybeUninit::uninit();
+    let mut length = size_of::<libc::sockaddr_storage>() as libc::socklen_t;
+    syscall!(getsockname(
+        socket,
+        addr.as_mut_ptr() as *mut _,
+        &mut length
+    ))?;
+    // This is safe because `getsockname` initialised the address.
+    unsafe { to_socket_addr(addr.as_ptr()) }
+}
+
 pub(crate) fn accept(listener: &net::TcpListener) -> io::Result<(net::TcpStream, SocketAddr)> {
     let mut addr: MaybeUninit<libc::sockaddr_storage> = MaybeUninit::uninit();
     let mut length = size_of::<libc::sockaddr_storage>() as libc::socklen_t;
@@ -260,6 +260,7 @@ pub(crate) type Event = wasi::Event;
 pub(crate) mod event {
     use std::fmt;
 
+    use crate::sys::mem::{READABLE, READ_CLOSED, WRITABLE, WRITE_CLOSED};
     use crate::sys::Event;
     use crate::Token;
 
@@ -309,6 +310,48 @@ pub(crate) mod event {
         false
     }
 
+    pub(crate) fn merge(event: &mut Event, other: &Event) -> bool {
+        // An event only holds the state of a single event type.
+        if event.type_ != other.type_ {
+            return false;
+        }
+        event.fd_readwrite.flags |= other.fd_readwrite.flags;
+        event.fd_readwrite.nbytes = other.fd_readwrite.nbytes;
+        true
+    }
+
+    pub(crate) fn push(events: &mut Vec<Event>, token: Token, readiness: u8) -> bool {
+        let read = readiness & (READABLE | READ_CLOSED) != 0;
+        let write = readiness & (WRITABLE | WRITE_CLOSED) != 0;
+        // An event only holds the state of a single event type, so this may
+        // need two events.
+        if events.capacity() - events.len() < read as usize + write as usize {
+            return false;
+        }
+        let mut push = |type_, closed| {
+            events.push(Event {
+                userdata: usize::from(token) as wasi::Userdata,
+                error: wasi::ERRNO_SUCCESS,
+                type_,
+                fd_readwrite: wasi::EventFdReadwrite {
+                    nbytes: 0,
+                    flags: if closed {
+                        wasi::EVENTRWFLAGS_FD_READWRITE_HANGUP
+                    } else {
+                        0
+                    },
+                },
+            })
+        };
+        if read {
+            push(wasi::EVENTTYPE_FD_READ, readiness & READ_CLOSED != 0);
+        }
+        if write {
+            push(wasi::EVENTTYPE_FD_WRITE, readiness & WRITE_CLOSED != 0);
+        }
+        true
+    }
+
     pub(crate) fn debug_details(f: &mut fmt::Formatter<'_>, event: &Event) -> fmt::Result {
         debug_detail!(
             TypeDetails(wasi::Eventtype),
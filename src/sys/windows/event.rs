@@ -2,6 +2,7 @@ use std::fmt;
 
 use super::afd;
 use super::iocp::CompletionStatus;
+use crate::sys::mem::{ERROR, READABLE, READ_CLOSED, WRITABLE, WRITE_CLOSED};
 use crate::Token;
 
 #[derive(Clone)]
@@ -88,6 +89,37 @@ pub fn is_lio(_: &Event) -> bool {
     false
 }
 
+pub fn merge(event: &mut Event, other: &Event) -> bool {
+    event.flags |= other.flags;
+    true
+}
+
+pub fn push(events: &mut Vec<Event>, token: Token, readiness: u8) -> bool {
+    if events.len() >= events.capacity() {
+        return false;
+    }
+    let mut event = Event::new(token);
+    if readiness & READABLE != 0 {
+        event.flags |= afd::POLL_RECEIVE;
+    }
+    if readiness & READ_CLOSED != 0 {
+        event.flags |= afd::POLL_DISCONNECT;
+    }
+    if readiness & WRITABLE != 0 {
+        event.flags |= afd::POLL_SEND;
+    }
+    // AFD has no flags for only one of these, so they also mark the event as
+    // read closed.
+    if readiness & WRITE_CLOSED != 0 {
+        event.flags |= afd::POLL_ABORT;
+    }
+    if readiness & ERROR != 0 {
+        event.flags |= afd::POLL_CONNECT_FAIL;
+    }
+    events.push(event);
+    true
+}
+
 pub fn debug_details(f: &mut fmt::Formatter<'_>, event: &Event) -> fmt::Result {
     #[allow(clippy::trivially_copy_pass_by_ref)]
     fn check_flags(got: &u32, want: &u32) -> bool {
@@ -1,12 +1,15 @@
-use std::io;
-use std::mem;
+use std::convert::TryInto;
+use std::mem::{self, size_of, MaybeUninit};
 use std::net::SocketAddr;
+use std::os::windows::io::AsRawSocket;
 use std::sync::Once;
+use std::time::Duration;
+use std::{cmp, io};
 
 use windows_sys::Win32::Networking::WinSock::{
-    closesocket, ioctlsocket, socket, AF_INET, AF_INET6, FIONBIO, IN6_ADDR, IN6_ADDR_0,
-    INVALID_SOCKET, IN_ADDR, IN_ADDR_0, SOCKADDR, SOCKADDR_IN, SOCKADDR_IN6, SOCKADDR_IN6_0,
-    SOCKET,
+    self, closesocket, ioctlsocket, socket, AF_INET, AF_INET6, FIONBIO, IN6_ADDR, IN6_ADDR_0,
+    INVALID_SOCKET, IN_ADDR, IN_ADDR_0, LINGER, SOCKADDR, SOCKADDR_IN, SOCKADDR_IN6,
+    SOCKADDR_IN6_0, SOCKET, SOCKET_ERROR, SOL_SOCKET, SO_LINGER, SO_RCVBUF, SO_SNDBUF,
 };
 
 /// Initialise the network stack for Windows.
@@ -109,3 +112,109 @@ pub(crate) fn socket_addr(addr: &SocketAddr) -> (SocketAddrCRepr, i32) {
         }
     }
 }
+
+pub(crate) fn set_linger<S: AsRawSocket>(socket: &S, linger: Option<Duration>) -> io::Result<()> {
+    let linger = LINGER {
+        l_onoff: u16::from(linger.is_some()),
+        l_linger: linger.map_or(0, |linger| {
+            cmp::min(linger.as_secs(), u16::max_value() as u64) as u16
+        }),
+    };
+    set_option(
+        socket.as_raw_socket() as SOCKET,
+        SOL_SOCKET as i32,
+        SO_LINGER as i32,
+        linger,
+    )
+}
+
+pub(crate) fn linger<S: AsRawSocket>(socket: &S) -> io::Result<Option<Duration>> {
+    option::<LINGER>(
+        socket.as_raw_socket() as SOCKET,
+        SOL_SOCKET as i32,
+        SO_LINGER as i32,
+    )
+    .map(|linger| {
+        if linger.l_onoff == 0 {
+            None
+        } else {
+            Some(Duration::from_secs(linger.l_linger.into()))
+        }
+    })
+}
+
+pub(crate) fn set_send_buffer_size<S: AsRawSocket>(socket: &S, size: u32) -> io::Result<()> {
+    let size = size.try_into().unwrap_or(i32::max_value());
+    set_option::<i32>(
+        socket.as_raw_socket() as SOCKET,
+        SOL_SOCKET as i32,
+        SO_SNDBUF as i32,
+        size,
+    )
+}
+
+pub(crate) fn send_buffer_size<S: AsRawSocket>(socket: &S) -> io::Result<u32> {
+    option::<i32>(
+        socket.as_raw_socket() as SOCKET,
+        SOL_SOCKET as i32,
+        SO_SNDBUF as i32,
+    )
+    .map(|size| size as u32)
+}
+
+pub(crate) fn set_recv_buffer_size<S: AsRawSocket>(socket: &S, size: u32) -> io::Result<()> {
+    let size = size.try_into().unwrap_or(i32::max_value());
+    set_option::<i32>(
+        socket.as_raw_socket() as SOCKET,
+        SOL_SOCKET as i32,
+        SO_RCVBUF as i32,
+        size,
+    )
+}
+
+pub(crate) fn recv_buffer_size<S: AsRawSocket>(socket: &S) -> io::Result<u32> {
+    option::<i32>(
+        socket.as_raw_socket() as SOCKET,
+        SOL_SOCKET as i32,
+        SO_RCVBUF as i32,
+    )
+    .map(|size| size as u32)
+}
+
+pub(crate) fn set_option<T>(socket: SOCKET, level: i32, name: i32, value: T) -> io::Result<()> {
+    use WinSock::setsockopt;
+
+    syscall!(
+        setsockopt(
+            socket,
+            level,
+            name,
+            &value as *const T as *const _,
+            size_of::<T>() as i32,
+        ),
+        PartialEq::eq,
+        SOCKET_ERROR
+    )?;
+    Ok(())
+}
+
+pub(crate) fn option<T>(socket: SOCKET, level: i32, name: i32) -> io::Result<T> {
+    use WinSock::getsockopt;
+
+    let mut value: MaybeUninit<T> = MaybeUninit::zeroed();
+    let mut length = size_of::<T>() as i32;
+    syscall!(
+        getsockopt(
+            socket,
+            level,
+            name,
+            value.as_mut_ptr() as *mut _,
+            &mut length,
+        ),
+        PartialEq::eq,
+        SOCKET_ERROR
+    )?;
+    // This is safe because `value` is zeroed and (partially) initialised by
+    // `getsockopt`, only integers (or structs of them) are used for `T`.
+    Ok(unsafe { value.assume_init() })
+}
@@ -1,25 +1,34 @@
-use std::io;
+use std::convert::TryInto;
+use std::mem::{size_of, ManuallyDrop};
 use std::net::{self, SocketAddr};
-use std::os::windows::io::AsRawSocket;
+use std::os::windows::io::{AsRawSocket, FromRawSocket};
+use std::time::Duration;
+use std::{cmp, io, ptr};
 
-use windows_sys::Win32::Networking::WinSock::{self, SOCKET, SOCKET_ERROR, SOCK_STREAM};
+use windows_sys::Win32::Networking::WinSock::{
+    self, AF_INET, AF_INET6, IPPROTO_IPV6, IPV6_V6ONLY, SOCKET, SOCKET_ERROR, SOCK_STREAM,
+    SOL_SOCKET, SO_KEEPALIVE, SO_REUSEADDR,
+};
 
-use crate::sys::windows::net::{new_ip_socket, socket_addr};
+use crate::net::TcpKeepalive;
+use crate::sys::windows::net::{new_socket, option, set_option, socket_addr};
 
-pub(crate) fn new_for_addr(address: SocketAddr) -> io::Result<SOCKET> {
-    new_ip_socket(address, SOCK_STREAM)
+pub(crate) type TcpSocket = SOCKET;
+
+pub(crate) fn new_v4_socket() -> io::Result<TcpSocket> {
+    new_socket(AF_INET.into(), SOCK_STREAM)
+}
+
+pub(crate) fn new_v6_socket() -> io::Result<TcpSocket> {
+    new_socket(AF_INET6.into(), SOCK_STREAM)
 }
 
-pub(crate) fn bind(socket: &net::TcpListener, addr: SocketAddr) -> io::Result<()> {
+pub(crate) fn bind(socket: TcpSocket, addr: SocketAddr) -> io::Result<()> {
     use WinSock::bind;
 
     let (raw_addr, raw_addr_length) = socket_addr(&addr);
     syscall!(
-        bind(
-            socket.as_raw_socket() as _,
-            raw_addr.as_ptr(),
-            raw_addr_length
-        ),
+        bind(socket, raw_addr.as_ptr(), raw_addr_length),
         PartialEq::eq,
         SOCKET_ERROR
     )?;
@@ -47,7 +56,6 @@ pub(crate) fn connect(socket: &net::TcpStream, addr: SocketAddr) -> io::Result<(
 }
 
 pub(crate) fn listen(socket: &net::TcpListener, backlog: u32) -> io::Result<()> {
-    use std::convert::TryInto;
     use WinSock::listen;
 
     let backlog = backlog.try_into().unwrap_or(i32::max_value());
@@ -59,6 +67,115 @@ pub(crate) fn listen(socket: &net::TcpListener, backlog: u32) -> io::Result<()>
     Ok(())
 }
 
+pub(crate) fn close(socket: TcpSocket) {
+    let _ = unsafe { net::TcpStream::from_raw_socket(socket as _) };
+}
+
+pub(crate) fn set_reuseaddr(socket: TcpSocket, reuseaddr: bool) -> io::Result<()> {
+    set_option(
+        socket,
+        SOL_SOCKET as i32,
+        SO_REUSEADDR as i32,
+        i32::from(reuseaddr),
+    )
+}
+
+pub(crate) fn reuseaddr(socket: TcpSocket) -> io::Result<bool> {
+    option::<i32>(socket, SOL_SOCKET as i32, SO_REUSEADDR as i32).map(|val| val != 0)
+}
+
+pub(crate) fn set_only_v6(socket: TcpSocket, only_v6: bool) -> io::Result<()> {
+    set_option(
+        socket,
+        IPPROTO_IPV6 as i32,
+        IPV6_V6ONLY as i32,
+        i32::from(only_v6),
+    )
+}
+
+pub(crate) fn only_v6(socket: TcpSocket) -> io::Result<bool> {
+    option::<i32>(socket, IPPROTO_IPV6 as i32, IPV6_V6ONLY as i32).map(|val| val != 0)
+}
+
+pub(crate) fn set_keepalive(
+    socket: &net::TcpStream,
+    keepalive: Option<TcpKeepalive>,
+) -> io::Result<()> {
+    use WinSock::WSAIoctl;
+
+    /// `struct tcp_keepalive`.
+    #[repr(C)]
+    struct tcp_keepalive {
+        onoff: u32,
+        keepalivetime: u32,
+        keepaliveinterval: u32,
+    }
+    const SIO_KEEPALIVE_VALS: u32 = 0x9800_0004;
+    // Defaults documented for `SIO_KEEPALIVE_VALS`, both must be set.
+    const DEFAULT_TIME: Duration = Duration::from_secs(2 * 60 * 60);
+    const DEFAULT_INTERVAL: Duration = Duration::from_secs(1);
+
+    fn millis(duration: Duration) -> u32 {
+        let millis = duration.as_millis() + u128::from(duration.subsec_nanos() % 1_000_000 != 0);
+        cmp::min(millis, u32::max_value() as u128) as u32
+    }
+
+    let socket = socket.as_raw_socket() as TcpSocket;
+    let keepalive = match keepalive {
+        Some(keepalive) if keepalive.time.is_some() || keepalive.interval.is_some() => keepalive,
+        keepalive => {
+            return set_option(
+                socket,
+                SOL_SOCKET as i32,
+                SO_KEEPALIVE as i32,
+                i32::from(keepalive.is_some()),
+            )
+        }
+    };
+
+    let values = tcp_keepalive {
+        onoff: 1,
+        keepalivetime: millis(keepalive.time.unwrap_or(DEFAULT_TIME)),
+        keepaliveinterval: millis(keepalive.interval.unwrap_or(DEFAULT_INTERVAL)),
+    };
+    let mut returned = 0;
+    syscall!(
+        WSAIoctl(
+            socket,
+            SIO_KEEPALIVE_VALS,
+            &values as *const tcp_keepalive as *const _,
+            size_of::<tcp_keepalive>() as u32,
+            ptr::null_mut(),
+            0,
+            &mut returned,
+            ptr::null_mut(),
+            None,
+        ),
+        PartialEq::eq,
+        SOCKET_ERROR
+    )?;
+    Ok(())
+}
+
+pub(crate) fn keepalive(socket: &net::TcpStream) -> io::Result<Option<TcpKeepalive>> {
+    let socket = socket.as_raw_socket() as TcpSocket;
+    // The parameters set using `SIO_KEEPALIVE_VALS` can't be retrieved.
+    option::<i32>(socket, SOL_SOCKET as i32, SO_KEEPALIVE as i32).map(|val| {
+        if val != 0 {
+            Some(TcpKeepalive::new())
+        } else {
+            None
+        }
+    })
+}
+
+pub(crate) fn local_addr(socket: TcpSocket) -> io::Result<SocketAddr> {
+    // Borrow the socket as `net::TcpListener` to reuse its implementation,
+    // `ManuallyDrop` ensures we don't close it.
+    let listener = ManuallyDrop::new(unsafe { net::TcpListener::from_raw_socket(socket as _) });
+    listener.local_addr()
+}
+
 pub(crate) fn accept(listener: &net::TcpListener) -> io::Result<(net::TcpStream, SocketAddr)> {
     // The non-blocking state of `listener` is inherited. See
     // https://docs.microsoft.com/en-us/windows/win32/api/winsock2/nf-winsock2-accept#remarks.
@@ -0,0 +1,81 @@
+use std::future::Future;
+use std::io;
+use std::sync::Arc;
+use std::task::{self, Context};
+
+use crate::{Events, Poll, Waker};
+
+/// Run `future` to completion on the current thread, parking in
+/// [`Poll::poll`] while it's pending.
+///
+/// This is a minimal executor for programs that only need to run a few
+/// futures next to Mio's event sources. The future is polled again after
+/// every call to `Poll::poll`, i.e. when it's woken using a waker created
+/// from `waker` (see [`Waker::into_task_waker`]), or when an event is received
+/// for any of the event sources registered with `poll`. Futures using Mio's
+/// types directly should try the I/O operation when polled and return
+/// `Pending` on a [`WouldBlock`] error.
+///
+/// `waker` must be registered with `poll`. Events are received in a buffer
+/// owned by this function, so they are not available to the caller.
+///
+/// [`WouldBlock`]: io::ErrorKind::WouldBlock
+///
+/// # Examples
+///
+#[cfg_attr(feature = "os-poll", doc = "```")]
+#[cfg_attr(not(feature = "os-poll"), doc = "```ignore")]
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use std::future::Future;
+/// use std::pin::Pin;
+/// use std::sync::Arc;
+/// use std::task::{Context, Poll as TaskPoll};
+///
+/// use mio::util::block_on;
+/// use mio::{Poll, Token, Waker};
+///
+/// /// Future that's ready after being polled once.
+/// struct YieldOnce(bool);
+///
+/// impl Future for YieldOnce {
+///     type Output = &'static str;
+///
+///     fn poll(mut self: Pin<&mut Self>, ctx: &mut Context<'_>) -> TaskPoll<Self::Output> {
+///         if self.0 {
+///             TaskPoll::Ready("done")
+///         } else {
+///             self.0 = true;
+///             ctx.waker().wake_by_ref();
+///             TaskPoll::Pending
+///         }
+///     }
+/// }
+///
+/// let mut poll = Poll::new()?;
+/// let waker = Arc::new(Waker::new(poll.registry(), Token(0))?);
+///
+/// let output = block_on(&mut poll, &waker, YieldOnce(false))?;
+/// assert_eq!(output, "done");
+/// #     Ok(())
+/// # }
+/// ```
+pub fn block_on<F>(poll: &mut Poll, waker: &Arc<Waker>, future: F) -> io::Result<F::Output>
+where
+    F: Future,
+{
+    let task_waker = waker.clone().into_task_waker();
+    let mut ctx = Context::from_waker(&task_waker);
+    let mut future = Box::pin(future);
+    let mut events = Events::with_capacity(64);
+    loop {
+        if let task::Poll::Ready(output) = future.as_mut().poll(&mut ctx) {
+            return Ok(output);
+        }
+
+        match poll.poll(&mut events, None) {
+            Ok(()) => {}
+            Err(ref err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+}
@@ -0,0 +1,469 @@
+use std::cmp::{min, Reverse};
+use std::collections::{BinaryHeap, HashMap};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::{Duration, Instant};
+use std::{fmt, io, mem};
+
+use crate::event::Event;
+use crate::util::TokenSlab;
+use crate::{Events, Poll, Registry, Token, Waker};
+
+/// Token used for the `Waker` of the notify channel, `TokenSlab` never hands
+/// it out.
+const NOTIFY: Token = Token(usize::MAX);
+
+/// Callbacks for an [`EventLoop`].
+///
+/// All handlers in an event loop have the same type, use an `enum` if
+/// different kinds of handlers are needed (e.g. a listener and connections).
+/// Each handler is identified by the [`Token`] returned when it was inserted
+/// into the event loop. During a callback the handler is temporarily taken out
+/// of the event loop, so it can freely use the `EventLoop` passed to it.
+pub trait Handler: Sized {
+    /// Value passed to [`Handler::timeout`], see [`EventLoop::timeout`].
+    type Timeout;
+    /// Message passed to [`Handler::notify`], see [`EventLoop::channel`].
+    type Message;
+
+    /// Called for each event for the handler's token.
+    fn ready(&mut self, event_loop: &mut EventLoop<Self>, event: &Event);
+
+    /// Called once a timeout set by [`EventLoop::timeout`] for the handler's
+    /// `token` expires.
+    fn timeout(
+        &mut self,
+        _event_loop: &mut EventLoop<Self>,
+        _token: Token,
+        _timeout: Self::Timeout,
+    ) {
+    }
+
+    /// Called for each message sent to the handler's `token` using a
+    /// [`Sender`].
+    fn notify(&mut self, _event_loop: &mut EventLoop<Self>, _token: Token, _msg: Self::Message) {}
+}
+
+/// Callback based event loop on top of [`Poll`].
+///
+/// The event loop owns a `Poll` instance, the [`Handler`]s and their timeouts.
+/// Each iteration it polls for events, dispatches them to the handler for the
+/// event's token, delivers messages sent using a [`Sender`] and calls the
+/// handlers of the expired timeouts.
+///
+/// Tokens are handed out by a [`TokenSlab`], so events, timeouts and messages
+/// for a removed handler are never delivered to a handler inserted later.
+/// `Token(usize::MAX)` is used internally by the event loop.
+///
+/// # Examples
+///
+#[cfg_attr(all(feature = "os-poll", feature = "net"), doc = "```")]
+#[cfg_attr(not(all(feature = "os-poll", feature = "net")), doc = "```ignore")]
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use std::io::{self, Read};
+///
+/// use mio::event::Event;
+/// use mio::net::{TcpListener, TcpStream};
+/// use mio::util::{EventLoop, Handler};
+/// use mio::Interest;
+///
+/// enum Server {
+///     Listener(TcpListener),
+///     Connection(TcpStream),
+/// }
+///
+/// impl Handler for Server {
+///     type Timeout = ();
+///     type Message = ();
+///
+///     fn ready(&mut self, event_loop: &mut EventLoop<Self>, event: &Event) {
+///         match self {
+///             Server::Listener(listener) => loop {
+///                 let mut stream = match listener.accept() {
+///                     Ok((stream, _)) => stream,
+///                     Err(_) => return,
+///                 };
+///                 let _ = event_loop.insert_with(|registry, token| {
+///                     registry.register(&mut stream, token, Interest::READABLE)?;
+///                     Ok(Server::Connection(stream))
+///                 });
+///             },
+///             Server::Connection(stream) => {
+///                 let mut buf = [0; 1024];
+///                 loop {
+///                     match stream.read(&mut buf) {
+///                         Ok(0) => break,
+///                         Ok(n) => println!("read {} bytes", n),
+///                         Err(ref err) if err.kind() == io::ErrorKind::WouldBlock => return,
+///                         Err(_) => break,
+///                     }
+///                 }
+///                 // Connection closed (or failed).
+///                 let _ = event_loop.registry().deregister(stream);
+///                 let _ = event_loop.remove(event.token());
+///                 // We only handle a single connection in this example.
+///                 event_loop.shutdown();
+///             }
+///         }
+///     }
+/// }
+///
+/// let mut event_loop = EventLoop::new()?;
+///
+/// let mut listener = TcpListener::bind("127.0.0.1:0".parse()?)?;
+/// let address = listener.local_addr()?;
+/// event_loop.insert_with(|registry, token| {
+///     registry.register(&mut listener, token, Interest::READABLE)?;
+///     Ok(Server::Listener(listener))
+/// })?;
+/// # let _ = std::thread::spawn(move || drop(std::net::TcpStream::connect(address)));
+///
+/// // Runs until the first connection is closed.
+/// event_loop.run()?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct EventLoop<H: Handler> {
+    poll: Poll,
+    events: Events,
+    /// A handler is `None` while its callback is running.
+    handlers: TokenSlab<Option<H>>,
+    /// Deadlines of the timeouts, cleared timeouts are removed lazily.
+    deadlines: BinaryHeap<Reverse<(Instant, u64)>>,
+    timeouts: HashMap<u64, (Token, H::Timeout)>,
+    next_timeout_id: u64,
+    shared: Arc<Shared>,
+    sender: mpsc::Sender<(Token, H::Message)>,
+    receiver: mpsc::Receiver<(Token, H::Message)>,
+    running: bool,
+}
+
+/// State shared between the `EventLoop` and `Sender`s.
+#[derive(Debug)]
+pub(crate) struct Shared {
+    waker: Waker,
+    shutdown: AtomicBool,
+}
+
+impl Shared {
+    /// Wake up the event loop.
+    pub(crate) fn wake(&self) -> io::Result<()> {
+        self.waker.wake()
+    }
+
+    /// Shut down the event loop once its current iteration is completed.
+    pub(crate) fn shutdown(&self) -> io::Result<()> {
+        self.shutdown.store(true, Ordering::Release);
+        self.waker.wake()
+    }
+}
+
+/// Handle to a timeout set using [`EventLoop::timeout`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Timer(u64);
+
+impl<H: Handler> EventLoop<H> {
+    /// Create a new `EventLoop`, receiving up to 1024 events per iteration.
+    pub fn new() -> io::Result<EventLoop<H>> {
+        EventLoop::with_capacity(1024)
+    }
+
+    /// Create a new `EventLoop`, receiving up to `capacity` events per
+    /// iteration.
+    pub fn with_capacity(capacity: usize) -> io::Result<EventLoop<H>> {
+        let poll = Poll::new()?;
+        let waker = Waker::new(poll.registry(), NOTIFY)?;
+        let (sender, receiver) = mpsc::channel();
+        Ok(EventLoop {
+            poll,
+            events: Events::with_capacity(capacity),
+            handlers: TokenSlab::new(),
+            deadlines: BinaryHeap::new(),
+            timeouts: HashMap::new(),
+            next_timeout_id: 0,
+            shared: Arc::new(Shared {
+                waker,
+                shutdown: AtomicBool::new(false),
+            }),
+            sender,
+            receiver,
+            running: false,
+        })
+    }
+
+    /// Returns the `Registry` of the event loop's `Poll` instance.
+    pub fn registry(&self) -> &Registry {
+        self.poll.registry()
+    }
+
+    /// Insert `handler`, returning its token.
+    pub fn insert(&mut self, handler: H) -> Token {
+        self.handlers.insert(Some(handler))
+    }
+
+    /// Insert the handler returned by `f`, returning its token.
+    ///
+    /// `f` is called with the token for the handler, so it can register its
+    /// event source(s) before it's inserted. If `f` returns an error nothing is
+    /// inserted.
+    pub fn insert_with<F>(&mut self, f: F) -> io::Result<Token>
+    where
+        F: FnOnce(&Registry, Token) -> io::Result<H>,
+    {
+        let token = self.handlers.vacant_token();
+        let handler = f(self.poll.registry(), token)?;
+        Ok(self.handlers.insert(Some(handler)))
+    }
+
+    /// Returns the handler for `token`.
+    ///
+    /// Returns `None` if no handler exists for `token` or if it's the handler
+    /// currently running a callback.
+    pub fn get(&self, token: Token) -> Option<&H> {
+        self.handlers.get(token).and_then(Option::as_ref)
+    }
+
+    /// Returns the handler for `token`.
+    ///
+    /// Returns `None` if no handler exists for `token` or if it's the handler
+    /// currently running a callback.
+    pub fn get_mut(&mut self, token: Token) -> Option<&mut H> {
+        self.handlers.get_mut(token).and_then(Option::as_mut)
+    }
+
+    /// Remove the handler for `token`.
+    ///
+    /// The handler is responsible for deregistering its event sources. If the
+    /// handler is currently running a callback it's dropped once the callback
+    /// returns and this returns `None`.
+    pub fn remove(&mut self, token: Token) -> Option<H> {
+        self.handlers.remove(token).and_then(|handler| handler)
+    }
+
+    /// Returns the number of handlers in the event loop.
+    pub fn len(&self) -> usize {
+        self.handlers.len()
+    }
+
+    /// Returns `true` if the event loop contains no handlers.
+    pub fn is_empty(&self) -> bool {
+        self.handlers.is_empty()
+    }
+
+    /// Call [`Handler::timeout`] with `timeout` for the handler of `token`
+    /// once `delay` has passed.
+    ///
+    /// The timeout isn't delivered if the handler is removed before it
+    /// expires. If `delay` is too large to be represented as an `Instant`
+    /// the timeout never expires, but it can still be cleared.
+    pub fn timeout(&mut self, token: Token, delay: Duration, timeout: H::Timeout) -> Timer {
+        let id = self.next_timeout_id;
+        self.next_timeout_id += 1;
+        if let Some(deadline) = Instant::now().checked_add(delay) {
+            self.deadlines.push(Reverse((deadline, id)));
+        }
+        let _ = self.timeouts.insert(id, (token, timeout));
+        Timer(id)
+    }
+
+    /// Clear a timeout, returning its value if it hadn't expired yet.
+    pub fn clear_timeout(&mut self, timer: Timer) -> Option<H::Timeout> {
+        self.timeouts.remove(&timer.0).map(|(_, timeout)| timeout)
+    }
+
+    /// Returns a `Sender` that can be used to send messages to the handlers,
+    /// or to shut down the event loop, from any thread.
+    pub fn channel(&self) -> Sender<H::Message> {
+        Sender {
+            sender: self.sender.clone(),
+            shared: self.shared.clone(),
+        }
+    }
+
+    /// Stop running the event loop once the current iteration is completed.
+    pub fn shutdown(&mut self) {
+        self.running = false;
+    }
+
+    /// Returns `true` if the event loop is running, i.e. [`run`] was called
+    /// and the event loop wasn't shut down yet.
+    ///
+    /// [`run`]: EventLoop::run
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    /// Run the event loop until it's shut down, using either
+    /// [`EventLoop::shutdown`] or [`Sender::shutdown`].
+    ///
+    /// Returns early if polling returns an error, other than
+    /// [`Interrupted`].
+    ///
+    /// [`Interrupted`]: io::ErrorKind::Interrupted
+    pub fn run(&mut self) -> io::Result<()> {
+        self.run_with(|_| {})
+    }
+
+    /// Same as [`EventLoop::run`], but calls `f` after each iteration.
+    pub(crate) fn run_with<F>(&mut self, mut f: F) -> io::Result<()>
+    where
+        F: FnMut(&mut EventLoop<H>),
+    {
+        self.running = true;
+        while self.running {
+            if let Err(err) = self.run_once(None) {
+                self.running = false;
+                return Err(err);
+            }
+            f(self);
+        }
+        Ok(())
+    }
+
+    /// Returns the state shared with `Sender`s.
+    pub(crate) fn shared(&self) -> Arc<Shared> {
+        self.shared.clone()
+    }
+
+    /// Run a single iteration of the event loop: poll for events, blocking
+    /// for at most `timeout` (or until the first timeout expires), and
+    /// dispatch the events, messages and expired timeouts.
+    pub fn run_once(&mut self, timeout: Option<Duration>) -> io::Result<()> {
+        let timeout = self.poll_timeout(timeout);
+        let mut events = mem::replace(&mut self.events, Events::with_capacity(0));
+        match self.poll.poll(&mut events, timeout) {
+            Ok(()) => {}
+            Err(ref err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => {
+                self.events = events;
+                return Err(err);
+            }
+        }
+
+        for event in events.iter() {
+            if event.token() != NOTIFY {
+                self.dispatch(event.token(), |handler, event_loop| {
+                    handler.ready(event_loop, event)
+                });
+            }
+        }
+        self.events = events;
+
+        // Only deliver the messages sent before this point, otherwise a
+        // handler sending messages to itself could block the event loop.
+        let mut pending = Vec::new();
+        while let Ok(msg) = self.receiver.try_recv() {
+            pending.push(msg);
+        }
+        for (token, msg) in pending {
+            self.dispatch(token, |handler, event_loop| {
+                handler.notify(event_loop, token, msg)
+            });
+        }
+
+        let now = Instant::now();
+        while let Some(&Reverse((deadline, id))) = self.deadlines.peek() {
+            if deadline > now {
+                break;
+            }
+            let _ = self.deadlines.pop();
+            if let Some((token, timeout)) = self.timeouts.remove(&id) {
+                self.dispatch(token, |handler, event_loop| {
+                    handler.timeout(event_loop, token, timeout)
+                });
+            }
+        }
+
+        if self.shared.shutdown.swap(false, Ordering::AcqRel) {
+            self.running = false;
+        }
+        Ok(())
+    }
+
+    /// Returns the timeout to use when polling, taking the timeouts into
+    /// account.
+    fn poll_timeout(&mut self, timeout: Option<Duration>) -> Option<Duration> {
+        while let Some(&Reverse((deadline, id))) = self.deadlines.peek() {
+            if !self.timeouts.contains_key(&id) {
+                // Cleared timeout.
+                let _ = self.deadlines.pop();
+                continue;
+            }
+            let until = deadline.saturating_duration_since(Instant::now());
+            return Some(timeout.map_or(until, |timeout| min(timeout, until)));
+        }
+        timeout
+    }
+
+    /// Call `f` with the handler for `token`, if any.
+    fn dispatch<F>(&mut self, token: Token, f: F)
+    where
+        F: FnOnce(&mut H, &mut EventLoop<H>),
+    {
+        let mut handler = match self.handlers.get_mut(token).and_then(Option::take) {
+            Some(handler) => handler,
+            None => return,
+        };
+        f(&mut handler, self);
+        // The handler could have been removed by the callback.
+        if let Some(slot) = self.handlers.get_mut(token) {
+            *slot = Some(handler);
+        }
+    }
+}
+
+impl<H: Handler> fmt::Debug for EventLoop<H> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventLoop")
+            .field("poll", &self.poll)
+            .field("handlers", &self.handlers.len())
+            .field("timeouts", &self.timeouts.len())
+            .field("running", &self.running)
+            .finish()
+    }
+}
+
+/// Sends messages to the handlers of an [`EventLoop`], see
+/// [`EventLoop::channel`].
+pub struct Sender<M> {
+    sender: mpsc::Sender<(Token, M)>,
+    shared: Arc<Shared>,
+}
+
+impl<M> Sender<M> {
+    /// Send `msg` to the handler for `token`, it's passed to
+    /// [`Handler::notify`].
+    ///
+    /// Messages for a handler that doesn't exist are dropped. Returns a
+    /// [`BrokenPipe`] error if the event loop is dropped.
+    ///
+    /// [`BrokenPipe`]: io::ErrorKind::BrokenPipe
+    pub fn send(&self, token: Token, msg: M) -> io::Result<()> {
+        self.sender
+            .send((token, msg))
+            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "event loop dropped"))?;
+        self.shared.wake()
+    }
+
+    /// Shut down the event loop once its current iteration is completed, see
+    /// [`EventLoop::shutdown`].
+    pub fn shutdown(&self) -> io::Result<()> {
+        self.shared.shutdown()
+    }
+}
+
+impl<M> Clone for Sender<M> {
+    fn clone(&self) -> Sender<M> {
+        Sender {
+            sender: self.sender.clone(),
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<M> fmt::Debug for Sender<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sender").finish()
+    }
+}
@@ -0,0 +1,22 @@
+//! Utilities built on top of Mio's core types.
+//!
+//! The types in this module are not needed to use Mio, but implement patterns
+//! common to most programs using it.
+
+#[cfg(not(target_os = "wasi"))]
+mod block_on;
+#[cfg(not(target_os = "wasi"))]
+pub use self::block_on::block_on;
+
+#[cfg(not(target_os = "wasi"))]
+mod event_loop;
+#[cfg(not(target_os = "wasi"))]
+pub use self::event_loop::{EventLoop, Handler, Sender, Timer};
+
+#[cfg(not(target_os = "wasi"))]
+mod shards;
+#[cfg(not(target_os = "wasi"))]
+pub use self::shards::{Balance, Shard, ShardHandle, Shards};
+
+mod token_slab;
+pub use self::token_slab::{Iter, TokenSlab};
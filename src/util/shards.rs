@@ -0,0 +1,418 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc, Mutex, MutexGuard, PoisonError};
+use std::{fmt, io, mem, panic, thread};
+
+use crate::event::Source;
+use crate::util::event_loop::Shared;
+use crate::util::{EventLoop, Handler};
+use crate::Registry;
+
+/// Strategy used by [`Shards::dispatch`] to pick a shard.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Balance {
+    /// Pick the shards in turn.
+    RoundRobin,
+    /// Pick the shard with the fewest handlers and queued sources.
+    LeastLoaded,
+}
+
+/// Multi-threaded reactor: a number of threads, the shards, each running its
+/// own [`EventLoop`].
+///
+/// Sources, e.g. accepted [`TcpStream`]s or [`UnixStream`]s, are handed to
+/// the shards using [`Shards::dispatch`]. Once received by a shard the `add`
+/// function passed to [`Shards::new`] is called on the shard's thread, which
+/// should register the source and insert a handler for it into the shard's
+/// event loop.
+///
+/// A source can be moved to another shard using [`ShardHandle::migrate`],
+/// e.g. from within a handler. A [`Shard`] (passed to `add`) gives access to
+/// the handles of all shards.
+///
+/// [`TcpStream`]: crate::net::TcpStream
+/// [`UnixStream`]: crate::net::UnixStream
+///
+/// # Examples
+///
+#[cfg_attr(all(feature = "os-poll", feature = "net"), doc = "```")]
+#[cfg_attr(not(all(feature = "os-poll", feature = "net")), doc = "```ignore")]
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use std::io::Write;
+///
+/// use mio::event::Event;
+/// use mio::net::{TcpListener, TcpStream};
+/// use mio::util::{Balance, EventLoop, Handler, Shards};
+/// use mio::Interest;
+///
+/// struct Connection(TcpStream);
+///
+/// impl Handler for Connection {
+///     type Timeout = ();
+///     type Message = ();
+///
+///     fn ready(&mut self, event_loop: &mut EventLoop<Self>, event: &Event) {
+///         if event.is_writable() {
+///             let _ = self.0.write_all(b"Hello world!\n");
+///             let _ = event_loop.registry().deregister(&mut self.0);
+///             let _ = event_loop.remove(event.token());
+///         }
+///     }
+/// }
+///
+/// let mut shards = Shards::new(2, Balance::LeastLoaded, |event_loop, _, mut stream: TcpStream| {
+///     let _ = event_loop.insert_with(|registry, token| {
+///         registry.register(&mut stream, token, Interest::WRITABLE)?;
+///         Ok(Connection(stream))
+///     });
+/// })?;
+///
+/// let listener = TcpListener::bind("127.0.0.1:0".parse()?)?;
+/// # let address = listener.local_addr()?;
+/// # let _ = std::thread::spawn(move || drop(std::net::TcpStream::connect(address)));
+/// # let mut poll = mio::Poll::new()?;
+/// # let mut events = mio::Events::with_capacity(8);
+/// # let mut listener = listener;
+/// # poll.registry().register(&mut listener, mio::Token(0), Interest::READABLE)?;
+/// # poll.poll(&mut events, None)?;
+/// // Normally the listener is registered with a `Poll` instance and accepts
+/// // connections once it's readable.
+/// let (stream, _) = listener.accept()?;
+/// shards.dispatch(stream)?;
+///
+/// shards.shutdown()?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct Shards<S> {
+    handles: Arc<[ShardHandle<S>]>,
+    threads: Vec<thread::JoinHandle<io::Result<()>>>,
+    balance: Balance,
+    next: usize,
+}
+
+/// Handle to a single shard of [`Shards`], can be used from any thread.
+pub struct ShardHandle<S> {
+    index: usize,
+    queue: Arc<Queue<S>>,
+    registry: Arc<Registry>,
+    shared: Arc<Shared>,
+}
+
+/// Sources sent to a shard.
+struct Queue<S> {
+    /// Sources not yet added to the shard, `None` once the shard is stopped.
+    sources: Mutex<Option<Vec<S>>>,
+    /// Number of handlers in the shard's event loop, updated after each
+    /// iteration.
+    handlers: AtomicUsize,
+    /// Number of sources sent to the shard, but not yet added. Only decreased
+    /// after `handlers` is updated, so the load is never underestimated.
+    queued: AtomicUsize,
+}
+
+impl<S> Queue<S> {
+    fn lock(&self) -> MutexGuard<'_, Option<Vec<S>>> {
+        self.sources.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Marks the queue as stopped when the shard's thread stops, also if it
+/// panics.
+struct Stopped<S>(Arc<Queue<S>>);
+
+impl<S> Drop for Stopped<S> {
+    fn drop(&mut self) {
+        let _ = self.0.lock().take();
+    }
+}
+
+/// The shard running the current thread, passed to the `add` function of
+/// [`Shards::new`].
+pub struct Shard<S> {
+    index: usize,
+    handles: Arc<[ShardHandle<S>]>,
+}
+
+impl<S: Send + 'static> Shards<S> {
+    /// Start `n` shards, each running a new [`EventLoop`] on its own thread.
+    ///
+    /// `add` is called on the shard's thread for each source sent to the
+    /// shard, using either [`Shards::dispatch`] or [`ShardHandle::send`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is zero.
+    pub fn new<H, F>(n: usize, balance: Balance, add: F) -> io::Result<Shards<S>>
+    where
+        H: Handler + 'static,
+        F: Fn(&mut EventLoop<H>, &Shard<S>, S) + Send + Sync + 'static,
+    {
+        assert!(n != 0, "can't create zero shards");
+        let add = Arc::new(add);
+        let mut threads = Vec::with_capacity(n);
+        let mut handles = Vec::with_capacity(n);
+        let mut starts = Vec::with_capacity(n);
+        for index in 0..n {
+            let (ready_send, ready_recv) = mpsc::channel();
+            let (start_send, start_recv) = mpsc::channel::<Arc<[ShardHandle<S>]>>();
+            let add = add.clone();
+            let thread = thread::Builder::new()
+                .name(format!("mio-shard-{}", index))
+                .spawn(move || {
+                    let (mut event_loop, queue) = match new_shard::<H, S>(index) {
+                        Ok((event_loop, handle)) => {
+                            let queue = Stopped(handle.queue.clone());
+                            let _ = ready_send.send(Ok(handle));
+                            (event_loop, queue)
+                        }
+                        Err(err) => {
+                            let _ = ready_send.send(Err(err));
+                            return Ok(());
+                        }
+                    };
+                    // If starting another shard failed we're never started.
+                    let shard = match start_recv.recv() {
+                        Ok(handles) => Shard { index, handles },
+                        Err(_) => return Ok(()),
+                    };
+                    let mut sources = Vec::new();
+                    event_loop.run_with(|event_loop| {
+                        if let Some(queued) = &mut *queue.0.lock() {
+                            mem::swap(queued, &mut sources);
+                        }
+                        let added = sources.len();
+                        for source in sources.drain(..) {
+                            add(event_loop, &shard, source);
+                        }
+                        queue.0.handlers.store(event_loop.len(), Ordering::Relaxed);
+                        let _ = queue.0.queued.fetch_sub(added, Ordering::Relaxed);
+                    })
+                })?;
+            match ready_recv.recv() {
+                Ok(Ok(handle)) => handles.push(handle),
+                Ok(Err(err)) => return Err(err),
+                // The thread panicked while creating the event loop.
+                Err(_) => match thread.join() {
+                    Ok(_) => unreachable!("shard thread stopped without sending its handle"),
+                    Err(panic) => panic::resume_unwind(panic),
+                },
+            }
+            threads.push(thread);
+            starts.push(start_send);
+        }
+
+        let handles: Arc<[ShardHandle<S>]> = handles.into();
+        for start in starts {
+            let _ = start.send(handles.clone());
+        }
+        Ok(Shards {
+            handles,
+            threads,
+            balance,
+            next: 0,
+        })
+    }
+
+    /// Send `source` to one of the shards, picked based on the [`Balance`]
+    /// strategy. Returns the index of the shard.
+    pub fn dispatch(&mut self, source: S) -> io::Result<usize> {
+        let index = match self.balance {
+            Balance::RoundRobin => {
+                let index = self.next;
+                self.next = (self.next + 1) % self.handles.len();
+                index
+            }
+            Balance::LeastLoaded => self
+                .handles
+                .iter()
+                .min_by_key(|handle| handle.load())
+                .map_or(0, |handle| handle.index),
+        };
+        self.handles[index].send(source).map(|()| index)
+    }
+
+    /// Returns the handles of all shards.
+    pub fn handles(&self) -> &[ShardHandle<S>] {
+        &self.handles
+    }
+
+    /// Returns the number of shards.
+    pub fn len(&self) -> usize {
+        self.handles.len()
+    }
+
+    /// Returns `false`, there is always at least one shard.
+    pub fn is_empty(&self) -> bool {
+        self.handles.is_empty()
+    }
+
+    /// Shut down all shards and wait for their threads to stop.
+    ///
+    /// Returns the first error returned by a shard's event loop. Sources not
+    /// yet added to a shard are dropped.
+    ///
+    /// # Panics
+    ///
+    /// If a shard's thread panicked the panic is propagated.
+    pub fn shutdown(mut self) -> io::Result<()> {
+        self.stop()
+    }
+}
+
+impl<S> Shards<S> {
+    fn stop(&mut self) -> io::Result<()> {
+        for handle in self.handles.iter() {
+            let _ = handle.shared.shutdown();
+        }
+        let mut result = Ok(());
+        for thread in mem::take(&mut self.threads) {
+            match thread.join() {
+                Ok(res) => result = result.and(res),
+                Err(panic) => panic::resume_unwind(panic),
+            }
+        }
+        result
+    }
+}
+
+/// Create the event loop for a new shard.
+fn new_shard<H: Handler, S>(index: usize) -> io::Result<(EventLoop<H>, ShardHandle<S>)> {
+    let event_loop = EventLoop::new()?;
+    let registry = Arc::new(event_loop.registry().try_clone()?);
+    let handle = ShardHandle {
+        index,
+        queue: Arc::new(Queue {
+            sources: Mutex::new(Some(Vec::new())),
+            handlers: AtomicUsize::new(0),
+            queued: AtomicUsize::new(0),
+        }),
+        registry,
+        shared: event_loop.shared(),
+    };
+    Ok((event_loop, handle))
+}
+
+impl<S> Drop for Shards<S> {
+    fn drop(&mut self) {
+        for handle in self.handles.iter() {
+            let _ = handle.shared.shutdown();
+        }
+        for thread in self.threads.drain(..) {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl<S> fmt::Debug for Shards<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Shards")
+            .field("handles", &self.handles)
+            .field("balance", &self.balance)
+            .finish()
+    }
+}
+
+impl<S> ShardHandle<S> {
+    /// Returns the index of the shard.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns the `Registry` of the shard's event loop.
+    ///
+    /// The tokens are owned by the shard's event loop, so this should only be
+    /// used to reregister or deregister sources, e.g. to stop receiving events
+    /// for a source before it's migrated.
+    pub fn registry(&self) -> &Registry {
+        &self.registry
+    }
+
+    /// Returns the approximate load of the shard: the number of handlers in
+    /// its event loop plus the number of sources sent to it not yet added.
+    pub fn load(&self) -> usize {
+        self.queue.handlers.load(Ordering::Relaxed) + self.queue.queued.load(Ordering::Relaxed)
+    }
+
+    /// Send `source` to the shard, where it's passed to the `add` function of
+    /// [`Shards::new`].
+    ///
+    /// Returns a [`BrokenPipe`] error if the shard is stopped.
+    ///
+    /// [`BrokenPipe`]: io::ErrorKind::BrokenPipe
+    pub fn send(&self, source: S) -> io::Result<()> {
+        match &mut *self.queue.lock() {
+            Some(sources) => {
+                sources.push(source);
+                let _ = self.queue.queued.fetch_add(1, Ordering::Relaxed);
+            }
+            None => return Err(io::Error::new(io::ErrorKind::BrokenPipe, "shard stopped")),
+        }
+        self.shared.wake()
+    }
+
+    /// Migrate `source` to this shard: deregister it from `registry`, the
+    /// registry of the shard currently owning it, and [send] it to this shard.
+    ///
+    /// The caller is responsible for removing the handler that owned the
+    /// source from its event loop. Events for the source already received by
+    /// the old shard may still be delivered there, but because tokens are
+    /// never reused for another handler they are not delivered to a handler
+    /// inserted later.
+    ///
+    /// [send]: ShardHandle::send
+    pub fn migrate(&self, registry: &Registry, mut source: S) -> io::Result<()>
+    where
+        S: Source,
+    {
+        registry.deregister(&mut source)?;
+        self.send(source)
+    }
+}
+
+impl<S> Clone for ShardHandle<S> {
+    fn clone(&self) -> ShardHandle<S> {
+        ShardHandle {
+            index: self.index,
+            queue: self.queue.clone(),
+            registry: self.registry.clone(),
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<S> fmt::Debug for ShardHandle<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ShardHandle")
+            .field("index", &self.index)
+            .field("load", &self.load())
+            .finish()
+    }
+}
+
+impl<S> Shard<S> {
+    /// Returns the index of this shard.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns the handles of all shards, indexed by the shard's index.
+    pub fn handles(&self) -> &[ShardHandle<S>] {
+        &self.handles
+    }
+}
+
+impl<S> Clone for Shard<S> {
+    fn clone(&self) -> Shard<S> {
+        Shard {
+            index: self.index,
+            handles: self.handles.clone(),
+        }
+    }
+}
+
+impl<S> fmt::Debug for Shard<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Shard").field("index", &self.index).finish()
+    }
+}
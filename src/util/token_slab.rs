@@ -0,0 +1,344 @@
+use std::{fmt, mem};
+
+use crate::Token;
+
+/// Number of bits of a [`Token`] used for the slot index, the remaining (high)
+/// bits are used for the generation.
+#[cfg(target_pointer_width = "64")]
+const INDEX_BITS: usize = 32;
+#[cfg(not(target_pointer_width = "64"))]
+const INDEX_BITS: usize = 20;
+
+const INDEX_MASK: usize = (1 << INDEX_BITS) - 1;
+const MAX_GENERATION: usize = usize::MAX >> INDEX_BITS;
+
+/// Pre-allocated storage for values keyed by generation-tagged [`Token`]s.
+///
+/// A `TokenSlab` hands out a `Token` for each value inserted into it. The
+/// token packs the index of the slot the value is stored in together with a
+/// generation counter for that slot. The generation is incremented each time
+/// a value is removed from its slot, so once a value is removed all tokens
+/// previously handed out for it become stale and will never match the value
+/// later stored in the same slot.
+///
+/// This solves a common problem with reusing tokens (or file descriptors): if
+/// a connection is closed and a new connection immediately takes its place,
+/// events that were already queued for the old connection would otherwise be
+/// dispatched to the new one. With a `TokenSlab` such events are simply
+/// dropped as [`get`] and [`get_mut`] return `None` for stale tokens.
+///
+/// [`get`]: TokenSlab::get
+/// [`get_mut`]: TokenSlab::get_mut
+///
+/// # Notes
+///
+/// On 64 bit platforms the lower 32 bits of a token are used as index, on
+/// other platforms the lower 20 bits. The highest index is never used, so
+/// tokens with that index, e.g. `Token(usize::MAX)`, can safely be used for
+/// other event sources (such as a [`Waker`]) registered with the same
+/// [`Poll`].
+///
+/// The remaining bits are used for the generation, which wraps around once
+/// it reaches its maximum. On 64 bit platforms that's after 2^32 reuses of a
+/// slot, but on 32 bit platforms only 12 bits remain, so the generation wraps
+/// after 4096 reuses of a slot. After wrapping a stale token can match the
+/// value in its slot again, so events for removed values should not be kept
+/// around for that long.
+///
+/// [`Waker`]: crate::Waker
+/// [`Poll`]: crate::Poll
+///
+/// # Examples
+///
+#[cfg_attr(all(feature = "os-poll", feature = "net"), doc = "```")]
+#[cfg_attr(not(all(feature = "os-poll", feature = "net")), doc = "```ignore")]
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use mio::net::{TcpListener, TcpStream};
+/// use mio::util::TokenSlab;
+/// use mio::{Events, Interest, Poll, Token};
+///
+/// const LISTENER: Token = Token(usize::MAX);
+///
+/// let mut poll = Poll::new()?;
+/// let mut events = Events::with_capacity(128);
+/// let mut connections: TokenSlab<TcpStream> = TokenSlab::new();
+///
+/// let mut listener = TcpListener::bind("127.0.0.1:0".parse()?)?;
+/// poll.registry().register(&mut listener, LISTENER, Interest::READABLE)?;
+/// # let _client = std::net::TcpStream::connect(listener.local_addr()?)?;
+///
+/// # let mut accepted = false;
+/// # while !accepted {
+/// poll.poll(&mut events, None)?;
+/// for event in events.iter() {
+///     match event.token() {
+///         LISTENER => {
+///             let (mut connection, _) = listener.accept()?;
+///             let token = connections.vacant_token();
+///             poll.registry().register(&mut connection, token, Interest::READABLE)?;
+///             connections.insert(connection);
+/// #           accepted = true;
+///         }
+///         token => match connections.get_mut(token) {
+///             Some(connection) => {
+///                 // Handle the event for `connection`.
+/// #               let _ = connection;
+///             }
+///             // The connection was removed, the event is stale.
+///             None => {}
+///         },
+///     }
+/// }
+/// # }
+/// # Ok(())
+/// # }
+/// ```
+pub struct TokenSlab<T> {
+    entries: Vec<Entry<T>>,
+    /// Index of the first vacant entry, equal to `entries.len()` if all
+    /// entries are occupied.
+    next_free: usize,
+    len: usize,
+}
+
+enum Entry<T> {
+    Occupied {
+        generation: usize,
+        value: T,
+    },
+    Vacant {
+        generation: usize,
+        /// Index of the next vacant entry.
+        next_free: usize,
+    },
+}
+
+impl<T> TokenSlab<T> {
+    /// Create a new, empty `TokenSlab`.
+    ///
+    /// This doesn't allocate.
+    pub fn new() -> TokenSlab<T> {
+        TokenSlab::with_capacity(0)
+    }
+
+    /// Create a new, empty `TokenSlab` that can hold at least `capacity`
+    /// values without reallocating.
+    pub fn with_capacity(capacity: usize) -> TokenSlab<T> {
+        TokenSlab {
+            entries: Vec::with_capacity(capacity),
+            next_free: 0,
+            len: 0,
+        }
+    }
+
+    /// Returns the number of values the slab can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.entries.capacity()
+    }
+
+    /// Returns the number of values in the slab.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the slab contains no values.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the token that will be returned by the next call to
+    /// [`insert`].
+    ///
+    /// This is useful when a value must be registered with a [`Registry`]
+    /// before it can be inserted into the slab.
+    ///
+    /// [`insert`]: TokenSlab::insert
+    /// [`Registry`]: crate::Registry
+    ///
+    /// # Panics
+    ///
+    /// This panics if the slab is full, see the notes on [`TokenSlab`].
+    pub fn vacant_token(&self) -> Token {
+        let generation = match self.entries.get(self.next_free) {
+            Some(Entry::Vacant { generation, .. }) => *generation,
+            Some(Entry::Occupied { .. }) => unreachable!("`next_free` points to occupied entry"),
+            None => {
+                assert!(self.next_free < INDEX_MASK, "`TokenSlab` is full");
+                0
+            }
+        };
+        to_token(self.next_free, generation)
+    }
+
+    /// Insert `value` into the slab, returning the token for it.
+    ///
+    /// # Panics
+    ///
+    /// This panics if the slab is full, see the notes on [`TokenSlab`].
+    pub fn insert(&mut self, value: T) -> Token {
+        let index = self.next_free;
+        let generation = match self.entries.get_mut(index) {
+            Some(entry) => {
+                let (generation, next_free) = match entry {
+                    Entry::Vacant {
+                        generation,
+                        next_free,
+                    } => (*generation, *next_free),
+                    Entry::Occupied { .. } => {
+                        unreachable!("`next_free` points to occupied entry")
+                    }
+                };
+                *entry = Entry::Occupied { generation, value };
+                self.next_free = next_free;
+                generation
+            }
+            None => {
+                assert!(index < INDEX_MASK, "`TokenSlab` is full");
+                self.entries.push(Entry::Occupied {
+                    generation: 0,
+                    value,
+                });
+                self.next_free = self.entries.len();
+                0
+            }
+        };
+        self.len += 1;
+        to_token(index, generation)
+    }
+
+    /// Returns `true` if `token` refers to a value in the slab.
+    ///
+    /// Returns `false` for stale tokens, i.e. tokens of values that have been
+    /// removed.
+    pub fn contains(&self, token: Token) -> bool {
+        self.get(token).is_some()
+    }
+
+    /// Returns a reference to the value for `token`, or `None` if `token` is
+    /// stale or was not handed out by this slab.
+    pub fn get(&self, token: Token) -> Option<&T> {
+        let (index, generation) = from_token(token);
+        match self.entries.get(index) {
+            Some(Entry::Occupied {
+                generation: g,
+                value,
+            }) if *g == generation => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns a mutable reference to the value for `token`, or `None` if
+    /// `token` is stale or was not handed out by this slab.
+    pub fn get_mut(&mut self, token: Token) -> Option<&mut T> {
+        let (index, generation) = from_token(token);
+        match self.entries.get_mut(index) {
+            Some(Entry::Occupied {
+                generation: g,
+                value,
+            }) if *g == generation => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Remove the value for `token` from the slab, returning it.
+    ///
+    /// After this `token` becomes stale, also if a new value is inserted in the
+    /// same slot. Returns `None` if `token` is already stale or was not handed
+    /// out by this slab.
+    pub fn remove(&mut self, token: Token) -> Option<T> {
+        let (index, generation) = from_token(token);
+        match self.entries.get(index) {
+            Some(Entry::Occupied { generation: g, .. }) if *g == generation => {}
+            _ => return None,
+        }
+
+        let vacant = Entry::Vacant {
+            generation: next_generation(generation),
+            next_free: self.next_free,
+        };
+        match mem::replace(&mut self.entries[index], vacant) {
+            Entry::Occupied { value, .. } => {
+                self.next_free = index;
+                self.len -= 1;
+                Some(value)
+            }
+            Entry::Vacant { .. } => unreachable!(),
+        }
+    }
+
+    /// Returns an iterator over the tokens and values in the slab.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            entries: self.entries.iter().enumerate(),
+        }
+    }
+}
+
+impl<T> Default for TokenSlab<T> {
+    fn default() -> TokenSlab<T> {
+        TokenSlab::new()
+    }
+}
+
+impl<T> fmt::Debug for TokenSlab<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a TokenSlab<T> {
+    type Item = (Token, &'a T);
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// [`TokenSlab`] iterator.
+///
+/// This struct is created by the [`iter`] method on [`TokenSlab`].
+///
+/// [`iter`]: TokenSlab::iter
+pub struct Iter<'a, T> {
+    entries: std::iter::Enumerate<std::slice::Iter<'a, Entry<T>>>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = (Token, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for (index, entry) in &mut self.entries {
+            if let Entry::Occupied { generation, value } = entry {
+                return Some((to_token(index, *generation), value));
+            }
+        }
+        None
+    }
+}
+
+impl<'a, T> fmt::Debug for Iter<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Iter").finish()
+    }
+}
+
+fn to_token(index: usize, generation: usize) -> Token {
+    Token(index | (generation << INDEX_BITS))
+}
+
+fn from_token(token: Token) -> (usize, usize) {
+    (token.0 & INDEX_MASK, token.0 >> INDEX_BITS)
+}
+
+fn next_generation(generation: usize) -> usize {
+    if generation == MAX_GENERATION {
+        0
+    } else {
+        generation + 1
+    }
+}
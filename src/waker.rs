@@ -1,6 +1,9 @@
 use crate::{sys, Registry, Token};
 
 use std::io;
+use std::mem::ManuallyDrop;
+use std::sync::Arc;
+use std::task::{self, RawWaker, RawWakerVTable};
 
 /// Waker allows cross-thread waking of [`Poll`].
 ///
@@ -93,4 +96,76 @@ impl Waker {
     pub fn wake(&self) -> io::Result<()> {
         self.inner.wake()
     }
+
+    /// Convert the `Waker` into a [`std::task::Waker`], so it can be used to
+    /// poll futures.
+    ///
+    /// Waking the returned waker calls [`wake`], errors returned by it are
+    /// ignored.
+    #[cfg_attr(
+        feature = "util",
+        doc = "A minimal executor using this is [`util::block_on`](crate::util::block_on)."
+    )]
+    ///
+    /// [`wake`]: Waker::wake
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(feature = "os-poll", doc = "```")]
+    #[cfg_attr(not(feature = "os-poll"), doc = "```ignore")]
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use std::sync::Arc;
+    ///
+    /// use mio::{Events, Token, Poll, Waker};
+    ///
+    /// const WAKE_TOKEN: Token = Token(10);
+    ///
+    /// let mut poll = Poll::new()?;
+    /// let mut events = Events::with_capacity(2);
+    ///
+    /// let waker = Arc::new(Waker::new(poll.registry(), WAKE_TOKEN)?);
+    /// let task_waker = waker.into_task_waker();
+    ///
+    /// // Usually this is done by a future.
+    /// task_waker.wake_by_ref();
+    ///
+    /// poll.poll(&mut events, None)?;
+    /// assert_eq!(events.iter().next().unwrap().token(), WAKE_TOKEN);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn into_task_waker(self: Arc<Self>) -> task::Waker {
+        // SAFETY: `VTABLE` upholds the contract of `RawWakerVTable`.
+        unsafe { task::Waker::from_raw(raw_waker(self)) }
+    }
+}
+
+// `std::task::Wake` requires a newer Rust version than our MSRV, so we
+// implement the `RawWakerVTable` ourselves. The data pointer is created using
+// `Arc::into_raw`.
+static VTABLE: RawWakerVTable =
+    RawWakerVTable::new(clone_waker, wake_waker, wake_waker_by_ref, drop_waker);
+
+fn raw_waker(waker: Arc<Waker>) -> RawWaker {
+    RawWaker::new(Arc::into_raw(waker) as *const (), &VTABLE)
+}
+
+unsafe fn clone_waker(ptr: *const ()) -> RawWaker {
+    // Don't drop the reference owned by the `RawWaker` we're cloning.
+    let waker = ManuallyDrop::new(Arc::from_raw(ptr as *const Waker));
+    raw_waker(Arc::clone(&waker))
+}
+
+unsafe fn wake_waker(ptr: *const ()) {
+    let waker = Arc::from_raw(ptr as *const Waker);
+    let _ = waker.wake();
+}
+
+unsafe fn wake_waker_by_ref(ptr: *const ()) {
+    let waker = ManuallyDrop::new(Arc::from_raw(ptr as *const Waker));
+    let _ = waker.wake();
+}
+
+unsafe fn drop_waker(ptr: *const ()) {
+    drop(Arc::from_raw(ptr as *const Waker));
 }
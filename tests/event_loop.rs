@@ -0,0 +1,133 @@
+#![cfg(not(target_os = "wasi"))]
+#![cfg(feature = "util")]
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+use mio::event::Event;
+use mio::util::{EventLoop, Handler};
+use mio::Token;
+
+/// Handler recording all callbacks.
+#[derive(Debug, Default)]
+struct Recorder {
+    timeouts: Vec<&'static str>,
+    messages: Vec<u32>,
+    /// Shut down the event loop after this many messages.
+    shutdown_after: Option<usize>,
+}
+
+impl Handler for Recorder {
+    type Timeout = &'static str;
+    type Message = u32;
+
+    fn ready(&mut self, _: &mut EventLoop<Self>, event: &Event) {
+        panic!("unexpected event: {:?}", event);
+    }
+
+    fn timeout(&mut self, event_loop: &mut EventLoop<Self>, token: Token, timeout: &'static str) {
+        assert!(event_loop.get(token).is_none());
+        self.timeouts.push(timeout);
+        if timeout == "shutdown" {
+            event_loop.shutdown();
+        }
+    }
+
+    fn notify(&mut self, event_loop: &mut EventLoop<Self>, _: Token, msg: u32) {
+        self.messages.push(msg);
+        if Some(self.messages.len()) == self.shutdown_after {
+            event_loop.shutdown();
+        }
+    }
+}
+
+#[test]
+fn timeouts() {
+    let mut event_loop = EventLoop::new().unwrap();
+    let token = event_loop.insert(Recorder::default());
+
+    let start = Instant::now();
+    event_loop.timeout(token, Duration::from_millis(100), "shutdown");
+    event_loop.timeout(token, Duration::from_millis(10), "first");
+    let cleared = event_loop.timeout(token, Duration::from_millis(20), "cleared");
+    assert_eq!(event_loop.clear_timeout(cleared), Some("cleared"));
+    assert_eq!(event_loop.clear_timeout(cleared), None);
+
+    event_loop.run().unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(100));
+    assert!(!event_loop.is_running());
+
+    let recorder = event_loop.remove(token).unwrap();
+    assert_eq!(recorder.timeouts, vec!["first", "shutdown"]);
+    assert!(event_loop.is_empty());
+}
+
+#[test]
+fn timeout_never_expires() {
+    let mut event_loop = EventLoop::new().unwrap();
+    let token = event_loop.insert(Recorder::default());
+
+    let timer = event_loop.timeout(token, Duration::MAX, "never");
+    event_loop
+        .run_once(Some(Duration::from_millis(10)))
+        .unwrap();
+    assert!(event_loop.get(token).unwrap().timeouts.is_empty());
+    assert_eq!(event_loop.clear_timeout(timer), Some("never"));
+}
+
+#[test]
+fn timeout_for_removed_handler() {
+    let mut event_loop = EventLoop::new().unwrap();
+    let token = event_loop.insert(Recorder::default());
+    event_loop.timeout(token, Duration::from_millis(0), "removed");
+    let _ = event_loop.remove(token).unwrap();
+
+    // Reuses the slot of the removed handler, but not its token.
+    let token2 = event_loop.insert(Recorder::default());
+    assert_ne!(token, token2);
+    event_loop
+        .run_once(Some(Duration::from_millis(10)))
+        .unwrap();
+    assert!(event_loop.get(token2).unwrap().timeouts.is_empty());
+}
+
+#[test]
+fn messages() {
+    let mut event_loop = EventLoop::new().unwrap();
+    let token = event_loop.insert(Recorder {
+        shutdown_after: Some(3),
+        ..Recorder::default()
+    });
+    let other = event_loop.insert(Recorder::default());
+
+    let sender = event_loop.channel();
+    let handle = thread::spawn(move || {
+        sender.send(token, 1).unwrap();
+        sender.send(other, 10).unwrap();
+        sender.send(Token(12345), 100).unwrap();
+        sender.send(token, 2).unwrap();
+        sender.send(token, 3).unwrap();
+    });
+
+    event_loop.run().unwrap();
+    handle.join().unwrap();
+    assert_eq!(event_loop.get(token).unwrap().messages, vec![1, 2, 3]);
+    assert_eq!(event_loop.get(other).unwrap().messages, vec![10]);
+}
+
+#[test]
+fn shutdown_from_other_thread() {
+    let mut event_loop: EventLoop<Recorder> = EventLoop::new().unwrap();
+    let sender = event_loop.channel();
+    let handle = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(10));
+        sender.shutdown().unwrap();
+    });
+
+    event_loop.run().unwrap();
+    handle.join().unwrap();
+
+    let sender = event_loop.channel();
+    drop(event_loop);
+    assert!(sender.send(Token(0), 1).is_err());
+}
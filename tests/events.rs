@@ -3,11 +3,11 @@
 
 use std::time::Duration;
 
-use mio::net::TcpStream;
-use mio::{event, Token, Waker};
+use mio::net::{TcpStream, UdpSocket};
+use mio::{event, Interest, Token, Waker};
 
 mod util;
-use util::init_with_poll;
+use util::{any_local_address, init_with_poll};
 
 const WAKE_TOKEN: Token = Token(10);
 
@@ -41,3 +41,54 @@ fn events_all() {
     events.clear();
     assert!(events.is_empty());
 }
+
+#[test]
+fn events_post_processing() {
+    let (mut poll, mut events) = init_with_poll();
+
+    // Register multiple sockets using the same token to get multiple events
+    // for it.
+    let mut socket1 = UdpSocket::bind(any_local_address()).unwrap();
+    let mut socket2 = UdpSocket::bind(any_local_address()).unwrap();
+    let mut socket3 = UdpSocket::bind(any_local_address()).unwrap();
+    socket3
+        .send_to(b"hello", socket1.local_addr().unwrap())
+        .unwrap();
+    let registry = poll.registry();
+    registry
+        .register(&mut socket1, Token(1), Interest::READABLE)
+        .unwrap();
+    registry
+        .register(&mut socket2, Token(1), Interest::WRITABLE)
+        .unwrap();
+    registry
+        .register(&mut socket3, Token(2), Interest::WRITABLE)
+        .unwrap();
+
+    poll.poll(&mut events, Some(Duration::from_millis(500)))
+        .unwrap();
+    let mut tokens: Vec<Token> = events.iter().map(|e| e.token()).collect();
+    tokens.sort();
+    assert_eq!(tokens, vec![Token(1), Token(1), Token(2)]);
+
+    events.coalesce();
+    assert_eq!(events.len(), 2);
+    let event = events.iter().find(|e| e.token() == Token(1)).unwrap();
+    assert!(event.is_readable());
+    assert!(event.is_writable());
+
+    events.sort_by_key(|event| std::cmp::Reverse(event.token()));
+    let tokens: Vec<Token> = events.iter().map(|e| e.token()).collect();
+    assert_eq!(tokens, vec![Token(2), Token(1)]);
+
+    events.retain(|event| event.token() == Token(1));
+    assert_eq!(events.len(), 1);
+
+    let mut drain = events.drain();
+    assert_eq!(drain.len(), 1);
+    let event = drain.next().unwrap();
+    assert_eq!(event.token(), Token(1));
+    assert!(drain.next().is_none());
+    drop(drain);
+    assert!(events.is_empty());
+}
@@ -0,0 +1,127 @@
+#![cfg(unix)]
+#![cfg(all(feature = "os-poll", feature = "net"))]
+
+use std::fs::File;
+use std::io::{self, Read};
+use std::net;
+use std::os::unix::io::AsRawFd;
+use std::os::unix::net as unix_net;
+
+use mio::net::{FdReserve, TcpListener, UnixListener};
+use mio::{Interest, Token};
+
+mod util;
+use util::{
+    any_local_address, assert_would_block, expect_events, init_with_poll, temp_file, ExpectEvent,
+};
+
+const RESERVE: Token = Token(0);
+
+// NOTE: this is the only test in this file as it lowers the file descriptor
+// limit of the process.
+#[test]
+fn accept_reserved() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let listener = TcpListener::bind(any_local_address()).unwrap();
+    let path = temp_file("fd_reserve_accept_reserved");
+    let unix_listener = UnixListener::bind(&path).unwrap();
+    // The reserved file descriptor, the lowest one available.
+    let reserved_fd = File::open("/dev/null").unwrap().as_raw_fd();
+    let mut reserve = FdReserve::new().unwrap();
+    assert!(reserve.is_reserved());
+    poll.registry()
+        .register(&mut reserve, RESERVE, Interest::READABLE)
+        .unwrap();
+
+    let mut client = net::TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    let listener2 = TcpListener::bind(any_local_address()).unwrap();
+    let _client2 = net::TcpStream::connect(listener2.local_addr().unwrap()).unwrap();
+    let mut unix_client = unix_net::UnixStream::connect(&path).unwrap();
+
+    // Use up all file descriptors.
+    let mut limit = libc::rlimit {
+        rlim_cur: 0,
+        rlim_max: 0,
+    };
+    assert_eq!(
+        unsafe { libc::getrlimit(libc::RLIMIT_NOFILE, &mut limit) },
+        0
+    );
+    let original = limit.rlim_cur;
+    limit.rlim_cur = 64;
+    assert_eq!(unsafe { libc::setrlimit(libc::RLIMIT_NOFILE, &limit) }, 0);
+    let mut files = Vec::new();
+    loop {
+        match File::open("/dev/null") {
+            Ok(file) => files.push(file),
+            Err(err) => {
+                assert_eq!(err.raw_os_error(), Some(libc::EMFILE));
+                break;
+            }
+        }
+    }
+
+    // `accept_many` returns the error along with the accepted connections.
+    let mut streams = Vec::new();
+    let (accepted, err) = listener.accept_many(&mut streams, 10);
+    assert_eq!(accepted, 0);
+    assert_eq!(err.unwrap().raw_os_error(), Some(libc::EMFILE));
+
+    // The connections are shed.
+    let err = listener.accept_reserved(&mut reserve).unwrap_err();
+    assert_eq!(err.raw_os_error(), Some(libc::EMFILE));
+    assert_eq!(reserve.shed_count(), 1);
+    assert!(reserve.is_reserved());
+    assert_would_block(listener.accept_reserved(&mut reserve));
+
+    let err = unix_listener.accept_reserved(&mut reserve).unwrap_err();
+    assert_eq!(err.raw_os_error(), Some(libc::EMFILE));
+    assert_eq!(reserve.shed_count(), 2);
+    assert_would_block(unix_listener.accept_reserved(&mut reserve));
+
+    // Without the reserved file descriptor connections can't be shed, which
+    // is reported as an event.
+    limit.rlim_cur = reserved_fd as libc::rlim_t;
+    assert_eq!(unsafe { libc::setrlimit(libc::RLIMIT_NOFILE, &limit) }, 0);
+    let err = listener2.accept_reserved(&mut reserve).unwrap_err();
+    assert_eq!(err.raw_os_error(), Some(libc::EMFILE));
+    assert!(!reserve.is_reserved());
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(RESERVE, Interest::READABLE)],
+    );
+    // Returns the error without accepting.
+    let err = listener2.accept_reserved(&mut reserve).unwrap_err();
+    assert_eq!(err.raw_os_error(), Some(libc::EMFILE));
+    assert_eq!(reserve.shed_count(), 2);
+    assert!(!reserve.reserve());
+
+    // Once a file descriptor is available it's reserved again.
+    limit.rlim_cur = 64;
+    assert_eq!(unsafe { libc::setrlimit(libc::RLIMIT_NOFILE, &limit) }, 0);
+    assert!(reserve.reserve());
+    assert!(reserve.is_reserved());
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(RESERVE, Interest::READABLE)],
+    );
+
+    drop(files);
+    limit.rlim_cur = original;
+    assert_eq!(unsafe { libc::setrlimit(libc::RLIMIT_NOFILE, &limit) }, 0);
+
+    let mut buf = [0; 8];
+    match client.read(&mut buf) {
+        Ok(0) => {}
+        Err(ref err) if err.kind() == io::ErrorKind::ConnectionReset => {}
+        res => panic!("unexpected result: {:?}", res),
+    }
+    assert_eq!(unix_client.read(&mut buf).unwrap(), 0);
+
+    // Accepting works again.
+    let _client = net::TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    listener.accept_reserved(&mut reserve).unwrap();
+}
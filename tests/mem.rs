@@ -0,0 +1,211 @@
+use std::io::{self, Read, Write};
+use std::sync::mpsc::TryRecvError;
+use std::thread;
+use std::time::Duration;
+
+use mio::mem::{channel, pipe};
+use mio::{Events, Interest, Poll, Token};
+
+const ID1: Token = Token(0);
+const ID2: Token = Token(1);
+const TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Poll once, returning the tokens of the events and whether they are
+/// readable, writable, read closed and write closed.
+fn poll_events(poll: &mut Poll, events: &mut Events) -> Vec<(Token, bool, bool, bool, bool)> {
+    poll.poll(events, Some(TIMEOUT)).unwrap();
+    events
+        .iter()
+        .map(|e| {
+            (
+                e.token(),
+                e.is_readable(),
+                e.is_writable(),
+                e.is_read_closed(),
+                e.is_write_closed(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn poll_timeout() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(8);
+    poll.poll(&mut events, Some(TIMEOUT)).unwrap();
+    assert!(events.is_empty());
+}
+
+#[test]
+fn pipe() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(8);
+
+    let (mut sender, mut receiver) = pipe::new().unwrap();
+    poll.registry()
+        .register(&mut receiver, ID1, Interest::READABLE)
+        .unwrap();
+    poll.registry()
+        .register(&mut sender, ID2, Interest::WRITABLE)
+        .unwrap();
+
+    // The sender is writable right away.
+    assert_eq!(
+        poll_events(&mut poll, &mut events),
+        vec![(ID2, false, true, false, false)]
+    );
+    assert!(poll_events(&mut poll, &mut events).is_empty());
+
+    let mut buf = [0; 16];
+    assert_eq!(
+        receiver.read(&mut buf).unwrap_err().kind(),
+        io::ErrorKind::WouldBlock
+    );
+
+    sender.write_all(b"Hello").unwrap();
+    assert_eq!(
+        poll_events(&mut poll, &mut events),
+        vec![(ID1, true, false, false, false)]
+    );
+    assert_eq!(receiver.read(&mut buf).unwrap(), 5);
+    assert_eq!(&buf[..5], b"Hello");
+
+    drop(sender);
+    assert_eq!(
+        poll_events(&mut poll, &mut events),
+        vec![(ID1, true, false, true, false)]
+    );
+    assert_eq!(receiver.read(&mut buf).unwrap(), 0);
+}
+
+#[test]
+fn pipe_full() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(8);
+
+    let (mut sender, mut receiver) = pipe::new().unwrap();
+    poll.registry()
+        .register(&mut sender, ID1, Interest::WRITABLE)
+        .unwrap();
+    assert_eq!(poll_events(&mut poll, &mut events).len(), 1);
+
+    let data = [1; 4096];
+    loop {
+        match sender.write(&data) {
+            Ok(_) => continue,
+            Err(ref err) if err.kind() == io::ErrorKind::WouldBlock => break,
+            Err(err) => panic!("unexpected error: {}", err),
+        }
+    }
+    assert!(poll_events(&mut poll, &mut events).is_empty());
+
+    let mut buf = [0; 16];
+    receiver.read_exact(&mut buf).unwrap();
+    assert_eq!(
+        poll_events(&mut poll, &mut events),
+        vec![(ID1, false, true, false, false)]
+    );
+
+    drop(receiver);
+    assert_eq!(
+        poll_events(&mut poll, &mut events),
+        vec![(ID1, false, true, false, true)]
+    );
+    assert_eq!(
+        sender.write(&data).unwrap_err().kind(),
+        io::ErrorKind::BrokenPipe
+    );
+}
+
+#[test]
+fn pipe_register_twice() {
+    let poll = Poll::new().unwrap();
+    let (_sender, mut receiver) = pipe::new().unwrap();
+
+    poll.registry()
+        .register(&mut receiver, ID1, Interest::READABLE)
+        .unwrap();
+    let err = poll
+        .registry()
+        .register(&mut receiver, ID1, Interest::READABLE)
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+
+    poll.registry().deregister(&mut receiver).unwrap();
+    let err = poll.registry().deregister(&mut receiver).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+}
+
+#[test]
+fn channel() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(8);
+
+    let (sender, mut receiver) = channel::new();
+    sender.send(1).unwrap();
+
+    // Values sent before registering are reported once registered.
+    poll.registry()
+        .register(&mut receiver, ID1, Interest::READABLE)
+        .unwrap();
+    assert_eq!(
+        poll_events(&mut poll, &mut events),
+        vec![(ID1, true, false, false, false)]
+    );
+    assert_eq!(receiver.try_recv(), Ok(1));
+    assert_eq!(receiver.try_recv(), Err(TryRecvError::Empty));
+
+    let sender2 = sender.clone();
+    let handle = thread::spawn(move || {
+        sender2.send(2).unwrap();
+    });
+    poll.poll(&mut events, None).unwrap();
+    assert_eq!(events.iter().next().unwrap().token(), ID1);
+    assert_eq!(receiver.try_recv(), Ok(2));
+    handle.join().unwrap();
+
+    drop(sender);
+    assert_eq!(
+        poll_events(&mut poll, &mut events),
+        vec![(ID1, true, false, true, false)]
+    );
+    assert_eq!(receiver.try_recv(), Err(TryRecvError::Disconnected));
+
+    // Re-registering after deregistering.
+    poll.registry().deregister(&mut receiver).unwrap();
+    poll.registry()
+        .register(&mut receiver, ID2, Interest::READABLE)
+        .unwrap();
+    assert_eq!(
+        poll_events(&mut poll, &mut events),
+        vec![(ID2, true, false, true, false)]
+    );
+}
+
+#[test]
+fn waker() {
+    use mio::Waker;
+    use std::sync::Arc;
+
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(8);
+
+    let waker = Arc::new(Waker::new(poll.registry(), ID1).unwrap());
+    let waker2 = waker.clone();
+    let handle = thread::spawn(move || waker2.wake().unwrap());
+
+    poll.poll(&mut events, None).unwrap();
+    assert_eq!(
+        events.iter().map(|e| e.token()).collect::<Vec<_>>(),
+        vec![ID1]
+    );
+    handle.join().unwrap();
+
+    // Multiple wake ups are coalesced.
+    waker.wake().unwrap();
+    waker.wake().unwrap();
+    assert_eq!(
+        poll_events(&mut poll, &mut events),
+        vec![(ID1, true, false, false, false)]
+    );
+}
@@ -4,14 +4,16 @@
 use std::net;
 use std::sync::{Arc, Barrier};
 use std::thread::{self, sleep};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use std::{fmt, io};
 
 use mio::event::Source;
 use mio::net::{TcpListener, TcpStream, UdpSocket};
-use mio::{event, Events, Interest, Poll, Registry, Token};
+use mio::{event, Events, Interest, Poll, Registry, Token, Waker};
 
 mod util;
+#[cfg(unix)]
+use util::expect_no_events;
 use util::{
     any_local_address, assert_send, assert_sync, expect_events, init, init_with_poll, ExpectEvent,
 };
@@ -96,6 +98,108 @@ fn zero_duration_polls_events() {
     drop(listener);
 }
 
+#[test]
+fn poll_until() {
+    init();
+
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(2);
+
+    let start = Instant::now();
+    let deadline = start + Duration::from_millis(50);
+    // Loop to handle spurious wakeups.
+    while !events.timed_out() {
+        poll.poll_until(&mut events, deadline).unwrap();
+    }
+    assert!(events.is_empty());
+    assert!(!events.is_full());
+    assert!(Instant::now() >= deadline);
+
+    // A deadline in the past must not block.
+    let start = Instant::now();
+    poll.poll_until(&mut events, deadline).unwrap();
+    assert!(events.timed_out());
+    assert!(start.elapsed() < Duration::from_millis(50));
+
+    let listener = net::TcpListener::bind(any_local_address()).unwrap();
+    let addr = listener.local_addr().unwrap();
+    let streams: Vec<TcpStream> = (0..3)
+        .map(|n| {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            poll.registry()
+                .register(&mut stream, Token(n), Interest::WRITABLE)
+                .unwrap();
+            stream
+        })
+        .collect();
+    sleep(Duration::from_millis(10));
+
+    poll.poll_until(&mut events, Instant::now() + Duration::from_secs(1))
+        .unwrap();
+    assert!(events.is_full());
+    assert!(!events.timed_out());
+
+    events.clear();
+    assert!(!events.timed_out());
+    assert!(!events.is_full());
+
+    drop(streams);
+    drop(listener);
+}
+
+#[test]
+fn busy_poll() {
+    init();
+
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(16);
+    poll.set_busy_poll(Some(Duration::from_millis(10))).unwrap();
+
+    // Times out while spinning.
+    let start = Instant::now();
+    loop {
+        poll.poll(&mut events, Some(Duration::from_millis(5)))
+            .unwrap();
+        if events.timed_out() {
+            break;
+        }
+    }
+    assert!(start.elapsed() >= Duration::from_millis(5));
+
+    // Blocks after spinning.
+    let start = Instant::now();
+    loop {
+        poll.poll(&mut events, Some(Duration::from_millis(30)))
+            .unwrap();
+        if events.timed_out() {
+            break;
+        }
+    }
+    assert!(start.elapsed() >= Duration::from_millis(30));
+
+    // Receives events while spinning and while blocking.
+    let waker = Arc::new(Waker::new(poll.registry(), ID1).unwrap());
+    for delay in [1, 20].iter() {
+        let waker = waker.clone();
+        let delay = Duration::from_millis(*delay);
+        let handle = thread::spawn(move || {
+            sleep(delay);
+            waker.wake().unwrap();
+        });
+        expect_events(
+            &mut poll,
+            &mut events,
+            vec![ExpectEvent::new(ID1, Interest::READABLE)],
+        );
+        handle.join().unwrap();
+    }
+
+    poll.set_busy_poll(None).unwrap();
+    poll.poll(&mut events, Some(Duration::from_millis(0)))
+        .unwrap();
+    assert!(events.is_empty());
+}
+
 #[test]
 fn poll_closes_fd() {
     init();
@@ -130,7 +234,7 @@ fn drop_cancels_interest_and_shuts_down() {
             .set_read_timeout(Some(Duration::from_secs(5)))
             .expect("set_read_timeout");
         match stream.read(&mut [0; 16]) {
-            Ok(_) => (),
+            Ok(_n) => (),
             Err(err) => {
                 if err.kind() != io::ErrorKind::UnexpectedEof {
                     panic!("{}", err);
@@ -581,7 +685,7 @@ fn poll_registration() {
     let interests = Interest::READABLE;
     registry.register(&mut source, token, interests).unwrap();
     assert_eq!(source.registrations.len(), 1);
-    assert_eq!(source.registrations.get(0), Some(&(token, interests)));
+    assert_eq!(source.registrations.first(), Some(&(token, interests)));
     assert!(source.reregistrations.is_empty());
     assert_eq!(source.deregister_count, 0);
 
@@ -593,7 +697,7 @@ fn poll_registration() {
     assert_eq!(source.registrations.len(), 1);
     assert_eq!(source.reregistrations.len(), 1);
     assert_eq!(
-        source.reregistrations.get(0),
+        source.reregistrations.first(),
         Some(&(re_token, re_interests))
     );
     assert_eq!(source.deregister_count, 0);
@@ -606,6 +710,8 @@ fn poll_registration() {
 
 struct ErroneousTestEventSource;
 
+// `io::Error::other` requires Rust 1.74, newer than our MSRV.
+#[allow(clippy::io_other_error)]
 impl event::Source for ErroneousTestEventSource {
     fn register(
         &mut self,
@@ -647,6 +753,52 @@ fn poll_erroneous_registration() {
     assert_error(registry.deregister(&mut source), "deregister");
 }
 
+#[test]
+#[cfg(unix)]
+fn nested_poll() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let mut inner = Poll::new().unwrap();
+    let mut inner_events = Events::with_capacity(8);
+    let waker = Waker::new(inner.registry(), ID2).unwrap();
+
+    assert_error(
+        poll.registry()
+            .register(&mut inner, ID1, Interest::WRITABLE),
+        "`Poll` can only be registered with readable interest",
+    );
+    poll.registry()
+        .register(&mut inner, ID1, Interest::READABLE)
+        .unwrap();
+    expect_no_events(&mut poll, &mut events);
+
+    waker.wake().unwrap();
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(ID1, Interest::READABLE)],
+    );
+    inner
+        .poll(&mut inner_events, Some(Duration::from_millis(0)))
+        .unwrap();
+    assert_eq!(inner_events.iter().count(), 1);
+    assert_eq!(inner_events.iter().next().unwrap().token(), ID2);
+
+    poll.registry()
+        .reregister(&mut inner, ID3, Interest::READABLE)
+        .unwrap();
+    waker.wake().unwrap();
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(ID3, Interest::READABLE)],
+    );
+
+    poll.registry().deregister(&mut inner).unwrap();
+    waker.wake().unwrap();
+    expect_no_events(&mut poll, &mut events);
+}
+
 /// Assert that `result` is an error and the formatted error (via
 /// `fmt::Display`) equals `expected_msg`.
 pub fn assert_error<T, E: fmt::Display>(result: Result<T, E>, expected_msg: &str) {
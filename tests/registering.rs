@@ -171,7 +171,10 @@ fn tcp_register_multiple_event_loops() {
         Token(0),
         Interest::READABLE | Interest::WRITABLE,
     );
-    assert_error(res, "I/O source already registered with a `Registry`");
+    assert_error(
+        res,
+        "I/O source already registered with a different `Registry`",
+    );
 
     // Try the stream
     let mut stream = TcpStream::connect(addr).unwrap();
@@ -190,7 +193,10 @@ fn tcp_register_multiple_event_loops() {
         Token(1),
         Interest::READABLE | Interest::WRITABLE,
     );
-    assert_error(res, "I/O source already registered with a `Registry`");
+    assert_error(
+        res,
+        "I/O source already registered with a different `Registry`",
+    );
 }
 
 #[test]
@@ -218,7 +224,21 @@ fn udp_register_multiple_event_loops() {
         Token(0),
         Interest::READABLE | Interest::WRITABLE,
     );
-    assert_error(res, "I/O source already registered with a `Registry`");
+    #[cfg(unix)]
+    {
+        let err = res.as_ref().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        let err = err
+            .get_ref()
+            .and_then(|err| err.downcast_ref::<mio::RegistrationError>())
+            .unwrap();
+        assert_eq!(err.fd(), std::os::unix::io::AsRawFd::as_raw_fd(&socket));
+        assert_eq!(err.token(), Some(Token(0)));
+    }
+    assert_error(
+        res,
+        "I/O source already registered with a different `Registry`",
+    );
 }
 
 #[test]
@@ -244,3 +264,355 @@ fn registering_after_deregistering() {
         .unwrap();
     assert!(events.is_empty());
 }
+
+#[test]
+#[cfg(all(unix, feature = "registry-introspection"))]
+fn registry_introspection() {
+    use std::os::unix::io::AsRawFd;
+
+    init();
+
+    let poll = Poll::new().unwrap();
+    let registry = poll.registry().try_clone().unwrap();
+    assert!(registry.registrations().is_empty());
+
+    let mut server = TcpListener::bind(any_local_address()).unwrap();
+    let mut client = TcpStream::connect(server.local_addr().unwrap()).unwrap();
+    registry
+        .register(&mut server, SERVER, Interest::READABLE)
+        .unwrap();
+    poll.registry()
+        .register(&mut client, CLIENT, Interest::WRITABLE)
+        .unwrap();
+
+    // Registrations are shared between all clones of a `Registry`.
+    let mut registrations = poll.registry().registrations();
+    registrations.sort_by_key(|r| r.token());
+    assert_eq!(registrations.len(), 2);
+    assert_eq!(registrations[0].fd(), server.as_raw_fd());
+    assert_eq!(registrations[0].token(), SERVER);
+    assert_eq!(registrations[0].interests(), Interest::READABLE);
+    assert_eq!(registrations[1].fd(), client.as_raw_fd());
+    assert_eq!(registrations[1].token(), CLIENT);
+    assert_eq!(registrations[1].interests(), Interest::WRITABLE);
+    assert!(format!("{:?}", registry).contains("registrations"));
+
+    poll.registry()
+        .reregister(&mut client, CLIENT, Interest::READABLE)
+        .unwrap();
+    let registration = registry
+        .registrations()
+        .into_iter()
+        .find(|r| r.fd() == client.as_raw_fd())
+        .unwrap();
+    assert_eq!(registration.interests(), Interest::READABLE);
+
+    registry.deregister(&mut client).unwrap();
+    assert!(!registry.is_registered(client.as_raw_fd()));
+    assert!(poll.registry().is_registered(server.as_raw_fd()));
+
+    // Closing a registered source removes it from the selector, which should
+    // be reflected in the bookkeeping.
+    let fd = server.as_raw_fd();
+    drop(server);
+    assert!(!registry.is_registered(fd));
+    assert!(registry.registrations().is_empty());
+}
+
+#[test]
+#[cfg(all(
+    any(target_os = "android", target_os = "illumos", target_os = "linux"),
+    feature = "os-ext",
+    feature = "registry-introspection"
+))]
+fn registry_introspection_double_registration() {
+    use mio::unix::SourceFd;
+    use mio::RegistrationError;
+    use std::error::Error;
+    use std::os::unix::io::AsRawFd;
+
+    init();
+
+    let poll = Poll::new().unwrap();
+    let listener = std::net::TcpListener::bind(any_local_address()).unwrap();
+    let fd = listener.as_raw_fd();
+
+    poll.registry()
+        .register(&mut SourceFd(&fd), SERVER, Interest::READABLE)
+        .unwrap();
+    let err = poll
+        .registry()
+        .register(&mut SourceFd(&fd), CLIENT, Interest::READABLE)
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+    let msg = format!(
+        "failed to register file descriptor {} (token: Token(1), interests: READABLE): already registered with Token(0)",
+        fd
+    );
+    assert!(err.to_string().contains(&msg), "unexpected error: {}", err);
+    let context = err
+        .get_ref()
+        .and_then(|err| err.downcast_ref::<RegistrationError>())
+        .unwrap();
+    assert_eq!(context.fd(), fd);
+    assert_eq!(context.token(), Some(CLIENT));
+    assert_eq!(context.interests(), Some(Interest::READABLE));
+    let source = context.source().unwrap();
+    let source = source.downcast_ref::<io::Error>().unwrap();
+    assert_eq!(source.raw_os_error(), Some(libc::EEXIST));
+}
+
+#[test]
+#[cfg(all(
+    any(target_os = "android", target_os = "illumos", target_os = "linux"),
+    feature = "os-ext"
+))]
+fn registration_error_keeps_os_error() {
+    use mio::unix::SourceFd;
+    use std::os::unix::io::AsRawFd;
+
+    init();
+
+    let poll = Poll::new().unwrap();
+    let listener = std::net::TcpListener::bind(any_local_address()).unwrap();
+    let fd = listener.as_raw_fd();
+
+    let err = poll
+        .registry()
+        .reregister(&mut SourceFd(&fd), SERVER, Interest::WRITABLE)
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    assert_eq!(err.raw_os_error(), Some(libc::ENOENT));
+
+    let err = poll.registry().deregister(&mut SourceFd(&fd)).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    assert_eq!(err.raw_os_error(), Some(libc::ENOENT));
+
+    // Without the bookkeeping the OS error is returned as is.
+    #[cfg(not(feature = "registry-introspection"))]
+    {
+        poll.registry()
+            .register(&mut SourceFd(&fd), SERVER, Interest::READABLE)
+            .unwrap();
+        let err = poll
+            .registry()
+            .register(&mut SourceFd(&fd), CLIENT, Interest::READABLE)
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+        assert_eq!(err.raw_os_error(), Some(libc::EEXIST));
+    }
+}
+
+#[test]
+#[cfg(debug_assertions)] // Check is only present when debug assertions are enabled.
+fn cross_registry_error_kinds() {
+    init();
+
+    let poll1 = Poll::new().unwrap();
+    let poll2 = Poll::new().unwrap();
+    let mut listener = TcpListener::bind(any_local_address()).unwrap();
+
+    poll1
+        .registry()
+        .register(&mut listener, SERVER, Interest::READABLE)
+        .unwrap();
+
+    let err = poll1
+        .registry()
+        .register(&mut listener, SERVER, Interest::READABLE)
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+
+    for res in [
+        poll2
+            .registry()
+            .register(&mut listener, SERVER, Interest::READABLE),
+        poll2
+            .registry()
+            .reregister(&mut listener, SERVER, Interest::READABLE),
+        poll2.registry().deregister(&mut listener),
+    ]
+    .iter()
+    {
+        assert_eq!(
+            res.as_ref().unwrap_err().kind(),
+            io::ErrorKind::InvalidInput
+        );
+    }
+
+    // The failed attempts must not change the registration.
+    poll1.registry().deregister(&mut listener).unwrap();
+    let err = poll1.registry().deregister(&mut listener).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+}
+
+#[test]
+#[cfg(all(unix, debug_assertions, feature = "drain-check"))]
+fn undrained_sources() {
+    use std::io::Read;
+    use std::os::unix::io::IntoRawFd;
+
+    init();
+
+    const UNDRAINED: &str = "wasn't drained";
+
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(8);
+
+    let server = std::net::TcpListener::bind(any_local_address()).unwrap();
+    let addr = server.local_addr().unwrap();
+    let connect = |poll: &Poll, token, interests| {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        poll.registry()
+            .register(&mut stream, token, interests)
+            .unwrap();
+        let (peer, _) = server.accept().unwrap();
+        (stream, peer)
+    };
+    let expect_readable = |poll: &mut Poll, events: &mut Events, token| loop {
+        poll.poll(events, Some(Duration::from_millis(500))).unwrap();
+        if events.iter().any(|e| e.token() == token && e.is_readable()) {
+            break;
+        }
+    };
+    let mut buf = [0; 8];
+
+    // A read that fills the buffer doesn't drain the source.
+    let (mut stream, mut peer) = connect(&poll, CLIENT, Interest::READABLE);
+    peer.write_all(&[1; 32]).unwrap();
+    expect_readable(&mut poll, &mut events, CLIENT);
+    assert_eq!(stream.read(&mut buf).unwrap(), buf.len());
+    let logs = util::capture_logs(|| {
+        poll.poll(&mut events, Some(Duration::from_millis(10)))
+            .unwrap()
+    });
+    assert!(logs.iter().any(|l| l.contains(UNDRAINED)), "{:?}", logs);
+
+    // Reading until `WouldBlock` drains it.
+    peer.write_all(&[1; 32]).unwrap();
+    expect_readable(&mut poll, &mut events, CLIENT);
+    while stream.read(&mut buf).is_ok() {}
+    let logs = util::capture_logs(|| {
+        poll.poll(&mut events, Some(Duration::from_millis(10)))
+            .unwrap()
+    });
+    assert!(logs.is_empty(), "{:?}", logs);
+
+    // So does a short read.
+    peer.write_all(&[1; 4]).unwrap();
+    expect_readable(&mut poll, &mut events, CLIENT);
+    assert_eq!(stream.read(&mut buf).unwrap(), 4);
+    let logs = util::capture_logs(|| {
+        poll.poll(&mut events, Some(Duration::from_millis(10)))
+            .unwrap()
+    });
+    assert!(logs.is_empty(), "{:?}", logs);
+
+    // And reaching the end of the stream.
+    drop(peer);
+    expect_readable(&mut poll, &mut events, CLIENT);
+    assert_eq!(stream.read(&mut buf).unwrap(), 0);
+    let logs = util::capture_logs(|| {
+        poll.poll(&mut events, Some(Duration::from_millis(10)))
+            .unwrap()
+    });
+    assert!(logs.is_empty(), "{:?}", logs);
+
+    // Dropping a registered source is fine, closing it removes it from the
+    // selector.
+    let logs = util::capture_logs(|| drop(stream));
+    assert!(logs.is_empty(), "{:?}", logs);
+
+    // A complete write without hitting `WouldBlock` is fine.
+    let (mut stream, _peer) = connect(&poll, SERVER, Interest::WRITABLE);
+    loop {
+        poll.poll(&mut events, Some(Duration::from_millis(500)))
+            .unwrap();
+        if events
+            .iter()
+            .any(|e| e.token() == SERVER && e.is_writable())
+        {
+            break;
+        }
+    }
+    stream.write_all(b"hello").unwrap();
+    let logs = util::capture_logs(|| {
+        poll.poll(&mut events, Some(Duration::from_millis(10)))
+            .unwrap()
+    });
+    assert!(logs.is_empty(), "{:?}", logs);
+
+    // Neither are deregistered or taken out sources.
+    poll.registry().deregister(&mut stream).unwrap();
+    let (stream2, _peer2) = connect(&poll, CLIENT, Interest::READABLE);
+    let logs = util::capture_logs(|| {
+        drop(stream);
+        let fd = stream2.into_raw_fd();
+        drop(unsafe { <std::net::TcpStream as std::os::unix::io::FromRawFd>::from_raw_fd(fd) });
+    });
+    assert!(logs.is_empty(), "{:?}", logs);
+}
+
+#[test]
+fn transfer() {
+    init();
+
+    let mut poll1 = Poll::new().unwrap();
+    let mut poll2 = Poll::new().unwrap();
+    let mut events = Events::with_capacity(8);
+
+    let server = TcpListener::bind(any_local_address()).unwrap();
+    let mut client = TcpStream::connect(server.local_addr().unwrap()).unwrap();
+    let (mut stream, _) = server.accept().unwrap();
+    poll1
+        .registry()
+        .register(&mut stream, SERVER, Interest::READABLE)
+        .unwrap();
+
+    // Receive the readable event, but don't read the data.
+    client.write_all(b"hello").unwrap();
+    poll1
+        .poll(&mut events, Some(Duration::from_millis(500)))
+        .unwrap();
+    assert!(events
+        .iter()
+        .any(|e| e.token() == SERVER && e.is_readable()));
+
+    poll1
+        .registry()
+        .transfer(&mut stream, poll2.registry(), CLIENT, Interest::READABLE)
+        .unwrap();
+
+    // With edge-triggered events the readiness must not be lost.
+    poll2
+        .poll(&mut events, Some(Duration::from_millis(500)))
+        .unwrap();
+    assert!(events
+        .iter()
+        .any(|e| e.token() == CLIENT && e.is_readable()));
+    client.write_all(b"world").unwrap();
+    poll1
+        .poll(&mut events, Some(Duration::from_millis(100)))
+        .unwrap();
+    assert!(events.is_empty());
+
+    // The source is now associated with `poll2`.
+    #[cfg(debug_assertions)]
+    assert_error(
+        poll1.registry().deregister(&mut stream),
+        "I/O source already registered with a different `Registry`",
+    );
+    #[cfg(all(unix, feature = "registry-introspection"))]
+    {
+        use std::os::unix::io::AsRawFd;
+        assert!(!poll1.registry().is_registered(stream.as_raw_fd()));
+        assert!(poll2.registry().is_registered(stream.as_raw_fd()));
+    }
+    poll2.registry().deregister(&mut stream).unwrap();
+
+    // Transferring an unregistered source fails.
+    let res = poll1
+        .registry()
+        .transfer(&mut stream, poll2.registry(), CLIENT, Interest::READABLE);
+    assert!(res.is_err());
+}
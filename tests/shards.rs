@@ -0,0 +1,189 @@
+#![cfg(not(target_os = "wasi"))]
+#![cfg(all(feature = "os-poll", feature = "net", feature = "util"))]
+
+use std::io::{self, Read, Write};
+use std::net;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use mio::event::Event;
+use mio::net::TcpStream;
+use mio::util::{Balance, EventLoop, Handler, Shard, Shards};
+use mio::Interest;
+
+mod util;
+use util::{any_local_address, assert_send, assert_sync, init};
+
+/// Echoes everything it reads.
+struct Echo(TcpStream);
+
+impl Handler for Echo {
+    type Timeout = ();
+    type Message = ();
+
+    fn ready(&mut self, event_loop: &mut EventLoop<Self>, event: &Event) {
+        let mut buf = [0; 64];
+        loop {
+            match self.0.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => self.0.write_all(&buf[..n]).unwrap(),
+                Err(ref err) if err.kind() == io::ErrorKind::WouldBlock => return,
+                Err(err) => panic!("unexpected error: {}", err),
+            }
+        }
+        event_loop.registry().deregister(&mut self.0).unwrap();
+        let _ = event_loop.remove(event.token());
+    }
+}
+
+/// Records the index of the shard each stream was added to.
+type Added = Arc<Mutex<Vec<usize>>>;
+
+fn add_echo(
+    event_loop: &mut EventLoop<Echo>,
+    shard: &Shard<TcpStream>,
+    stream: TcpStream,
+    added: &Added,
+) {
+    added.lock().unwrap().push(shard.index());
+    let mut stream = stream;
+    event_loop
+        .insert_with(|registry, token| {
+            registry.register(&mut stream, token, Interest::READABLE)?;
+            Ok(Echo(stream))
+        })
+        .unwrap();
+}
+
+/// Returns a connected (client, server) pair.
+fn connect(listener: &net::TcpListener) -> (net::TcpStream, TcpStream) {
+    let client = net::TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    client
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+    let (server, _) = listener.accept().unwrap();
+    server.set_nonblocking(true).unwrap();
+    (client, TcpStream::from_std(server))
+}
+
+fn expect_echo(client: &mut net::TcpStream) {
+    client.write_all(b"hello").unwrap();
+    let mut buf = [0; 5];
+    client.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"hello");
+}
+
+#[test]
+fn is_send_and_sync() {
+    assert_send::<Shards<TcpStream>>();
+    assert_sync::<Shards<TcpStream>>();
+    assert_send::<mio::util::ShardHandle<TcpStream>>();
+    assert_sync::<mio::util::ShardHandle<TcpStream>>();
+}
+
+#[test]
+fn round_robin() {
+    init();
+    let added = Added::default();
+    let added2 = added.clone();
+    let mut shards = Shards::new(3, Balance::RoundRobin, move |event_loop, shard, stream| {
+        add_echo(event_loop, shard, stream, &added2)
+    })
+    .unwrap();
+    assert_eq!(shards.len(), 3);
+
+    let listener = net::TcpListener::bind(any_local_address()).unwrap();
+    let mut clients = Vec::new();
+    for expected in &[0, 1, 2, 0] {
+        let (client, server) = connect(&listener);
+        assert_eq!(shards.dispatch(server).unwrap(), *expected);
+        clients.push(client);
+    }
+    for client in &mut clients {
+        expect_echo(client);
+    }
+
+    let mut added = added.lock().unwrap().clone();
+    added.sort_unstable();
+    assert_eq!(added, vec![0, 0, 1, 2]);
+    shards.shutdown().unwrap();
+}
+
+#[test]
+fn least_loaded() {
+    init();
+    let added = Added::default();
+    let added2 = added.clone();
+    let mut shards = Shards::new(2, Balance::LeastLoaded, move |event_loop, shard, stream| {
+        add_echo(event_loop, shard, stream, &added2)
+    })
+    .unwrap();
+
+    let listener = net::TcpListener::bind(any_local_address()).unwrap();
+    let (mut client1, server) = connect(&listener);
+    assert_eq!(shards.dispatch(server).unwrap(), 0);
+    let (mut client2, server) = connect(&listener);
+    assert_eq!(shards.dispatch(server).unwrap(), 1);
+    expect_echo(&mut client1);
+    expect_echo(&mut client2);
+    assert_eq!(shards.handles()[0].load(), 1);
+
+    // Closing the connection removes the handler from the first shard.
+    drop(client1);
+    while shards.handles()[0].load() != 0 {
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    let (mut client3, server) = connect(&listener);
+    assert_eq!(shards.dispatch(server).unwrap(), 0);
+    expect_echo(&mut client3);
+    shards.shutdown().unwrap();
+}
+
+#[test]
+fn migrate() {
+    init();
+    let added = Added::default();
+    let added2 = added.clone();
+    let mut shards = Shards::new(
+        2,
+        Balance::RoundRobin,
+        move |event_loop, shard, mut stream| {
+            if shard.index() == 0 {
+                // Register with this shard first, then move it to the other one.
+                added2.lock().unwrap().push(shard.index());
+                let registry = event_loop.registry();
+                registry
+                    .register(&mut stream, mio::Token(0), Interest::READABLE)
+                    .unwrap();
+                shard.handles()[1].migrate(registry, stream).unwrap();
+            } else {
+                add_echo(event_loop, shard, stream, &added2)
+            }
+        },
+    )
+    .unwrap();
+
+    let listener = net::TcpListener::bind(any_local_address()).unwrap();
+    let (mut client, server) = connect(&listener);
+    assert_eq!(shards.dispatch(server).unwrap(), 0);
+    expect_echo(&mut client);
+    assert_eq!(*added.lock().unwrap(), vec![0, 1]);
+    shards.shutdown().unwrap();
+}
+
+#[test]
+fn send_after_shutdown() {
+    init();
+    let shards = Shards::new(1, Balance::RoundRobin, |event_loop, shard, stream| {
+        add_echo(event_loop, shard, stream, &Added::default())
+    })
+    .unwrap();
+    let handle = shards.handles()[0].clone();
+    assert_eq!(handle.index(), 0);
+    shards.shutdown().unwrap();
+
+    let listener = net::TcpListener::bind(any_local_address()).unwrap();
+    let (_client, server) = connect(&listener);
+    let err = handle.send(server).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::BrokenPipe);
+}
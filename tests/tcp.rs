@@ -29,8 +29,22 @@ fn assert_size() {
     // Without debug assertions enabled `TcpListener`, `TcpStream` and
     // `UdpSocket` should have the same size as the system specific socket, i.e.
     // just a file descriptor on Unix platforms.
+    #[cfg(not(feature = "readiness-cache"))]
     assert_eq!(size_of::<TcpListener>(), size_of::<std::net::TcpListener>());
+    #[cfg(not(feature = "readiness-cache"))]
     assert_eq!(size_of::<TcpStream>(), size_of::<std::net::TcpStream>());
+    // With the `readiness-cache` feature enabled they also hold a pointer to
+    // the state shared with the `Registry`.
+    #[cfg(feature = "readiness-cache")]
+    assert_eq!(
+        size_of::<TcpListener>(),
+        size_of::<(std::net::TcpListener, usize)>()
+    );
+    #[cfg(feature = "readiness-cache")]
+    assert_eq!(
+        size_of::<TcpStream>(),
+        size_of::<(std::net::TcpStream, usize)>()
+    );
 }
 
 #[test]
@@ -205,12 +219,8 @@ fn read() {
         for event in &events {
             assert_eq!(event.token(), Token(1));
             let mut buf = [0; 1024];
-            loop {
-                if let Ok(amt) = data.socket.read(&mut buf) {
-                    data.amt += amt;
-                } else {
-                    break;
-                }
+            while let Ok(amt) = data.socket.read(&mut buf) {
+                data.amt += amt;
                 if data.amt >= N {
                     data.shutdown = true;
                     break;
@@ -270,12 +280,8 @@ fn peek() {
                 Err(err) => panic!("unexpected error: {}", err),
             }
 
-            loop {
-                if let Ok(amt) = data.socket.read(&mut buf) {
-                    data.amt += amt;
-                } else {
-                    break;
-                }
+            while let Ok(amt) = data.socket.read(&mut buf) {
+                data.amt += amt;
                 if data.amt >= N {
                     data.shutdown = true;
                     break;
@@ -329,12 +335,8 @@ fn write() {
         for event in &events {
             assert_eq!(event.token(), Token(1));
             let buf = [0; 1024];
-            loop {
-                if let Ok(amt) = data.socket.write(&buf) {
-                    data.amt += amt;
-                } else {
-                    break;
-                }
+            while let Ok(amt) = data.socket.write(&buf) {
+                data.amt += amt;
                 if data.amt >= N {
                     data.shutdown = true;
                     break;
@@ -0,0 +1,152 @@
+#![cfg(not(target_os = "wasi"))]
+#![cfg(all(feature = "os-poll", feature = "net"))]
+
+use mio::net::{TcpConnector, TcpSocket, TcpStream};
+use mio::{Events, Poll, Token};
+use std::io::{self, Read, Write};
+use std::net::{self, SocketAddr};
+use std::time::{Duration, Instant};
+
+mod util;
+use util::{any_local_address, assert_send, assert_sync, init_with_poll};
+
+const ID: Token = Token(0);
+
+#[test]
+fn is_send_and_sync() {
+    assert_send::<TcpConnector>();
+    assert_sync::<TcpConnector>();
+}
+
+#[test]
+fn connect_single_address() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let listener = net::TcpListener::bind(any_local_address()).unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let mut connector = TcpConnector::new(vec![addr], ID);
+    let (mut stream, address) = connect(&mut poll, &mut events, &mut connector).unwrap();
+    assert_eq!(address, addr);
+    assert_eq!(stream.peer_addr().unwrap(), addr);
+
+    let (mut peer, _) = listener.accept().unwrap();
+    stream.write_all(b"hello").unwrap();
+    let mut buf = [0; 5];
+    peer.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"hello");
+
+    // The connector is done.
+    assert!(connector.timeout().is_none());
+    assert!(connector.drive(poll.registry()).is_err());
+}
+
+#[test]
+fn connect_fallback_on_failure() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let (_socket, refused) = refused_address();
+    let listener = net::TcpListener::bind(any_local_address()).unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    // A failed attempt should start the next one without waiting for the
+    // delay.
+    let mut connector =
+        TcpConnector::new(vec![refused, addr], ID).with_delay(Duration::from_secs(3600));
+    let (stream, address) = connect(&mut poll, &mut events, &mut connector).unwrap();
+    assert_eq!(address, addr);
+    assert_eq!(stream.peer_addr().unwrap(), addr);
+}
+
+#[test]
+fn connect_all_failed() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let (_socket1, refused1) = refused_address();
+    let (_socket2, refused2) = refused_address();
+    let mut connector = TcpConnector::new(vec![refused1, refused2], ID);
+    let err = connect(&mut poll, &mut events, &mut connector).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::ConnectionRefused);
+}
+
+#[test]
+fn connect_no_addresses() {
+    let (poll, _) = init_with_poll();
+
+    let mut connector = TcpConnector::new(Vec::new(), ID);
+    assert!(connector.timeout().is_none());
+    let err = connector.drive(poll.registry()).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn connect_staggered_timeout() {
+    let (poll, _) = init_with_poll();
+
+    let listener1 = net::TcpListener::bind(any_local_address()).unwrap();
+    let listener2 = net::TcpListener::bind(any_local_address()).unwrap();
+    let addrs = vec![
+        listener1.local_addr().unwrap(),
+        listener2.local_addr().unwrap(),
+    ];
+
+    let delay = Duration::from_secs(10);
+    let mut connector = TcpConnector::new(addrs, ID).with_delay(delay);
+    // The first attempt is started right away.
+    assert_eq!(connector.timeout(), Some(Duration::from_secs(0)));
+
+    // The second attempt is started after the delay.
+    assert!(connector.drive(poll.registry()).unwrap().is_none());
+    let timeout = connector.timeout().unwrap();
+    assert!(timeout > Duration::from_secs(0) && timeout <= delay);
+}
+
+#[test]
+fn connect_delay_overflow() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let listener1 = net::TcpListener::bind(any_local_address()).unwrap();
+    let listener2 = net::TcpListener::bind(any_local_address()).unwrap();
+    let addrs = vec![
+        listener1.local_addr().unwrap(),
+        listener2.local_addr().unwrap(),
+    ];
+
+    // The next attempt is never started, but the first one can still connect.
+    let mut connector = TcpConnector::new(addrs.clone(), ID).with_delay(Duration::MAX);
+    assert!(connector.drive(poll.registry()).unwrap().is_none());
+    assert!(connector.timeout().is_none());
+    let (_, address) = connect(&mut poll, &mut events, &mut connector).unwrap();
+    assert_eq!(address, addrs[0]);
+}
+
+/// Drives `connector` until it completes, failing the test if it takes too
+/// long.
+fn connect(
+    poll: &mut Poll,
+    events: &mut Events,
+    connector: &mut TcpConnector,
+) -> io::Result<(TcpStream, SocketAddr)> {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    loop {
+        if let Some(connected) = connector.drive(poll.registry())? {
+            return Ok(connected);
+        }
+        let now = Instant::now();
+        assert!(now < deadline, "connector didn't complete in time");
+        let timeout = connector
+            .timeout()
+            .unwrap_or(deadline - now)
+            .min(deadline - now);
+        poll.poll(events, Some(timeout))?;
+    }
+}
+
+/// Returns an address on which connections are refused, for as long as the
+/// returned socket, bound but not listening, is alive.
+fn refused_address() -> (TcpSocket, SocketAddr) {
+    let socket = TcpSocket::new_v4().unwrap();
+    socket.bind(any_local_address()).unwrap();
+    let addr = socket.local_addr().unwrap();
+    (socket, addr)
+}
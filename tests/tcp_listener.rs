@@ -47,6 +47,29 @@ fn tcp_listener_std() {
     });
 }
 
+#[test]
+fn accept_many() {
+    init();
+
+    let listener = TcpListener::bind(any_local_address()).unwrap();
+    let addr = listener.local_addr().unwrap();
+    let clients: Vec<_> = (0..5)
+        .map(|_| net::TcpStream::connect(addr).unwrap())
+        .collect();
+
+    let mut streams = Vec::new();
+    assert!(matches!(listener.accept_many(&mut streams, 3), (3, None)));
+    assert_eq!(streams.len(), 3);
+    assert!(matches!(listener.accept_many(&mut streams, 10), (2, None)));
+    assert_eq!(streams.len(), 5);
+    assert!(matches!(listener.accept_many(&mut streams, 10), (0, None)));
+
+    for (client, (stream, peer_addr)) in clients.iter().zip(streams.iter()) {
+        assert_eq!(client.local_addr().unwrap(), *peer_addr);
+        assert_eq!(stream.peer_addr().unwrap(), *peer_addr);
+    }
+}
+
 fn smoke_test_tcp_listener<F>(addr: SocketAddr, make_listener: F)
 where
     F: FnOnce(SocketAddr) -> io::Result<TcpListener>,
@@ -0,0 +1,139 @@
+#![cfg(not(target_os = "wasi"))]
+#![cfg(all(feature = "os-poll", feature = "net"))]
+
+use mio::net::TcpSocket;
+use mio::{Interest, Token};
+use std::io::{Read, Write};
+
+mod util;
+use util::{
+    any_local_address, any_local_ipv6_address, assert_send, assert_socket_close_on_exec,
+    assert_socket_non_blocking, assert_sync, expect_events, init_with_poll, ExpectEvent,
+};
+
+const ID1: Token = Token(0);
+const ID2: Token = Token(1);
+
+#[test]
+fn is_send_and_sync() {
+    assert_send::<TcpSocket>();
+    assert_sync::<TcpSocket>();
+}
+
+#[test]
+fn set_reuseaddr() {
+    let socket = TcpSocket::new_v4().unwrap();
+    socket.set_reuseaddr(true).unwrap();
+    assert!(socket.reuseaddr().unwrap());
+    socket.set_reuseaddr(false).unwrap();
+    assert!(!socket.reuseaddr().unwrap());
+}
+
+#[test]
+#[cfg(all(unix, not(any(target_os = "illumos", target_os = "solaris"))))]
+fn set_reuseport() {
+    let socket = TcpSocket::new_v4().unwrap();
+    socket.set_reuseport(true).unwrap();
+    assert!(socket.reuseport().unwrap());
+
+    // Two listeners on the same address.
+    socket.bind(any_local_address()).unwrap();
+    let addr = socket.local_addr().unwrap();
+    let _listener1 = socket.listen(8).unwrap();
+
+    let socket = TcpSocket::new_v4().unwrap();
+    socket.set_reuseport(true).unwrap();
+    socket.bind(addr).unwrap();
+    let _listener2 = socket.listen(8).unwrap();
+}
+
+#[test]
+fn buffer_sizes() {
+    let socket = TcpSocket::new_v4().unwrap();
+    socket.set_send_buffer_size(16 * 1024).unwrap();
+    // The OS may round the size, e.g. Linux doubles it.
+    assert!(socket.send_buffer_size().unwrap() >= 16 * 1024);
+    socket.set_recv_buffer_size(16 * 1024).unwrap();
+    assert!(socket.recv_buffer_size().unwrap() >= 16 * 1024);
+}
+
+#[test]
+fn set_only_v6() {
+    let socket = TcpSocket::new_v6().unwrap();
+    socket.set_only_v6(true).unwrap();
+    assert!(socket.only_v6().unwrap());
+    socket.set_only_v6(false).unwrap();
+    assert!(!socket.only_v6().unwrap());
+
+    socket.bind(any_local_ipv6_address()).unwrap();
+    assert!(socket.local_addr().unwrap().is_ipv6());
+}
+
+#[test]
+#[cfg(any(target_os = "android", target_os = "linux"))]
+fn bind_device() {
+    let socket = TcpSocket::new_v4().unwrap();
+    assert_eq!(socket.device().unwrap(), None);
+    match socket.bind_device(Some(b"lo")) {
+        Ok(()) => assert_eq!(socket.device().unwrap().as_deref(), Some(&b"lo"[..])),
+        // Requires `CAP_NET_RAW`.
+        Err(ref err) if err.kind() == std::io::ErrorKind::PermissionDenied => return,
+        Err(err) => panic!("unexpected error: {}", err),
+    }
+    socket.bind_device(None).unwrap();
+    assert_eq!(socket.device().unwrap(), None);
+}
+
+#[test]
+fn listen_and_connect() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let socket = TcpSocket::new_for_addr(any_local_address()).unwrap();
+    socket.set_reuseaddr(true).unwrap();
+    socket.bind(any_local_address()).unwrap();
+    let addr = socket.local_addr().unwrap();
+    let mut listener = socket.listen(8).unwrap();
+    assert_eq!(listener.local_addr().unwrap(), addr);
+    assert_socket_non_blocking(&listener);
+    assert_socket_close_on_exec(&listener);
+
+    // Connect from a specific local address.
+    let socket = TcpSocket::new_v4().unwrap();
+    socket.bind(any_local_address()).unwrap();
+    let local_addr = socket.local_addr().unwrap();
+    let mut stream = socket.connect(addr).unwrap();
+    assert_socket_non_blocking(&stream);
+    assert_socket_close_on_exec(&stream);
+
+    poll.registry()
+        .register(&mut listener, ID1, Interest::READABLE)
+        .unwrap();
+    poll.registry()
+        .register(&mut stream, ID2, Interest::WRITABLE)
+        .unwrap();
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![
+            ExpectEvent::new(ID1, Interest::READABLE),
+            ExpectEvent::new(ID2, Interest::WRITABLE),
+        ],
+    );
+
+    let (mut accepted, peer_addr) = listener.accept().unwrap();
+    assert_eq!(peer_addr, local_addr);
+    assert_eq!(stream.local_addr().unwrap(), local_addr);
+
+    stream.write_all(b"hello").unwrap();
+    poll.registry()
+        .register(&mut accepted, ID1, Interest::READABLE)
+        .unwrap();
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(ID1, Interest::READABLE)],
+    );
+    let mut buf = [0; 16];
+    let n = accepted.read(&mut buf).unwrap();
+    assert_eq!(&buf[..n], b"hello");
+}
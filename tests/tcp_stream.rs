@@ -9,7 +9,7 @@ use std::sync::{mpsc::channel, Arc, Barrier};
 use std::thread;
 use std::time::Duration;
 
-use mio::net::TcpStream;
+use mio::net::{TcpKeepalive, TcpStream};
 use mio::{Interest, Token};
 
 #[macro_use]
@@ -260,6 +260,298 @@ fn get_nodelay_without_previous_set() {
     thread_handle.join().expect("unable to join thread");
 }
 
+#[test]
+fn set_get_keepalive() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let barrier = Arc::new(Barrier::new(2));
+    let (thread_handle, address) = start_listener(1, Some(barrier.clone()), false);
+
+    let mut stream = TcpStream::connect(address).unwrap();
+
+    // on Windows: the stream must be connected before setting keepalive.
+    poll.registry()
+        .register(&mut stream, ID1, Interest::WRITABLE)
+        .expect("unable to register TCP stream");
+
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(ID1, Interest::WRITABLE)],
+    );
+
+    assert_eq!(stream.keepalive().unwrap(), None);
+
+    #[allow(unused_mut)]
+    let mut keepalive = TcpKeepalive::new();
+    #[cfg(not(any(target_os = "illumos", target_os = "openbsd", target_os = "redox")))]
+    {
+        keepalive = keepalive
+            .with_time(Duration::from_secs(60))
+            .with_interval(Duration::from_secs(10));
+    }
+    #[cfg(not(any(
+        target_os = "illumos",
+        target_os = "openbsd",
+        target_os = "redox",
+        windows
+    )))]
+    {
+        keepalive = keepalive.with_retries(5);
+    }
+    stream.set_keepalive(Some(keepalive)).unwrap();
+    let got = stream.keepalive().unwrap().expect("keepalive not enabled");
+    // The parameters can't be retrieved on Windows.
+    #[cfg(not(windows))]
+    assert_eq!(got, keepalive);
+    #[cfg(windows)]
+    let _ = got;
+
+    // Sub-second values are rounded up.
+    #[cfg(not(any(
+        target_os = "illumos",
+        target_os = "openbsd",
+        target_os = "redox",
+        windows
+    )))]
+    {
+        let rounded = keepalive
+            .with_time(Duration::from_millis(1500))
+            .with_interval(Duration::from_millis(1));
+        stream.set_keepalive(Some(rounded)).unwrap();
+        let got = stream.keepalive().unwrap().expect("keepalive not enabled");
+        let expected = keepalive
+            .with_time(Duration::from_secs(2))
+            .with_interval(Duration::from_secs(1));
+        assert_eq!(got, expected);
+    }
+
+    stream.set_keepalive(None).unwrap();
+    assert_eq!(stream.keepalive().unwrap(), None);
+    assert!(stream.take_error().unwrap().is_none());
+
+    barrier.wait();
+    thread_handle.join().expect("unable to join thread");
+}
+
+#[test]
+#[cfg(any(target_os = "android", target_os = "linux"))]
+fn set_get_user_timeout() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let barrier = Arc::new(Barrier::new(2));
+    let (thread_handle, address) = start_listener(1, Some(barrier.clone()), false);
+
+    let mut stream = TcpStream::connect(address).unwrap();
+    poll.registry()
+        .register(&mut stream, ID1, Interest::WRITABLE)
+        .expect("unable to register TCP stream");
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(ID1, Interest::WRITABLE)],
+    );
+
+    assert_eq!(stream.user_timeout().unwrap(), None);
+    let timeout = Duration::from_millis(1500);
+    stream.set_user_timeout(Some(timeout)).unwrap();
+    assert_eq!(stream.user_timeout().unwrap(), Some(timeout));
+    // Sub-millisecond values are rounded up.
+    stream
+        .set_user_timeout(Some(Duration::from_micros(1)))
+        .unwrap();
+    assert_eq!(
+        stream.user_timeout().unwrap(),
+        Some(Duration::from_millis(1))
+    );
+    stream.set_user_timeout(None).unwrap();
+    assert_eq!(stream.user_timeout().unwrap(), None);
+
+    barrier.wait();
+    thread_handle.join().expect("unable to join thread");
+}
+
+#[test]
+fn set_get_linger_and_buffer_sizes() {
+    let listener = std::net::TcpListener::bind(any_local_address()).unwrap();
+    let stream = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+
+    assert_eq!(stream.linger().unwrap(), None);
+    stream.set_linger(Some(Duration::from_secs(5))).unwrap();
+    assert_eq!(stream.linger().unwrap(), Some(Duration::from_secs(5)));
+    stream.set_linger(None).unwrap();
+    assert_eq!(stream.linger().unwrap(), None);
+
+    // The OS may round the size, e.g. Linux doubles it.
+    stream.set_send_buffer_size(16 * 1024).unwrap();
+    assert!(stream.send_buffer_size().unwrap() >= 16 * 1024);
+    stream.set_recv_buffer_size(16 * 1024).unwrap();
+    assert!(stream.recv_buffer_size().unwrap() >= 16 * 1024);
+}
+
+#[test]
+#[cfg(any(
+    target_os = "android",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "tvos",
+    target_os = "watchos",
+))]
+fn tcp_info() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let (thread_handle, address) = echo_listener(any_local_address(), 1);
+
+    let mut stream = TcpStream::connect(address).unwrap();
+    poll.registry()
+        .register(&mut stream, ID1, Interest::WRITABLE)
+        .expect("unable to register TCP stream");
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(ID1, Interest::WRITABLE)],
+    );
+
+    let info = stream.tcp_info().unwrap();
+    assert!(info.rtt().is_some());
+    assert!(info.rtt_var().is_some());
+    assert!(info.congestion_window().unwrap() > 0);
+    #[cfg(any(target_os = "android", target_os = "freebsd", target_os = "linux"))]
+    assert_eq!(info.retransmits(), Some(0));
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    assert_eq!(info.unacked(), Some(0));
+
+    drop(stream);
+    thread_handle.join().expect("unable to join thread");
+}
+
+#[test]
+#[cfg(any(
+    target_os = "android",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "tvos",
+    target_os = "watchos",
+))]
+fn send_file() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let path = util::temp_file("tcp_stream_send_file");
+    let data: Vec<u8> = (0..255).cycle().take(4 * 1024 * 1024).collect();
+    std::fs::write(&path, &data).unwrap();
+    let file = std::fs::File::open(&path).unwrap();
+
+    let listener = net::TcpListener::bind(any_local_address()).unwrap();
+    let mut stream = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    let (mut peer, _) = listener.accept().unwrap();
+    poll.registry()
+        .register(&mut stream, ID1, Interest::WRITABLE)
+        .unwrap();
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(ID1, Interest::WRITABLE)],
+    );
+
+    // Nothing to send beyond the end of the file.
+    assert_eq!(stream.send_file(&file, data.len() as u64, 10).unwrap(), 0);
+
+    let n = stream.send_file(&file, 5, 10).unwrap();
+    assert_eq!(n, 10);
+    let mut buf = [0; 10];
+    peer.read_exact(&mut buf).unwrap();
+    assert_eq!(buf, data[5..15]);
+
+    // Fill the send buffer, the peer isn't reading.
+    let mut sent = 0;
+    loop {
+        match stream.send_file(&file, sent as u64, data.len() - sent) {
+            Ok(n) => {
+                assert!(n > 0);
+                sent += n;
+                assert!(sent < data.len(), "sent the entire file");
+            }
+            Err(ref err) if err.kind() == io::ErrorKind::WouldBlock => break,
+            Err(err) => panic!("unexpected error: {}", err),
+        }
+    }
+
+    // Once the peer reads the stream becomes writable again.
+    let mut received = vec![0; sent];
+    peer.read_exact(&mut received).unwrap();
+    assert!(received == data[..sent]);
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(ID1, Interest::WRITABLE)],
+    );
+}
+
+#[test]
+#[cfg(any(target_os = "android", target_os = "linux"))]
+fn send_zerocopy() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let listener = net::TcpListener::bind(any_local_address()).unwrap();
+    let mut stream = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    let (mut peer, _) = listener.accept().unwrap();
+    poll.registry()
+        .register(&mut stream, ID1, Interest::WRITABLE)
+        .unwrap();
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(ID1, Interest::WRITABLE)],
+    );
+
+    let mut queue = mio::net::ZeroCopyQueue::new();
+    queue.push(vec![1; 16]);
+    // Without `SO_ZEROCOPY` the buffers would never complete.
+    let err = stream.send_zerocopy(&mut queue).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+    stream.set_zerocopy(true).unwrap();
+    assert!(stream.zerocopy().unwrap());
+
+    queue.push(Vec::new());
+    queue.push(vec![2; 32]);
+    while queue.has_unsent() {
+        assert!(stream.send_zerocopy(&mut queue).unwrap() > 0);
+    }
+    assert_eq!(stream.send_zerocopy(&mut queue).unwrap(), 0);
+    assert_eq!(queue.len(), 3);
+
+    let mut buf = [0; 48];
+    peer.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf[..16], &[1; 16][..]);
+    assert_eq!(&buf[16..], &[2; 32][..]);
+
+    // Completions are signalled as error.
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(ID1, Readiness::ERROR)],
+    );
+    let mut completed = Vec::new();
+    for _ in 0..10 {
+        for completion in stream.zerocopy_completions(&mut queue) {
+            completed.push(completion.unwrap().into_buffer());
+        }
+        if queue.is_empty() {
+            break;
+        }
+        poll.poll(&mut events, Some(Duration::from_millis(100)))
+            .unwrap();
+    }
+    assert_eq!(completed, vec![vec![1; 16], Vec::new(), vec![2; 32]]);
+    assert!(stream.take_error().unwrap().is_none());
+}
+
 #[test]
 fn shutdown_read() {
     let (mut poll, mut events) = init_with_poll();
@@ -509,6 +801,160 @@ fn no_events_after_deregister() {
     thread_handle.join().expect("unable to join thread");
 }
 
+#[test]
+#[cfg(feature = "readiness-cache")]
+fn readiness() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let (thread_handle, address) = echo_listener(any_local_address(), 1);
+
+    let mut stream = TcpStream::connect(address).unwrap();
+    assert_eq!(stream.readiness(), None);
+
+    poll.registry()
+        .register(&mut stream, ID1, Interest::WRITABLE.add(Interest::READABLE))
+        .expect("unable to register TCP stream");
+
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(ID1, Interest::WRITABLE)],
+    );
+    assert_eq!(stream.readiness(), Some(Interest::WRITABLE));
+
+    checked_write!(stream.write(DATA1));
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(ID1, Interest::READABLE)],
+    );
+    assert_eq!(
+        stream.readiness(),
+        Some(Interest::READABLE | Interest::WRITABLE)
+    );
+
+    // Reading until `WouldBlock` only clears the readable readiness.
+    let mut buf = [0; 16];
+    expect_read!(stream.read(&mut buf), DATA1);
+    assert_would_block(stream.read(&mut buf));
+    assert_eq!(stream.readiness(), Some(Interest::WRITABLE));
+
+    poll.registry()
+        .deregister(&mut stream)
+        .expect("unable to deregister TCP stream");
+    assert_eq!(stream.readiness(), None);
+
+    drop(stream);
+    thread_handle.join().expect("unable to join thread");
+}
+
+#[test]
+#[cfg(feature = "readiness-cache")]
+fn fair_redelivery() {
+    let (mut poll, mut events) = init_with_poll();
+    poll.set_fair_redelivery(true);
+
+    let listener = net::TcpListener::bind(any_local_address()).unwrap();
+    let mut clients = Vec::new();
+    let mut streams = Vec::new();
+    for token in &[ID1, ID2] {
+        let mut client = net::TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (stream, _) = listener.accept().unwrap();
+        stream.set_nonblocking(true).unwrap();
+        let mut stream = TcpStream::from_std(stream);
+        poll.registry()
+            .register(&mut stream, *token, Interest::READABLE)
+            .unwrap();
+        client.write_all(DATA1).unwrap();
+        clients.push(client);
+        streams.push(stream);
+    }
+
+    // Read at most 4 bytes per event, without losing any readiness.
+    let mut received = vec![Vec::new(), Vec::new()];
+    let mut buf = [0; 4];
+    while received.iter().any(|r| r.len() != DATA1_LEN) {
+        poll.poll(&mut events, Some(Duration::from_millis(500)))
+            .unwrap();
+        assert!(!events.is_empty(), "stalled: {:?}", received);
+        for event in events.iter() {
+            let i = usize::from(event.token());
+            match streams[i].read(&mut buf) {
+                Ok(n) => received[i].extend_from_slice(&buf[..n]),
+                Err(ref err) if err.kind() == io::ErrorKind::WouldBlock => {}
+                Err(err) => panic!("unexpected error: {}", err),
+            }
+        }
+    }
+    assert_eq!(received, vec![DATA1.to_vec(), DATA1.to_vec()]);
+
+    // The sources are re-delivered until they return `WouldBlock`.
+    poll.poll(&mut events, Some(Duration::from_millis(500)))
+        .unwrap();
+    assert_eq!(events.iter().count(), 2);
+    for stream in &mut streams {
+        assert_would_block(stream.read(&mut buf));
+    }
+    expect_no_events(&mut poll, &mut events);
+
+    // Without fair re-delivery a source that isn't drained doesn't receive
+    // another event.
+    poll.set_fair_redelivery(false);
+    clients[0].write_all(DATA1).unwrap();
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(ID1, Interest::READABLE)],
+    );
+    assert_eq!(streams[0].read(&mut buf).unwrap(), 4);
+    expect_no_events(&mut poll, &mut events);
+
+    for mut stream in streams {
+        poll.registry().deregister(&mut stream).unwrap();
+    }
+}
+
+#[test]
+#[cfg(feature = "readiness-cache")]
+fn fair_redelivery_half_closed() {
+    let (mut poll, mut events) = init_with_poll();
+    poll.set_fair_redelivery(true);
+
+    let listener = net::TcpListener::bind(any_local_address()).unwrap();
+    let mut client = net::TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    let (stream, _) = listener.accept().unwrap();
+    stream.set_nonblocking(true).unwrap();
+    let mut stream = TcpStream::from_std(stream);
+    poll.registry()
+        .register(&mut stream, ID1, Interest::READABLE)
+        .unwrap();
+    client.write_all(DATA1).unwrap();
+    client.shutdown(Shutdown::Write).unwrap();
+
+    // After the peer closed its writing side a short read doesn't end the
+    // re-delivery, only reading the end of the stream does.
+    let mut received = Vec::new();
+    let mut buf = [0; 4];
+    loop {
+        poll.poll(&mut events, Some(Duration::from_millis(500)))
+            .unwrap();
+        assert!(!events.is_empty(), "stalled: {:?}", received);
+        match stream.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => received.extend_from_slice(&buf[..n]),
+            Err(ref err) if err.kind() == io::ErrorKind::WouldBlock => {}
+            Err(err) => panic!("unexpected error: {}", err),
+        }
+    }
+    assert_eq!(received, DATA1);
+
+    // Once the end of the stream is read the source isn't re-delivered, even
+    // though it stays read closed.
+    expect_no_events(&mut poll, &mut events);
+
+    poll.registry().deregister(&mut stream).unwrap();
+}
+
 #[test]
 #[cfg_attr(
     windows,
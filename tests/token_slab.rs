@@ -0,0 +1,77 @@
+#![cfg(feature = "util")]
+
+use mio::util::TokenSlab;
+use mio::Token;
+
+#[test]
+fn insert_get_remove() {
+    let mut slab = TokenSlab::new();
+    assert!(slab.is_empty());
+
+    let token1 = slab.insert("a");
+    let token2 = slab.insert("b");
+    assert_ne!(token1, token2);
+    assert_eq!(slab.len(), 2);
+    assert_eq!(slab.get(token1), Some(&"a"));
+    assert_eq!(slab.get(token2), Some(&"b"));
+
+    *slab.get_mut(token2).unwrap() = "c";
+    assert_eq!(slab.remove(token2), Some("c"));
+    assert_eq!(slab.remove(token2), None);
+    assert!(!slab.contains(token2));
+    assert_eq!(slab.len(), 1);
+}
+
+#[test]
+fn stale_tokens() {
+    let mut slab = TokenSlab::new();
+
+    let old_token = slab.insert(1);
+    assert_eq!(slab.remove(old_token), Some(1));
+
+    // The slot is reused, but with a new generation.
+    assert_ne!(slab.vacant_token(), old_token);
+    let new_token = slab.insert(2);
+    assert_ne!(new_token, old_token);
+    assert_eq!(slab.get(old_token), None);
+    assert_eq!(slab.get_mut(old_token), None);
+    assert_eq!(slab.remove(old_token), None);
+    assert_eq!(slab.get(new_token), Some(&2));
+}
+
+#[test]
+fn vacant_token() {
+    let mut slab = TokenSlab::with_capacity(4);
+    assert!(slab.capacity() >= 4);
+
+    for value in 0..8 {
+        let token = slab.vacant_token();
+        assert_eq!(slab.insert(value), token);
+    }
+    let token = slab.iter().nth(3).unwrap().0;
+    slab.remove(token);
+    let token = slab.vacant_token();
+    assert_eq!(slab.insert(100), token);
+    assert_eq!(slab.get(token), Some(&100));
+}
+
+#[test]
+fn unknown_tokens() {
+    let mut slab = TokenSlab::new();
+    slab.insert(());
+
+    assert!(!slab.contains(Token(usize::MAX)));
+    assert!(!slab.contains(Token(1)));
+    assert_eq!(slab.remove(Token(usize::MAX)), None);
+}
+
+#[test]
+fn iter() {
+    let mut slab = TokenSlab::new();
+    let tokens: Vec<Token> = (0..4).map(|value| slab.insert(value)).collect();
+    slab.remove(tokens[1]);
+
+    let got: Vec<(Token, i32)> = slab.iter().map(|(token, value)| (token, *value)).collect();
+    assert_eq!(got, vec![(tokens[0], 0), (tokens[2], 2), (tokens[3], 3)]);
+    assert_eq!((&slab).into_iter().count(), 3);
+}
@@ -38,7 +38,15 @@ fn assert_size() {
     // Without debug assertions enabled `TcpListener`, `TcpStream` and
     // `UdpSocket` should have the same size as the system specific socket, i.e.
     // just a file descriptor on Unix platforms.
+    #[cfg(not(feature = "readiness-cache"))]
     assert_eq!(size_of::<UdpSocket>(), size_of::<std::net::UdpSocket>());
+    // With the `readiness-cache` feature enabled they also hold a pointer to
+    // the state shared with the `Registry`.
+    #[cfg(feature = "readiness-cache")]
+    assert_eq!(
+        size_of::<UdpSocket>(),
+        size_of::<(std::net::UdpSocket, usize)>()
+    );
 }
 
 #[test]
@@ -109,6 +117,35 @@ fn unconnected_udp_socket_std() {
     smoke_test_unconnected_udp_socket(socket1, socket2);
 }
 
+#[test]
+#[cfg(feature = "readiness-cache")]
+fn readiness() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let mut socket1 = UdpSocket::bind(any_local_address()).unwrap();
+    let socket2 = UdpSocket::bind(any_local_address()).unwrap();
+    let address1 = socket1.local_addr().unwrap();
+
+    poll.registry()
+        .register(&mut socket1, ID1, Interest::READABLE)
+        .expect("unable to register UDP socket");
+    assert_eq!(socket1.readiness(), None);
+
+    checked_write!(socket2.send_to(DATA1, address1));
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(ID1, Interest::READABLE)],
+    );
+    assert_eq!(socket1.readiness(), Some(Interest::READABLE));
+
+    let mut buf = [0; 20];
+    expect_read!(socket1.recv_from(&mut buf), DATA1, __anywhere);
+    assert_eq!(socket1.readiness(), Some(Interest::READABLE));
+    assert_would_block(socket1.recv_from(&mut buf));
+    assert_eq!(socket1.readiness(), None);
+}
+
 fn smoke_test_unconnected_udp_socket(mut socket1: UdpSocket, mut socket2: UdpSocket) {
     let (mut poll, mut events) = init_with_poll();
 
@@ -181,6 +218,71 @@ fn set_get_ttl() {
     assert!(socket1.take_error().unwrap().is_none());
 }
 
+#[test]
+#[cfg(any(target_os = "android", target_os = "linux"))]
+fn send_zerocopy() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let mut socket1 = UdpSocket::bind(any_local_address()).unwrap();
+    let socket2 = UdpSocket::bind(any_local_address()).unwrap();
+    socket1.connect(socket2.local_addr().unwrap()).unwrap();
+    poll.registry()
+        .register(&mut socket1, ID1, Interest::WRITABLE)
+        .unwrap();
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(ID1, Interest::WRITABLE)],
+    );
+
+    // Supported for UDP since Linux 5.0.
+    if socket1.set_zerocopy(true).is_err() {
+        return;
+    }
+    assert!(socket1.zerocopy().unwrap());
+
+    let mut queue = mio::net::ZeroCopyQueue::new();
+    queue.push(DATA1);
+    queue.push(DATA2);
+    assert_eq!(socket1.send_zerocopy(&mut queue).unwrap(), DATA1.len());
+    assert_eq!(socket1.send_zerocopy(&mut queue).unwrap(), DATA2.len());
+    assert_eq!(socket1.send_zerocopy(&mut queue).unwrap(), 0);
+
+    let mut buf = [0; 20];
+    let n = socket2.recv(&mut buf).unwrap();
+    assert_eq!(&buf[..n], DATA1);
+    let n = socket2.recv(&mut buf).unwrap();
+    assert_eq!(&buf[..n], DATA2);
+
+    let mut completed = Vec::new();
+    for _ in 0..10 {
+        for completion in socket1.zerocopy_completions(&mut queue) {
+            completed.push(completion.unwrap().into_buffer());
+        }
+        if queue.is_empty() {
+            break;
+        }
+        poll.poll(&mut events, Some(Duration::from_millis(100)))
+            .unwrap();
+    }
+    assert_eq!(completed, vec![DATA1, DATA2]);
+}
+
+#[test]
+fn set_get_linger_and_buffer_sizes() {
+    let socket = UdpSocket::bind(any_local_address()).unwrap();
+
+    socket.set_linger(Some(Duration::from_secs(1))).unwrap();
+    assert_eq!(socket.linger().unwrap(), Some(Duration::from_secs(1)));
+    socket.set_linger(None).unwrap();
+    assert_eq!(socket.linger().unwrap(), None);
+
+    socket.set_send_buffer_size(16 * 1024).unwrap();
+    assert!(socket.send_buffer_size().unwrap() >= 16 * 1024);
+    socket.set_recv_buffer_size(16 * 1024).unwrap();
+    assert!(socket.recv_buffer_size().unwrap() >= 16 * 1024);
+}
+
 #[test]
 fn get_ttl_without_previous_set() {
     let socket1 = UdpSocket::bind(any_local_address()).unwrap();
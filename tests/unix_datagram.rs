@@ -5,6 +5,7 @@ use mio::{Interest, Token};
 use std::io;
 use std::net::Shutdown;
 use std::os::unix::net;
+use std::time::Duration;
 
 #[macro_use]
 mod util;
@@ -26,6 +27,21 @@ fn is_send_and_sync() {
     assert_sync::<UnixDatagram>();
 }
 
+#[test]
+fn set_get_linger_and_buffer_sizes() {
+    let (socket, _) = UnixDatagram::pair().unwrap();
+
+    socket.set_linger(Some(Duration::from_secs(1))).unwrap();
+    assert_eq!(socket.linger().unwrap(), Some(Duration::from_secs(1)));
+    socket.set_linger(None).unwrap();
+    assert_eq!(socket.linger().unwrap(), None);
+
+    socket.set_send_buffer_size(16 * 1024).unwrap();
+    assert!(socket.send_buffer_size().unwrap() >= 16 * 1024);
+    socket.set_recv_buffer_size(16 * 1024).unwrap();
+    assert!(socket.recv_buffer_size().unwrap() >= 16 * 1024);
+}
+
 #[test]
 fn unix_datagram_smoke_unconnected() {
     init();
@@ -44,6 +44,24 @@ fn unix_listener_from_std() {
     )
 }
 
+#[test]
+fn unix_listener_accept_many() {
+    let path = temp_file("unix_listener_accept_many");
+    let listener = UnixListener::bind(&path).unwrap();
+    let _clients: Vec<_> = (0..5)
+        .map(|_| net::UnixStream::connect(&path).unwrap())
+        .collect();
+
+    let mut streams = Vec::new();
+    assert!(matches!(listener.accept_many(&mut streams, 3), (3, None)));
+    assert!(matches!(listener.accept_many(&mut streams, 10), (2, None)));
+    assert_eq!(streams.len(), 5);
+    assert!(matches!(listener.accept_many(&mut streams, 10), (0, None)));
+    for (stream, _) in &streams {
+        assert_eq!(stream.local_addr().unwrap().as_pathname().unwrap(), &path);
+    }
+}
+
 #[test]
 fn unix_listener_local_addr() {
     let (mut poll, mut events) = init_with_poll();
@@ -215,3 +215,93 @@ pub fn expect_one_closed_event(poll: &mut Poll, events: &mut Events, token: Toke
     }
     assert!(iter.next().is_none());
 }
+
+#[test]
+#[cfg(any(target_os = "android", target_os = "linux"))]
+fn splice_through_pipe() {
+    use mio::net::TcpStream;
+    use mio::unix::{splice, SpliceBlocked};
+
+    let (mut poll, mut events) = util::init_with_poll();
+
+    let listener = std::net::TcpListener::bind(util::any_local_address()).unwrap();
+    let mut client = std::net::TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    let (from, _) = listener.accept().unwrap();
+    from.set_nonblocking(true).unwrap();
+    let from = TcpStream::from_std(from);
+    let to = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    let (mut peer, _) = listener.accept().unwrap();
+
+    let (mut sender, mut receiver) = pipe::new().unwrap();
+
+    // Nothing to move yet.
+    let err = splice(&from, &sender, 64).unwrap_err();
+    assert_eq!(SpliceBlocked::from_error(&err), Some(SpliceBlocked::Read));
+
+    client.write_all(DATA1).unwrap();
+    let n = splice(&from, &sender, 64).unwrap();
+    assert_eq!(n, DATA1.len());
+    let n = splice(&receiver, &to, 64).unwrap();
+    assert_eq!(n, DATA1.len());
+    let mut buf = [0; DATA1.len()];
+    peer.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, DATA1);
+
+    // Fill the pipe, now `to` isn't ready.
+    poll.registry()
+        .register(&mut sender, SENDER, Interest::WRITABLE)
+        .unwrap();
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(SENDER, Interest::WRITABLE)],
+    );
+    let mut filled = 0;
+    loop {
+        match sender.write(&[0; 4096]) {
+            Ok(n) => filled += n,
+            Err(ref err) if err.kind() == std::io::ErrorKind::WouldBlock => break,
+            Err(err) => panic!("unexpected error: {}", err),
+        }
+    }
+    client.write_all(DATA1).unwrap();
+    let err = splice(&from, &sender, 64).unwrap_err();
+    assert_eq!(SpliceBlocked::from_error(&err), Some(SpliceBlocked::Write));
+
+    // Once there is room again `to` becomes writable.
+    let mut buf = vec![0; filled];
+    receiver.set_nonblocking(false).unwrap();
+    receiver.read_exact(&mut buf).unwrap();
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(SENDER, Interest::WRITABLE)],
+    );
+    assert_eq!(splice(&from, &sender, 64).unwrap(), DATA1.len());
+}
+
+#[test]
+#[cfg(any(target_os = "android", target_os = "linux"))]
+fn tee() {
+    let (mut sender1, mut receiver1) = pipe::new().unwrap();
+    let (sender2, mut receiver2) = pipe::new().unwrap();
+
+    let err = mio::unix::tee(&receiver1, &sender2, 64).unwrap_err();
+    assert_eq!(
+        mio::unix::SpliceBlocked::from_error(&err),
+        Some(mio::unix::SpliceBlocked::Read)
+    );
+
+    sender1.write_all(DATA1).unwrap();
+    assert_eq!(
+        mio::unix::tee(&receiver1, &sender2, 64).unwrap(),
+        DATA1.len()
+    );
+
+    // The data is available on both pipes.
+    let mut buf = [0; 20];
+    assert_eq!(receiver2.read(&mut buf).unwrap(), DATA1.len());
+    assert_eq!(&buf[..DATA1.len()], DATA1);
+    assert_eq!(receiver1.read(&mut buf).unwrap(), DATA1.len());
+    assert_eq!(&buf[..DATA1.len()], DATA1);
+}
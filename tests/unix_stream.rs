@@ -9,6 +9,7 @@ use std::path::Path;
 use std::sync::mpsc::channel;
 use std::sync::{Arc, Barrier};
 use std::thread;
+use std::time::Duration;
 
 #[macro_use]
 mod util;
@@ -32,6 +33,21 @@ fn unix_stream_send_and_sync() {
     assert_sync::<UnixStream>();
 }
 
+#[test]
+fn set_get_linger_and_buffer_sizes() {
+    let (socket, _) = UnixStream::pair().unwrap();
+
+    socket.set_linger(Some(Duration::from_secs(1))).unwrap();
+    assert_eq!(socket.linger().unwrap(), Some(Duration::from_secs(1)));
+    socket.set_linger(None).unwrap();
+    assert_eq!(socket.linger().unwrap(), None);
+
+    socket.set_send_buffer_size(16 * 1024).unwrap();
+    assert!(socket.send_buffer_size().unwrap() >= 16 * 1024);
+    socket.set_recv_buffer_size(16 * 1024).unwrap();
+    assert!(socket.recv_buffer_size().unwrap() >= 16 * 1024);
+}
+
 #[test]
 fn unix_stream_smoke() {
     #[allow(clippy::redundant_closure)]
@@ -124,6 +140,42 @@ fn unix_stream_pair() {
     assert_would_block(s2.read(&mut buf));
 }
 
+#[test]
+#[cfg(any(
+    target_os = "android",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "ios",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "tvos",
+    target_os = "watchos",
+))]
+fn unix_stream_send_file() {
+    let (mut poll, mut events) = init_with_poll();
+
+    let path = temp_file("unix_stream_send_file");
+    std::fs::write(&path, DATA1).unwrap();
+    let file = std::fs::File::open(&path).unwrap();
+
+    let (s1, mut s2) = UnixStream::pair().unwrap();
+    poll.registry()
+        .register(&mut s2, TOKEN_1, Interest::READABLE)
+        .unwrap();
+
+    let n = s1.send_file(&file, 0, DATA1.len()).unwrap();
+    assert_eq!(n, DATA1.len());
+    expect_events(
+        &mut poll,
+        &mut events,
+        vec![ExpectEvent::new(TOKEN_1, Interest::READABLE)],
+    );
+
+    let mut buf = [0; DEFAULT_BUF_SIZE];
+    expect_read!(s2.read(&mut buf), DATA1);
+    assert_eq!(s1.send_file(&file, DATA1.len() as u64, 1).unwrap(), 0);
+}
+
 #[test]
 fn unix_stream_peer_addr() {
     init();
@@ -310,8 +362,8 @@ fn unix_stream_shutdown_both() {
     let err = stream.write(DATA2).unwrap_err();
     #[cfg(unix)]
     assert_eq!(err.kind(), io::ErrorKind::BrokenPipe);
-    #[cfg(window)]
-    assert_eq!(err.kind(), io::ErrorKind::ConnectionAbroted);
+    #[cfg(windows)]
+    assert_eq!(err.kind(), io::ErrorKind::ConnectionAborted);
 
     // Close the connection to allow the remote to shutdown
     drop(stream);
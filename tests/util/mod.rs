@@ -3,7 +3,7 @@
 #![cfg(not(target_os = "wasi"))]
 #![cfg(all(feature = "os-poll", feature = "net"))]
 
-use std::mem::size_of;
+use std::cell::RefCell;
 use std::net::SocketAddr;
 use std::ops::BitOr;
 #[cfg(unix)]
@@ -13,7 +13,7 @@ use std::sync::Once;
 use std::time::Duration;
 use std::{env, fmt, fs, io};
 
-use log::{error, warn};
+use log::{error, warn, LevelFilter, Log, Metadata, Record};
 use mio::event::Event;
 use mio::net::TcpStream;
 use mio::{Events, Interest, Poll, Token};
@@ -22,7 +22,12 @@ pub fn init() {
     static INIT: Once = Once::new();
 
     INIT.call_once(|| {
-        env_logger::try_init().expect("unable to initialise logger");
+        let logger = Logger {
+            inner: env_logger::Builder::from_default_env().build(),
+        };
+        // Always enable warnings so they can be captured, see `capture_logs`.
+        log::set_max_level(logger.inner.filter().max(LevelFilter::Warn));
+        log::set_boxed_logger(Box::new(logger)).expect("unable to initialise logger");
 
         // Remove all temporary files from previous test runs.
         let dir = temp_dir();
@@ -31,6 +36,47 @@ pub fn init() {
     })
 }
 
+thread_local! {
+    static CAPTURED: RefCell<Option<Vec<String>>> = const { RefCell::new(None) };
+}
+
+/// Logger that passes records on to `env_logger`, while also capturing the
+/// warnings logged on the current thread in `capture_logs`.
+struct Logger {
+    inner: env_logger::Logger,
+}
+
+impl Log for Logger {
+    fn enabled(&self, _: &Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record) {
+        if record.level() <= log::Level::Warn {
+            CAPTURED.with(|captured| {
+                if let Some(captured) = &mut *captured.borrow_mut() {
+                    captured.push(record.args().to_string());
+                }
+            });
+        }
+        if self.inner.enabled(record.metadata()) {
+            self.inner.log(record);
+        }
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+    }
+}
+
+/// Call `f` and return the warnings (and errors) it logged.
+pub fn capture_logs<F: FnOnce()>(f: F) -> Vec<String> {
+    init();
+    CAPTURED.with(|captured| *captured.borrow_mut() = Some(Vec::new()));
+    f();
+    CAPTURED.with(|captured| captured.borrow_mut().take().unwrap())
+}
+
 pub fn init_with_poll() -> (Poll, Events) {
     init();
 
@@ -239,63 +285,8 @@ pub fn any_local_ipv6_address() -> SocketAddr {
     "[::1]:0".parse().unwrap()
 }
 
-#[cfg(unix)]
 pub fn set_linger_zero(socket: &TcpStream) {
-    let val = libc::linger {
-        l_onoff: 1,
-        l_linger: 0,
-    };
-    let res = unsafe {
-        libc::setsockopt(
-            socket.as_raw_fd(),
-            libc::SOL_SOCKET,
-            #[cfg(any(
-                target_os = "ios",
-                target_os = "macos",
-                target_os = "tvos",
-                target_os = "watchos",
-            ))]
-            libc::SO_LINGER_SEC,
-            #[cfg(not(any(
-                target_os = "ios",
-                target_os = "macos",
-                target_os = "tvos",
-                target_os = "watchos",
-            )))]
-            libc::SO_LINGER,
-            &val as *const libc::linger as *const libc::c_void,
-            size_of::<libc::linger>() as libc::socklen_t,
-        )
-    };
-    assert_eq!(res, 0);
-}
-
-#[cfg(windows)]
-pub fn set_linger_zero(socket: &TcpStream) {
-    use std::os::windows::io::AsRawSocket;
-    use windows_sys::Win32::Networking::WinSock::{
-        setsockopt, LINGER, SOCKET_ERROR, SOL_SOCKET, SO_LINGER,
-    };
-
-    let mut val = LINGER {
-        l_onoff: 1,
-        l_linger: 0,
-    };
-
-    let res = unsafe {
-        setsockopt(
-            socket.as_raw_socket() as _,
-            SOL_SOCKET as i32,
-            SO_LINGER as i32,
-            &mut val as *mut _ as *mut _,
-            size_of::<LINGER>() as _,
-        )
-    };
-    assert!(
-        res != SOCKET_ERROR,
-        "error setting linger: {}",
-        io::Error::last_os_error()
-    );
+    socket.set_linger(Some(Duration::from_secs(0))).unwrap();
 }
 
 /// Returns a path to a temporary file using `name` as filename.
@@ -107,6 +107,85 @@ fn waker_multiple_wakeups_different_thread() {
     handle2.join().unwrap();
 }
 
+#[test]
+fn task_waker() {
+    init();
+
+    let mut poll = Poll::new().expect("unable to create new Poll instance");
+    let mut events = Events::with_capacity(10);
+
+    let token = Token(10);
+    let waker = Waker::new(poll.registry(), token).expect("unable to create waker");
+    let waker = Arc::new(waker);
+
+    let task_waker = Arc::clone(&waker).into_task_waker();
+    let task_waker2 = task_waker.clone();
+    task_waker.wake_by_ref();
+    expect_waker_event(&mut poll, &mut events, token);
+
+    let handle = thread::spawn(move || task_waker2.wake());
+    expect_waker_event(&mut poll, &mut events, token);
+    handle.join().unwrap();
+
+    drop(task_waker);
+    assert_eq!(Arc::strong_count(&waker), 1);
+}
+
+#[test]
+#[cfg(feature = "util")]
+fn block_on() {
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::sync::Mutex;
+    use std::task::{self, Context};
+
+    /// Future that completes once `value` is set by another thread.
+    struct Shared {
+        value: Mutex<(Option<u32>, Option<task::Waker>)>,
+    }
+
+    struct Wait(Arc<Shared>);
+
+    impl Future for Wait {
+        type Output = u32;
+
+        fn poll(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> task::Poll<u32> {
+            let mut value = self.0.value.lock().unwrap();
+            match value.0 {
+                Some(value) => task::Poll::Ready(value),
+                None => {
+                    value.1 = Some(ctx.waker().clone());
+                    task::Poll::Pending
+                }
+            }
+        }
+    }
+
+    init();
+
+    let mut poll = Poll::new().expect("unable to create new Poll instance");
+    let waker = Waker::new(poll.registry(), Token(10)).expect("unable to create waker");
+    let waker = Arc::new(waker);
+
+    let shared = Arc::new(Shared {
+        value: Mutex::new((None, None)),
+    });
+    let shared2 = shared.clone();
+    let handle = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(10));
+        let mut value = shared2.value.lock().unwrap();
+        value.0 = Some(123);
+        if let Some(waker) = value.1.take() {
+            waker.wake();
+        }
+    });
+
+    let value = mio::util::block_on(&mut poll, &waker, Wait(shared)).unwrap();
+    assert_eq!(value, 123);
+    handle.join().unwrap();
+    assert_eq!(Arc::strong_count(&waker), 1);
+}
+
 #[test]
 #[cfg_attr(
     not(debug_assertions),